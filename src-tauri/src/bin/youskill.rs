@@ -0,0 +1,1072 @@
+//! Headless `youskill` command line interface.
+//!
+//! Drives the same services as the desktop app so skills can be managed on
+//! dev boxes and CI runners that have no display.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::ExitCode;
+use you_skill::config::load_config;
+use you_skill::models::{
+  DetectedSkill, ExportSkillPackRequest, InstallGithubRequest, InstallMethod, InstallNativeRequest,
+  InstallScope, ProjectLockSyncStatus, SkillDriftStatus, SyncProjectLockRequest,
+};
+use you_skill::services::backup_service::RestoreBackupRequest;
+use you_skill::services::{
+  agent_apps_service, agent_registry_service, backup_service, doctor_service,
+  project_drift_service, skill_lint_service, skill_pack_service, skill_service,
+  user_projects_service, workspace_service,
+};
+
+#[derive(Parser)]
+#[command(
+  name = "youskill",
+  version,
+  about = "Manage agent skills from the command line"
+)]
+struct Cli {
+  /// Print JSON instead of human-readable tables
+  #[arg(long, global = true)]
+  json: bool,
+
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// List installed skills
+  List(ScopeArgs),
+  /// Detect skills in a git repository, ZIP file or local folder
+  Detect {
+    /// Repository URL (GitHub, GitLab, Gitea/Forgejo, Bitbucket) or GitHub owner/repo, path to a ZIP file, or path to a folder
+    source: String,
+    /// Branch, tag or commit to read a repository from
+    #[arg(long = "ref", value_name = "REF")]
+    git_ref: Option<String>,
+  },
+  /// Install skills from a git repository, ZIP file or local folder
+  Install {
+    /// Repository URL (GitHub, GitLab, Gitea/Forgejo, Bitbucket) or GitHub owner/repo, path to a ZIP file, or path to a folder
+    source: String,
+    /// Branch, tag or commit to read a repository from
+    #[arg(long = "ref", value_name = "REF")]
+    git_ref: Option<String>,
+    /// Only install the named skills (defaults to every detected skill)
+    #[arg(long = "skill", value_name = "NAME", value_delimiter = ',')]
+    skills: Vec<String>,
+    /// Agent app ids to link the skill into (see `youskill agents`)
+    #[arg(
+      long = "agent",
+      value_name = "ID",
+      value_delimiter = ',',
+      required = true
+    )]
+    agents: Vec<String>,
+    /// How the skill is linked into each agent folder
+    #[arg(long, value_enum, default_value_t = MethodArg::Symlink)]
+    method: MethodArg,
+    /// For skill packs, also restore each skill's recorded source and agent links
+    #[arg(long)]
+    restore_origin: bool,
+    #[command(flatten)]
+    scope: ScopeArgs,
+  },
+  /// Export installed skills with their source and agent links into a skill pack ZIP
+  Export {
+    /// Skills to export (defaults to every skill in the scope)
+    #[arg(long = "skill", value_name = "NAME", value_delimiter = ',')]
+    skills: Vec<String>,
+    /// ZIP file to write
+    #[arg(short, long, value_name = "FILE")]
+    output: String,
+    #[command(flatten)]
+    scope: ScopeArgs,
+  },
+  /// Delete a skill and every agent link to it
+  Delete {
+    name: String,
+    #[command(flatten)]
+    scope: ScopeArgs,
+  },
+  /// Update skills installed from a git repository from their recorded source
+  Update {
+    /// Skill to update (omit together with --all to update every repository skill)
+    name: Option<String>,
+    /// Update every repository-sourced skill in the scope
+    #[arg(long, conflicts_with = "name")]
+    all: bool,
+    #[command(flatten)]
+    scope: ScopeArgs,
+  },
+  /// Install the skills listed in a project's skills-lock.json
+  Sync {
+    /// Project root containing skills-lock.json
+    #[arg(default_value = ".")]
+    project: String,
+    /// Agent app ids to link into (defaults to agents whose skills folder already exists in the project)
+    #[arg(long = "agent", value_name = "ID", value_delimiter = ',')]
+    agents: Vec<String>,
+    /// How the skill is linked into each agent folder
+    #[arg(long, value_enum, default_value_t = MethodArg::Symlink)]
+    method: MethodArg,
+  },
+  /// Compare a project's skills with its skills-lock.json
+  Drift {
+    /// Project root containing skills-lock.json
+    #[arg(default_value = ".")]
+    project: String,
+    /// Accept the local state of this skill into skills-lock.json
+    #[arg(long, value_name = "NAME", conflicts_with = "restore")]
+    relock: Option<String>,
+    /// Restore this skill to the locked content from its repository
+    #[arg(long, value_name = "NAME")]
+    restore: Option<String>,
+  },
+  /// Check skills against the SKILL.md format and report problems
+  Lint {
+    /// Skill folder or SKILL.md to lint
+    path: Option<String>,
+    /// Lint every installed skill, globally and in each registered project
+    #[arg(long, conflicts_with = "path")]
+    all: bool,
+  },
+  /// Find broken links, orphaned skills and lock file inconsistencies
+  Doctor {
+    /// Apply the suggested fix for every finding
+    #[arg(long)]
+    fix: bool,
+  },
+  /// Back up the global skills folder, as a ZIP or into the deduplicating store (see settings)
+  Backup {
+    /// Folder to write to (defaults to the configured backup folder)
+    #[arg(long, value_name = "PATH")]
+    folder: Option<String>,
+  },
+  /// Restore skills from a backup ZIP or deduplicated snapshot
+  Restore {
+    /// Backup file (a `.zip`, or a `.json` snapshot in the backup store)
+    backup: String,
+    /// Only restore the named global skills (defaults to every skill in the backup)
+    #[arg(long = "skill", value_name = "NAME", value_delimiter = ',')]
+    skills: Vec<String>,
+    /// Also delete global skills that are not in the backup
+    #[arg(long)]
+    remove_missing: bool,
+    /// Also restore lock files, custom agent apps, registered projects and settings
+    #[arg(long)]
+    state: bool,
+    /// Also restore the skills of backed up projects whose folder exists here
+    #[arg(long)]
+    projects: bool,
+    /// Restore the backed up project at OLD into NEW on this machine
+    #[arg(long = "map-project", value_name = "OLD=NEW")]
+    map_project: Vec<String>,
+    /// Only show what the backup contains and what a restore would change
+    #[arg(long)]
+    preview: bool,
+  },
+  /// Delete backups that the configured retention rules no longer keep
+  Prune {
+    /// Backup folder (defaults to the configured backup folder)
+    #[arg(long, value_name = "PATH")]
+    folder: Option<String>,
+    /// Only list the backups that would be deleted
+    #[arg(long)]
+    dry_run: bool,
+  },
+  /// Remove leftover detect sessions and temp folders
+  Gc,
+  /// List agent apps detected on this machine
+  Agents {
+    /// Fetch the built-in agent list from the registry source first
+    #[arg(long)]
+    refresh_registry: bool,
+    /// Registry URL or file to refresh from instead of the configured source
+    #[arg(long, value_name = "URL", requires = "refresh_registry")]
+    registry_url: Option<String>,
+    /// Write custom agent apps to a shareable registry file
+    #[arg(long, value_name = "FILE", conflicts_with = "import")]
+    export: Option<String>,
+    /// Custom agent app ids to export (defaults to all)
+    #[arg(
+      long = "id",
+      value_name = "ID",
+      value_delimiter = ',',
+      requires = "export"
+    )]
+    ids: Vec<String>,
+    /// Add custom agent apps from a shared registry file or URL
+    #[arg(long, value_name = "FILE_OR_URL")]
+    import: Option<String>,
+  },
+  /// Manage registered projects
+  Projects {
+    #[command(subcommand)]
+    command: Option<ProjectsCommand>,
+  },
+}
+
+#[derive(Subcommand)]
+enum ProjectsCommand {
+  /// List registered projects
+  List,
+  /// Register a project
+  Add { name: String, path: String },
+  /// Unregister a project
+  Remove { name: String },
+}
+
+#[derive(Args)]
+struct ScopeArgs {
+  /// Use the project at PATH instead of the global scope
+  #[arg(long, value_name = "PATH")]
+  project: Option<String>,
+}
+
+impl ScopeArgs {
+  fn scope(&self) -> InstallScope {
+    if self.project.is_some() {
+      InstallScope::Project
+    } else {
+      InstallScope::Global
+    }
+  }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MethodArg {
+  Symlink,
+  Copy,
+}
+
+impl From<MethodArg> for InstallMethod {
+  fn from(value: MethodArg) -> Self {
+    match value {
+      MethodArg::Symlink => InstallMethod::Symlink,
+      MethodArg::Copy => InstallMethod::Copy,
+    }
+  }
+}
+
+enum SkillSource {
+  Remote(String),
+  Zip(String),
+  Folder(String),
+}
+
+impl SkillSource {
+  fn parse(source: &str) -> Self {
+    let path = Path::new(source);
+    if path.is_dir() {
+      SkillSource::Folder(source.to_string())
+    } else if path.is_file() {
+      SkillSource::Zip(source.to_string())
+    } else {
+      SkillSource::Remote(source.to_string())
+    }
+  }
+}
+
+#[derive(Serialize)]
+struct InstallReport {
+  name: String,
+  success: bool,
+  message: String,
+}
+
+fn main() -> ExitCode {
+  tracing_subscriber::fmt()
+    .with_writer(std::io::stderr)
+    .with_env_filter(
+      tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn")),
+    )
+    .init();
+
+  let cli = Cli::parse();
+  match run(cli) {
+    Ok(code) => code,
+    Err(err) => {
+      eprintln!("error: {}", err);
+      ExitCode::FAILURE
+    },
+  }
+}
+
+fn run(cli: Cli) -> Result<ExitCode, String> {
+  let json = cli.json;
+  match cli.command {
+    Command::List(scope) => {
+      let skills = skill_service::list_skills(scope.scope(), scope.project.clone())?;
+      if json {
+        return print_json(&skills);
+      }
+      let rows = skills
+        .iter()
+        .map(|skill| {
+          vec![
+            skill.name.clone(),
+            format!("{:?}", skill.source_type).to_lowercase(),
+            skill.source.clone().unwrap_or_else(|| "-".to_string()),
+            skill
+              .installed_agent_apps
+              .iter()
+              .map(|app| app.id.as_str())
+              .collect::<Vec<_>>()
+              .join(", "),
+          ]
+        })
+        .collect();
+      print_table(&["NAME", "SOURCE TYPE", "SOURCE", "AGENTS"], rows);
+    },
+    Command::Detect { source, git_ref } => {
+      let detected = detect(&SkillSource::parse(&source), git_ref)?;
+      release_sessions(&detected);
+      if json {
+        return print_json(&detected);
+      }
+      let rows = detected
+        .iter()
+        .map(|skill| {
+          let origin = match &skill.pack_origin {
+            Some(origin) => origin.source.clone().unwrap_or_else(|| "local".to_string()),
+            None => "-".to_string(),
+          };
+          vec![skill.name.clone(), skill.skill_path.clone(), origin]
+        })
+        .collect();
+      print_table(&["NAME", "SKILL PATH", "PACK ORIGIN"], rows);
+    },
+    Command::Install {
+      source,
+      git_ref,
+      skills,
+      agents,
+      method,
+      restore_origin,
+      scope,
+    } => {
+      let source = SkillSource::parse(&source);
+      let detected = detect(&source, git_ref)?;
+      let selected = select_skills(detected.clone(), &skills);
+      let reports: Result<Vec<InstallReport>, String> = selected.map(|selected| {
+        selected
+          .into_iter()
+          .map(|skill| {
+            let result = install(
+              &source,
+              &skill,
+              &agents,
+              method.into(),
+              restore_origin,
+              &scope,
+            );
+            InstallReport {
+              name: skill.name,
+              success: result.is_ok(),
+              message: result.unwrap_or_else(|err| err),
+            }
+          })
+          .collect()
+      });
+      release_sessions(&detected);
+      let reports = reports?;
+      let failed = reports.iter().any(|report| !report.success);
+      if json {
+        print_json(&reports)?;
+      } else {
+        let rows = reports
+          .iter()
+          .map(|report| {
+            vec![
+              report.name.clone(),
+              if report.success { "ok" } else { "failed" }.to_string(),
+              report.message.clone(),
+            ]
+          })
+          .collect();
+        print_table(&["NAME", "STATUS", "MESSAGE"], rows);
+      }
+      if failed {
+        return Ok(ExitCode::FAILURE);
+      }
+    },
+    Command::Export {
+      skills,
+      output,
+      scope,
+    } => {
+      let names = if skills.is_empty() {
+        skill_service::list_skills(scope.scope(), scope.project.clone())?
+          .into_iter()
+          .map(|skill| skill.name)
+          .collect()
+      } else {
+        skills
+      };
+      let result = skill_pack_service::export_skill_pack(ExportSkillPackRequest {
+        names,
+        scope: scope.scope(),
+        project_path: scope.project.clone(),
+        output_path: output,
+      })?;
+      if json {
+        return print_json(&result);
+      }
+      println!(
+        "Exported {} skill(s) to {}",
+        result.skills.len(),
+        result.path
+      );
+    },
+    Command::Delete { name, scope } => {
+      skill_service::delete_skill(name.clone(), scope.scope(), scope.project.clone())?;
+      if json {
+        return print_json(&serde_json::json!({ "deleted": name }));
+      }
+      println!("Deleted skill '{}'", name);
+    },
+    Command::Update { name, all, scope } => {
+      let results = match name {
+        Some(name) => vec![block_on(skill_service::update_skill(
+          name,
+          scope.scope(),
+          scope.project.clone(),
+        ))?],
+        None if all => block_on(skill_service::update_all_skills(
+          scope.scope(),
+          scope.project.clone(),
+        ))?,
+        None => return Err("Specify a skill name or --all".to_string()),
+      };
+      let failed = results.iter().any(|result| !result.success);
+      if json {
+        print_json(&results)?;
+      } else {
+        let rows = results
+          .iter()
+          .map(|result| {
+            let status = if !result.success {
+              "failed"
+            } else if result.updated {
+              "updated"
+            } else {
+              "up to date"
+            };
+            vec![
+              result.name.clone(),
+              status.to_string(),
+              result.message.clone(),
+            ]
+          })
+          .collect();
+        print_table(&["NAME", "STATUS", "MESSAGE"], rows);
+      }
+      if failed {
+        return Ok(ExitCode::FAILURE);
+      }
+    },
+    Command::Sync {
+      project,
+      agents,
+      method,
+    } => {
+      let project_path = std::fs::canonicalize(&project)
+        .map_err(|e| format!("Invalid project path {}: {}", project, e))?;
+      let results = block_on(skill_service::sync_project_from_lock(
+        SyncProjectLockRequest {
+          project_path: project_path.to_string_lossy().to_string(),
+          agent_apps: agents,
+          method: method.into(),
+        },
+      ))?;
+      let failed = results.iter().any(|result| {
+        !matches!(
+          result.status,
+          ProjectLockSyncStatus::Installed | ProjectLockSyncStatus::UpToDate
+        )
+      });
+      if json {
+        print_json(&results)?;
+      } else {
+        let rows = results
+          .iter()
+          .map(|result| {
+            let status = match result.status {
+              ProjectLockSyncStatus::Installed => "installed",
+              ProjectLockSyncStatus::UpToDate => "up to date",
+              ProjectLockSyncStatus::HashMismatch => "hash mismatch",
+              ProjectLockSyncStatus::Unsatisfiable => "unsatisfiable",
+              ProjectLockSyncStatus::Failed => "failed",
+            };
+            vec![
+              result.name.clone(),
+              status.to_string(),
+              result.message.clone(),
+            ]
+          })
+          .collect();
+        print_table(&["NAME", "STATUS", "MESSAGE"], rows);
+      }
+      if failed {
+        return Ok(ExitCode::FAILURE);
+      }
+    },
+    Command::Drift {
+      project,
+      relock,
+      restore,
+    } => {
+      let project_path = std::fs::canonicalize(&project)
+        .map_err(|e| format!("Invalid project path {}: {}", project, e))?
+        .to_string_lossy()
+        .to_string();
+      if let Some(name) = relock {
+        project_drift_service::relock_project_skill(project_path, name.clone())?;
+        if json {
+          return print_json(&serde_json::json!({ "relocked": name }));
+        }
+        println!("Updated skills-lock.json for '{}'", name);
+        return Ok(ExitCode::SUCCESS);
+      }
+      if let Some(name) = restore {
+        let result = block_on(skill_service::restore_project_skill(project_path, name))?;
+        let restored = result.status == ProjectLockSyncStatus::Installed;
+        if json {
+          print_json(&result)?;
+        } else {
+          println!("{}", result.message);
+        }
+        return Ok(if restored {
+          ExitCode::SUCCESS
+        } else {
+          ExitCode::FAILURE
+        });
+      }
+
+      let items = project_drift_service::check_project_drift(project_path)?;
+      let drifted = items
+        .iter()
+        .any(|item| item.status != SkillDriftStatus::Clean);
+      if json {
+        print_json(&items)?;
+      } else {
+        let rows = items
+          .iter()
+          .map(|item| {
+            let status = match item.status {
+              SkillDriftStatus::Clean => "clean",
+              SkillDriftStatus::Modified => "modified",
+              SkillDriftStatus::Missing => "missing",
+              SkillDriftStatus::Untracked => "untracked",
+            };
+            let files = item
+              .changes
+              .iter()
+              .map(|change| {
+                format!(
+                  "{} {}",
+                  format!("{:?}", change.change).to_lowercase(),
+                  change.path
+                )
+              })
+              .collect::<Vec<_>>()
+              .join(", ");
+            vec![item.name.clone(), status.to_string(), files]
+          })
+          .collect();
+        print_table(&["NAME", "STATUS", "CHANGED FILES"], rows);
+      }
+      if drifted {
+        return Ok(ExitCode::FAILURE);
+      }
+    },
+    Command::Lint { path, all } => {
+      let reports = match path {
+        Some(path) => vec![skill_lint_service::lint_skill(path)?],
+        None if all => skill_lint_service::lint_installed_skills()?,
+        None => return Err("Specify a skill path or --all".to_string()),
+      };
+      let failed = reports.iter().any(|report| report.has_errors());
+      if json {
+        print_json(&reports)?;
+      } else {
+        let rows = reports
+          .iter()
+          .flat_map(|report| {
+            let skill = report.name.clone().unwrap_or_else(|| report.path.clone());
+            report.diagnostics.iter().map(move |diagnostic| {
+              let location = match (&diagnostic.file, diagnostic.line) {
+                (Some(file), Some(line)) => format!("{}:{}", file, line),
+                (Some(file), None) => file.clone(),
+                (None, _) => "-".to_string(),
+              };
+              vec![
+                skill.clone(),
+                format!("{:?}", diagnostic.severity).to_lowercase(),
+                location,
+                diagnostic.message.clone(),
+              ]
+            })
+          })
+          .collect::<Vec<_>>();
+        if rows.is_empty() {
+          println!("No problems found in {} skill(s)", reports.len());
+        } else {
+          print_table(&["SKILL", "SEVERITY", "LOCATION", "MESSAGE"], rows);
+        }
+      }
+      if failed {
+        return Ok(ExitCode::FAILURE);
+      }
+    },
+    Command::Doctor { fix } => {
+      let findings = doctor_service::run_doctor()?;
+      if fix {
+        let results = doctor_service::apply_doctor_fixes(findings);
+        let failed = results.iter().any(|result| !result.success);
+        if json {
+          print_json(&results)?;
+        } else if results.is_empty() {
+          println!("No problems found");
+        } else {
+          let rows = results
+            .iter()
+            .map(|result| {
+              let status = if result.success { "fixed" } else { "failed" };
+              vec![status.to_string(), result.message.clone()]
+            })
+            .collect();
+          print_table(&["STATUS", "MESSAGE"], rows);
+        }
+        if failed {
+          return Ok(ExitCode::FAILURE);
+        }
+      } else {
+        if json {
+          print_json(&findings)?;
+        } else if findings.is_empty() {
+          println!("No problems found");
+        } else {
+          let rows = findings
+            .iter()
+            .map(|finding| {
+              vec![
+                serde_label(&finding.kind, None),
+                finding
+                  .project_path
+                  .clone()
+                  .unwrap_or_else(|| "global".to_string()),
+                finding
+                  .skill_name
+                  .clone()
+                  .unwrap_or_else(|| "-".to_string()),
+                finding.message.clone(),
+                serde_label(&finding.fix, Some("action")),
+              ]
+            })
+            .collect();
+          print_table(&["KIND", "SCOPE", "SKILL", "MESSAGE", "FIX"], rows);
+        }
+        if !findings.is_empty() {
+          return Ok(ExitCode::FAILURE);
+        }
+      }
+    },
+    Command::Backup { folder } => {
+      let folder = folder
+        .or(load_config()?.backup_folder)
+        .ok_or("No backup folder configured; pass --folder")?;
+      let result = block_on(backup_service::backup_skills(folder))?;
+      if json {
+        print_json(&result)?;
+      } else if let Some(path) = &result.backup_path {
+        println!("Backed up to {}", path);
+      } else {
+        println!("{}", result.message);
+      }
+      if !result.success {
+        return Ok(ExitCode::FAILURE);
+      }
+    },
+    Command::Restore {
+      backup,
+      skills,
+      remove_missing,
+      state,
+      projects,
+      map_project,
+      preview,
+    } => {
+      if preview {
+        let preview = backup_service::preview_backup_restore(backup)?;
+        if json {
+          return print_json(&preview);
+        }
+        let mut rows = Vec::new();
+        for (label, names) in [
+          ("add", &preview.added),
+          ("overwrite", &preview.overwritten),
+          ("remove", &preview.removed),
+          ("unchanged", &preview.unchanged),
+          ("agent only", &preview.agent_skills),
+          ("project", &preview.projects),
+          ("missing project", &preview.missing_projects),
+          ("state", &preview.state_files),
+        ] {
+          rows.extend(
+            names
+              .iter()
+              .map(|name| vec![label.to_string(), name.clone()]),
+          );
+        }
+        print_table(&["CHANGE", "NAME"], rows);
+        return Ok(ExitCode::SUCCESS);
+      }
+      let project_roots = map_project
+        .iter()
+        .map(|mapping| {
+          mapping
+            .split_once('=')
+            .map(|(old, new)| (old.to_string(), new.to_string()))
+            .ok_or_else(|| format!("Invalid --map-project {}, expected OLD=NEW", mapping))
+        })
+        .collect::<Result<_, String>>()?;
+      let result = backup_service::restore_backup(RestoreBackupRequest {
+        backup_path: backup,
+        skills,
+        remove_missing,
+        include_state: state,
+        include_projects: projects,
+        project_roots,
+      })?;
+      if json {
+        print_json(&result)?;
+      } else {
+        println!(
+          "Restored {} skill(s), {} project(s), {} state file(s); removed {} skill(s)",
+          result.restored.len(),
+          result.projects.len(),
+          result.state_files.len(),
+          result.removed.len()
+        );
+        for error in &result.errors {
+          eprintln!("error: {}", error);
+        }
+      }
+      if !result.errors.is_empty() {
+        return Ok(ExitCode::FAILURE);
+      }
+    },
+    Command::Prune { folder, dry_run } => {
+      let result = backup_service::prune_backups(folder, dry_run)?;
+      if json {
+        return print_json(&result);
+      }
+      for backup in &result.removed {
+        println!(
+          "{} {}",
+          if dry_run { "would remove" } else { "removed" },
+          backup.path
+        );
+      }
+      if dry_run {
+        println!(
+          "{} backup(s) would be removed, {} kept",
+          result.removed.len(),
+          result.kept
+        );
+      } else {
+        println!(
+          "{} backup(s) removed, {} kept, {:.1} MB freed",
+          result.removed.len(),
+          result.kept,
+          result.freed_bytes as f64 / (1024.0 * 1024.0)
+        );
+      }
+    },
+    Command::Gc => {
+      let result = workspace_service::sweep_workspace()?;
+      if json {
+        return print_json(&result);
+      }
+      println!(
+        "Removed {} folder(s), freed {:.1} MB, {:.1} MB still staged",
+        result.removed_sessions,
+        result.freed_bytes as f64 / (1024.0 * 1024.0),
+        result.remaining_bytes as f64 / (1024.0 * 1024.0)
+      );
+    },
+    Command::Agents {
+      refresh_registry,
+      registry_url,
+      export,
+      ids,
+      import,
+    } => {
+      if let Some(path) = export {
+        let count = agent_apps_service::export_user_agent_apps(ids, path.clone())?;
+        if json {
+          return print_json(&serde_json::json!({ "exported": count, "path": path }));
+        }
+        println!("exported {} custom agent apps to {}", count, path);
+        return Ok(ExitCode::SUCCESS);
+      }
+      if let Some(source) = import {
+        let results = block_on(agent_apps_service::import_user_agent_apps(source))?;
+        if json {
+          return print_json(&results);
+        }
+        let rows = results
+          .iter()
+          .map(|result| {
+            vec![
+              result.id.clone(),
+              result.display_name.clone(),
+              serde_label(&result.status, None),
+              result.message.clone().unwrap_or_default(),
+            ]
+          })
+          .collect();
+        print_table(&["ID", "NAME", "STATUS", "MESSAGE"], rows);
+        return Ok(ExitCode::SUCCESS);
+      }
+      if refresh_registry {
+        let status = block_on(agent_registry_service::refresh_agent_registry(registry_url))?;
+        if !json {
+          println!(
+            "agent registry revision {} ({}, bundled {}), {} agents",
+            status.revision,
+            serde_label(&status.source, None),
+            status.bundled_revision,
+            status.agent_count
+          );
+        }
+      }
+      let apps = agent_apps_service::local_agent_apps();
+      if json {
+        return print_json(&apps);
+      }
+      let rows = apps
+        .iter()
+        .map(|app| {
+          vec![
+            app.id.clone(),
+            app.display_name.clone(),
+            app
+              .global_root
+              .clone()
+              .or_else(|| app.global_path.clone())
+              .unwrap_or_else(|| "-".to_string()),
+            app.project_path.clone().unwrap_or_else(|| "-".to_string()),
+            app
+              .detected_version
+              .clone()
+              .unwrap_or_else(|| "-".to_string()),
+            app
+              .detected_by
+              .as_ref()
+              .map(|found| format!("{} {}", serde_label(&found.kind, None), found.path))
+              .unwrap_or_else(|| "-".to_string()),
+          ]
+        })
+        .collect();
+      print_table(
+        &[
+          "ID",
+          "NAME",
+          "GLOBAL PATH",
+          "PROJECT PATH",
+          "VERSION",
+          "DETECTED BY",
+        ],
+        rows,
+      );
+    },
+    Command::Projects { command } => match command.unwrap_or(ProjectsCommand::List) {
+      ProjectsCommand::List => {
+        let projects = user_projects_service::list_user_projects()?;
+        if json {
+          return print_json(&projects);
+        }
+        let rows = projects
+          .iter()
+          .map(|project| vec![project.name.clone(), project.path.clone()])
+          .collect();
+        print_table(&["NAME", "PATH"], rows);
+      },
+      ProjectsCommand::Add { name, path } => {
+        let project = user_projects_service::add_user_project(name, path)?;
+        if json {
+          return print_json(&project);
+        }
+        println!("Added project '{}' ({})", project.name, project.path);
+      },
+      ProjectsCommand::Remove { name } => {
+        user_projects_service::remove_user_project(name.clone())?;
+        if json {
+          return print_json(&serde_json::json!({ "removed": name }));
+        }
+        println!("Removed project '{}'", name);
+      },
+    },
+  }
+  Ok(ExitCode::SUCCESS)
+}
+
+fn detect(source: &SkillSource, git_ref: Option<String>) -> Result<Vec<DetectedSkill>, String> {
+  match source {
+    SkillSource::Remote(url) => block_on(skill_service::detect_github_manual(url.clone(), git_ref)),
+    SkillSource::Zip(path) => skill_service::detect_zip(path.clone()),
+    SkillSource::Folder(path) => skill_service::detect_folder(path.clone()),
+  }
+}
+
+/// Detected skills are staged in workspace sessions; the CLI never keeps them.
+fn release_sessions(detected: &[DetectedSkill]) {
+  let session_ids: HashSet<&str> = detected
+    .iter()
+    .map(|skill| skill.session_id.as_str())
+    .collect();
+  for session_id in session_ids {
+    if let Err(err) = workspace_service::release_session(session_id) {
+      eprintln!("{}", err);
+    }
+  }
+}
+
+fn select_skills(
+  detected: Vec<DetectedSkill>,
+  names: &[String],
+) -> Result<Vec<DetectedSkill>, String> {
+  if names.is_empty() {
+    return Ok(detected);
+  }
+  let missing: Vec<&str> = names
+    .iter()
+    .filter(|name| !detected.iter().any(|skill| &skill.name == *name))
+    .map(|name| name.as_str())
+    .collect();
+  if !missing.is_empty() {
+    return Err(format!(
+      "Skills not found in source: {}",
+      missing.join(", ")
+    ));
+  }
+  Ok(
+    detected
+      .into_iter()
+      .filter(|skill| names.contains(&skill.name))
+      .collect(),
+  )
+}
+
+fn install(
+  source: &SkillSource,
+  skill: &DetectedSkill,
+  agents: &[String],
+  method: InstallMethod,
+  restore_origin: bool,
+  scope: &ScopeArgs,
+) -> Result<String, String> {
+  let result = match source {
+    SkillSource::Remote(url) => {
+      block_on(skill_service::install_from_github(InstallGithubRequest {
+        name: skill.name.clone(),
+        session_id: skill.session_id.clone(),
+        skill_path: skill.skill_path.clone(),
+        source_url: url.clone(),
+        skill_folder_hash: None,
+        source_ref: skill.source_ref.clone(),
+        agent_apps: agents.to_vec(),
+        method,
+        scope: scope.scope(),
+        project_path: scope.project.clone(),
+      }))?
+    },
+    SkillSource::Zip(_) | SkillSource::Folder(_) => {
+      skill_service::install_from_native(InstallNativeRequest {
+        name: skill.name.clone(),
+        session_id: skill.session_id.clone(),
+        skill_path: skill.skill_path.clone(),
+        agent_apps: agents.to_vec(),
+        method,
+        scope: scope.scope(),
+        project_path: scope.project.clone(),
+        restore_pack_origin: restore_origin,
+      })?
+    },
+  };
+  // A warning from the install, e.g. a skill pack entry edited since its export
+  Ok(if result.stderr.is_empty() {
+    result.stdout
+  } else {
+    format!("{}; {}", result.stdout, result.stderr)
+  })
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("failed to build tokio runtime")
+    .block_on(future)
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<ExitCode, String> {
+  let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+  println!("{}", content);
+  Ok(ExitCode::SUCCESS)
+}
+
+/// Serialized name of an enum, or of its tag field for internally tagged enums.
+fn serde_label<T: Serialize>(value: &T, tag: Option<&str>) -> String {
+  let value = serde_json::to_value(value).unwrap_or_default();
+  let label = match tag {
+    Some(tag) => value.get(tag),
+    None => Some(&value),
+  };
+  label
+    .and_then(|label| label.as_str())
+    .unwrap_or("-")
+    .replace('_', " ")
+}
+
+fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+  if rows.is_empty() {
+    println!("(none)");
+    return;
+  }
+
+  let mut widths: Vec<usize> = headers
+    .iter()
+    .map(|header| header.chars().count())
+    .collect();
+  for row in &rows {
+    for (index, cell) in row.iter().enumerate() {
+      widths[index] = widths[index].max(cell.chars().count());
+    }
+  }
+
+  let format_row = |cells: Vec<&str>| {
+    cells
+      .iter()
+      .enumerate()
+      .map(|(index, cell)| format!("{:width$}", cell, width = widths[index]))
+      .collect::<Vec<_>>()
+      .join("  ")
+      .trim_end()
+      .to_string()
+  };
+
+  println!("{}", format_row(headers.to_vec()));
+  for row in &rows {
+    println!(
+      "{}",
+      format_row(row.iter().map(|cell| cell.as_str()).collect())
+    );
+  }
+}
@@ -1,8 +1,9 @@
-use crate::models::AgentApp;
+use crate::models::{AgentApp, AgentAppImportResult, AgentRegistryStatus};
 use crate::services::agent_apps_service::{
-  create_user_agent_app, delete_user_agent_app_by_id, local_agent_apps, refresh_local_agent_apps,
-  update_user_agent_app_detail,
+  self, create_user_agent_app, delete_user_agent_app_by_id, local_agent_apps,
+  refresh_local_agent_apps, update_user_agent_app_detail,
 };
+use crate::services::agent_registry_service;
 
 /// List local agent apps (installed on system)
 #[tauri::command]
@@ -17,6 +18,18 @@ pub fn refresh_agent_apps() -> Result<Vec<AgentApp>, String> {
   Ok(local_agent_apps())
 }
 
+/// Which registry the built-in agent apps come from
+#[tauri::command]
+pub fn get_agent_registry_status() -> Result<AgentRegistryStatus, String> {
+  Ok(agent_registry_service::agent_registry_status())
+}
+
+/// Fetch the built-in agent apps from the registry source (or `url`)
+#[tauri::command]
+pub async fn refresh_agent_registry(url: Option<String>) -> Result<AgentRegistryStatus, String> {
+  agent_registry_service::refresh_agent_registry(url).await
+}
+
 /// Add a user agent app
 #[tauri::command]
 pub fn add_user_agent_app(
@@ -43,3 +56,15 @@ pub fn update_user_agent_app(
 ) -> Result<AgentApp, String> {
   update_user_agent_app_detail(id, display_name, global_path, project_path)
 }
+
+/// Export custom agent apps (all of them when `ids` is empty) to a file
+#[tauri::command]
+pub fn export_user_agent_apps(ids: Vec<String>, path: String) -> Result<usize, String> {
+  agent_apps_service::export_user_agent_apps(ids, path)
+}
+
+/// Import custom agent apps from an exported file or URL
+#[tauri::command]
+pub async fn import_user_agent_apps(source: String) -> Result<Vec<AgentAppImportResult>, String> {
+  agent_apps_service::import_user_agent_apps(source).await
+}
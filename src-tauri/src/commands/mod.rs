@@ -2,4 +2,5 @@ pub mod agent_apps;
 pub mod remote;
 pub mod settings;
 pub mod skill;
+pub mod tray;
 pub mod user_projects;
@@ -1,6 +1,13 @@
-use crate::config::{load_config, save_config};
+use crate::config::{
+  load_config, save_config, BackupMode, BackupRetention, BackupSchedule, SourceHostConfig,
+};
+use crate::models::{ScheduleState, SourceConnectionStatus};
 use crate::services::ai_service::{self, OpenRouterModelOption};
-use crate::services::backup_service::{self, BackupResult};
+use crate::services::backup_service::{
+  self, BackupInfo, BackupPruneResult, BackupRestorePreview, BackupRestoreResult, BackupResult,
+  RestoreBackupRequest,
+};
+use crate::services::{scheduler_service, source_host_service};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -18,6 +25,24 @@ pub struct SettingsPayload {
   pub translate_model: String,
   pub backup_folder: Option<String>,
   pub last_backup_time: Option<String>,
+  /// Left untouched by `update_settings` when omitted
+  #[serde(default)]
+  pub backup_mode: Option<BackupMode>,
+  /// Left untouched by `update_settings` when omitted
+  #[serde(default)]
+  pub backup_retention: Option<BackupRetention>,
+  /// Left untouched by `update_settings` when omitted
+  #[serde(default)]
+  pub backup_schedule: Option<BackupSchedule>,
+  /// Left untouched by `update_settings` when omitted
+  #[serde(default)]
+  pub update_check_hours: Option<u32>,
+  /// Left untouched by `update_settings` when omitted
+  #[serde(default)]
+  pub source_hosts: Option<Vec<SourceHostConfig>>,
+  /// Left untouched by `update_settings` when omitted
+  #[serde(default)]
+  pub workspace_max_mb: Option<u64>,
 }
 
 fn default_translate_target_language() -> String {
@@ -41,6 +66,12 @@ pub fn get_settings() -> Result<SettingsPayload, String> {
     translate_model: config.translate_model,
     backup_folder: config.backup_folder,
     last_backup_time: config.last_backup_time,
+    backup_mode: Some(config.backup_mode),
+    backup_retention: Some(config.backup_retention),
+    backup_schedule: Some(config.backup_schedule),
+    update_check_hours: Some(config.update_check_hours),
+    source_hosts: Some(config.source_hosts),
+    workspace_max_mb: Some(config.workspace_max_mb),
   })
 }
 
@@ -66,6 +97,27 @@ pub fn update_settings(settings: SettingsPayload) -> Result<SettingsPayload, Str
   } else {
     settings.translate_model.trim().to_string()
   };
+  if let Some(source_hosts) = settings.source_hosts {
+    config.source_hosts = source_hosts
+      .into_iter()
+      .filter(|host| !host.base_url.trim().is_empty())
+      .collect();
+  }
+  if let Some(backup_mode) = settings.backup_mode {
+    config.backup_mode = backup_mode;
+  }
+  if let Some(backup_retention) = settings.backup_retention {
+    config.backup_retention = backup_retention;
+  }
+  if let Some(backup_schedule) = settings.backup_schedule {
+    config.backup_schedule = backup_schedule;
+  }
+  if let Some(update_check_hours) = settings.update_check_hours {
+    config.update_check_hours = update_check_hours;
+  }
+  if let Some(workspace_max_mb) = settings.workspace_max_mb {
+    config.workspace_max_mb = workspace_max_mb;
+  }
   save_config(&config)?;
   Ok(SettingsPayload {
     language: config.language,
@@ -77,6 +129,12 @@ pub fn update_settings(settings: SettingsPayload) -> Result<SettingsPayload, Str
     translate_model: config.translate_model,
     backup_folder: config.backup_folder,
     last_backup_time: config.last_backup_time,
+    backup_mode: Some(config.backup_mode),
+    backup_retention: Some(config.backup_retention),
+    backup_schedule: Some(config.backup_schedule),
+    update_check_hours: Some(config.update_check_hours),
+    source_hosts: Some(config.source_hosts),
+    workspace_max_mb: Some(config.workspace_max_mb),
   })
 }
 
@@ -98,6 +156,42 @@ pub async fn backup_skills(backup_folder: String) -> Result<BackupResult, String
   backup_service::backup_skills(backup_folder).await
 }
 
+#[tauri::command]
+pub fn list_backups(backup_folder: Option<String>) -> Result<Vec<BackupInfo>, String> {
+  backup_service::list_backups(backup_folder)
+}
+
+#[tauri::command]
+pub async fn prune_backups(
+  backup_folder: Option<String>,
+  dry_run: bool,
+) -> Result<BackupPruneResult, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    backup_service::prune_backups(backup_folder, dry_run)
+  })
+  .await
+  .map_err(|e| format!("prune_backups join error: {}", e))?
+}
+
+#[tauri::command]
+pub fn get_schedule_state() -> Result<ScheduleState, String> {
+  scheduler_service::read_schedule_state()
+}
+
+#[tauri::command]
+pub async fn preview_backup_restore(backup_path: String) -> Result<BackupRestorePreview, String> {
+  tauri::async_runtime::spawn_blocking(move || backup_service::preview_backup_restore(backup_path))
+    .await
+    .map_err(|e| format!("preview_backup_restore join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn restore_backup(request: RestoreBackupRequest) -> Result<BackupRestoreResult, String> {
+  tauri::async_runtime::spawn_blocking(move || backup_service::restore_backup(request))
+    .await
+    .map_err(|e| format!("restore_backup join error: {}", e))?
+}
+
 #[tauri::command]
 pub async fn list_openrouter_models(
   search: Option<String>,
@@ -105,3 +199,10 @@ pub async fn list_openrouter_models(
   let config = load_config()?;
   ai_service::list_openrouter_models(config.openrouter_api_key.as_deref(), search).await
 }
+
+#[tauri::command]
+pub async fn test_source_connection(
+  host: SourceHostConfig,
+) -> Result<SourceConnectionStatus, String> {
+  source_host_service::test_source_connection(host).await
+}
@@ -1,9 +1,14 @@
 use crate::models::{
-  DetectedSkill, InstallGithubRequest, InstallNativeRequest, InstallResult, InstallScope,
-  InstallUnknownRequest, LocalSkill, ManageSkillAgentAppsRequest, SkillDirectoryEntry,
-  SkillUpdateCheckItem, SourceCheckResult,
+  DetectedSkill, DoctorFinding, DoctorFixResult, ExportSkillPackRequest, InstallGithubRequest,
+  InstallNativeRequest, InstallResult, InstallScope, InstallUnknownRequest, LocalSkill,
+  ManageSkillAgentAppsRequest, ProjectLockSyncResult, SkillDirectoryEntry, SkillDriftItem,
+  SkillLintReport, SkillPackExportResult, SkillUpdateCheckItem, SkillUpdateResult,
+  SourceCheckResult, SyncProjectLockRequest,
+};
+use crate::services::{
+  doctor_service, project_drift_service, skill_lint_service, skill_pack_service, skill_service,
+  translate_service, workspace_service,
 };
-use crate::services::{skill_service, translate_service};
 
 #[tauri::command]
 pub fn list_skills(
@@ -33,16 +38,27 @@ pub fn detect_folder(folder_path: String) -> Result<Vec<DetectedSkill>, String>
 }
 
 #[tauri::command]
-pub async fn detect_github_manual(github_path: String) -> Result<Vec<DetectedSkill>, String> {
-  skill_service::detect_github_manual(github_path).await
+pub async fn detect_github_manual(
+  github_path: String,
+  git_ref: Option<String>,
+) -> Result<Vec<DetectedSkill>, String> {
+  skill_service::detect_github_manual(github_path, git_ref).await
 }
 
 #[tauri::command]
 pub async fn detect_github_auto(
   github_path: String,
   skill_name: String,
+  git_ref: Option<String>,
 ) -> Result<DetectedSkill, String> {
-  skill_service::detect_github_auto(github_path, skill_name).await
+  skill_service::detect_github_auto(github_path, skill_name, git_ref).await
+}
+
+#[tauri::command]
+pub async fn release_detect_session(session_id: String) -> Result<(), String> {
+  tauri::async_runtime::spawn_blocking(move || workspace_service::release_session(&session_id))
+    .await
+    .map_err(|e| format!("release_detect_session join error: {}", e))?
 }
 
 #[tauri::command]
@@ -118,7 +134,90 @@ pub fn check_skills_updates(checks: Vec<SkillUpdateCheckItem>) -> Result<Vec<Str
   skill_service::check_skills_updates(checks)
 }
 
+#[tauri::command]
+pub async fn update_skill(
+  name: String,
+  scope: InstallScope,
+  project_path: Option<String>,
+) -> Result<SkillUpdateResult, String> {
+  skill_service::update_skill(name, scope, project_path).await
+}
+
+#[tauri::command]
+pub async fn update_all_skills(
+  scope: InstallScope,
+  project_path: Option<String>,
+) -> Result<Vec<SkillUpdateResult>, String> {
+  skill_service::update_all_skills(scope, project_path).await
+}
+
 #[tauri::command]
 pub async fn translate_skill_markdown(markdown: String) -> Result<String, String> {
   translate_service::translate_skill_markdown(markdown).await
 }
+
+#[tauri::command]
+pub async fn sync_project_from_lock(
+  request: SyncProjectLockRequest,
+) -> Result<Vec<ProjectLockSyncResult>, String> {
+  skill_service::sync_project_from_lock(request).await
+}
+
+#[tauri::command]
+pub async fn check_project_drift(project_path: String) -> Result<Vec<SkillDriftItem>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    project_drift_service::check_project_drift(project_path)
+  })
+  .await
+  .map_err(|e| format!("check_project_drift join error: {}", e))?
+}
+
+#[tauri::command]
+pub fn relock_project_skill(project_path: String, name: String) -> Result<(), String> {
+  project_drift_service::relock_project_skill(project_path, name)
+}
+
+#[tauri::command]
+pub async fn restore_project_skill(
+  project_path: String,
+  name: String,
+) -> Result<ProjectLockSyncResult, String> {
+  skill_service::restore_project_skill(project_path, name).await
+}
+
+#[tauri::command]
+pub fn lint_skill(path: String) -> Result<SkillLintReport, String> {
+  skill_lint_service::lint_skill(path)
+}
+
+#[tauri::command]
+pub async fn lint_installed_skills() -> Result<Vec<SkillLintReport>, String> {
+  tauri::async_runtime::spawn_blocking(skill_lint_service::lint_installed_skills)
+    .await
+    .map_err(|e| format!("lint_installed_skills join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn run_doctor() -> Result<Vec<DoctorFinding>, String> {
+  tauri::async_runtime::spawn_blocking(doctor_service::run_doctor)
+    .await
+    .map_err(|e| format!("run_doctor join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn apply_doctor_fixes(
+  findings: Vec<DoctorFinding>,
+) -> Result<Vec<DoctorFixResult>, String> {
+  tauri::async_runtime::spawn_blocking(move || doctor_service::apply_doctor_fixes(findings))
+    .await
+    .map_err(|e| format!("apply_doctor_fixes join error: {}", e))
+}
+
+#[tauri::command]
+pub async fn export_skill_pack(
+  request: ExportSkillPackRequest,
+) -> Result<SkillPackExportResult, String> {
+  tauri::async_runtime::spawn_blocking(move || skill_pack_service::export_skill_pack(request))
+    .await
+    .map_err(|e| format!("export_skill_pack join error: {}", e))?
+}
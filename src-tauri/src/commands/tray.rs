@@ -0,0 +1,9 @@
+use crate::models::RemoteSkill;
+use crate::tray;
+use tauri::AppHandle;
+
+/// Share the UI's update check result with the tray menu.
+#[tauri::command]
+pub fn set_tray_skill_updates(app: AppHandle, skills: Vec<RemoteSkill>) {
+  tray::set_tray_updates(&app, skills);
+}
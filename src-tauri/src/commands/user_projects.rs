@@ -1,5 +1,7 @@
 use crate::models::UserProject;
 use crate::services::user_projects_service;
+use crate::tray::refresh_tray;
+use tauri::AppHandle;
 
 #[tauri::command]
 pub fn list_user_projects() -> Result<Vec<UserProject>, String> {
@@ -7,20 +9,27 @@ pub fn list_user_projects() -> Result<Vec<UserProject>, String> {
 }
 
 #[tauri::command]
-pub fn add_user_project(name: String, path: String) -> Result<UserProject, String> {
-  user_projects_service::add_user_project(name, path)
+pub fn add_user_project(app: AppHandle, name: String, path: String) -> Result<UserProject, String> {
+  let project = user_projects_service::add_user_project(name, path)?;
+  refresh_tray(&app);
+  Ok(project)
 }
 
 #[tauri::command]
 pub fn update_user_project(
+  app: AppHandle,
   original_name: String,
   name: String,
   path: String,
 ) -> Result<UserProject, String> {
-  user_projects_service::update_user_project(original_name, name, path)
+  let project = user_projects_service::update_user_project(original_name, name, path)?;
+  refresh_tray(&app);
+  Ok(project)
 }
 
 #[tauri::command]
-pub fn remove_user_project(name: String) -> Result<(), String> {
-  user_projects_service::remove_user_project(name)
+pub fn remove_user_project(app: AppHandle, name: String) -> Result<(), String> {
+  user_projects_service::remove_user_project(name)?;
+  refresh_tray(&app);
+  Ok(())
 }
@@ -1,3 +1,4 @@
+use crate::models::ProviderKind;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -11,9 +12,74 @@ pub struct AppConfig {
   pub unknown_skill_install_permission: bool,
   pub backup_folder: Option<String>,
   pub last_backup_time: Option<String>,
+  pub backup_mode: BackupMode,
+  /// Applied after every backup and by an explicit prune; keeps everything when unset
+  pub backup_retention: BackupRetention,
+  /// Automatic backups into `backup_folder` while the app runs
+  pub backup_schedule: BackupSchedule,
+  /// Hours between background skill update checks; 0 turns them off
+  pub update_check_hours: u32,
   pub openrouter_api_key: Option<String>,
   pub translate_target_language: String,
   pub translate_model: String,
+  /// Self-hosted git servers, on top of github.com, gitlab.com, codeberg.org and bitbucket.org
+  pub source_hosts: Vec<SourceHostConfig>,
+  /// Size cap for staged detect sessions, enforced when the app starts
+  pub workspace_max_mb: u64,
+  /// Where `refresh_agent_registry` fetches built-in agent definitions from; the
+  /// registry published with the app's repository when unset
+  pub agent_registry_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SourceHostConfig {
+  pub kind: ProviderKind,
+  /// Web base URL, e.g. `https://gitlab.example.com`
+  pub base_url: String,
+  /// API base URL, when it is not at the provider's usual path under `base_url`
+  #[serde(default)]
+  pub api_url: Option<String>,
+  /// Access token sent with API and archive requests. Bitbucket app passwords are
+  /// given as `username:app_password`.
+  #[serde(default)]
+  pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupMode {
+  /// A self-contained ZIP of the whole skills folder per backup
+  #[default]
+  Zip,
+  /// Content-addressed store: file blobs are kept once, each backup is a small manifest
+  Dedup,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupSchedule {
+  #[default]
+  Off,
+  Daily,
+  Weekly,
+}
+
+/// Which backups survive a prune. A backup is kept when any rule selects it.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct BackupRetention {
+  /// Newest backups to keep
+  pub keep_last: u32,
+  /// Keep the newest backup of each of the last N days that have one
+  pub keep_daily: u32,
+  /// Keep the newest backup of each of the last N weeks that have one
+  pub keep_weekly: u32,
+}
+
+impl BackupRetention {
+  pub fn is_enabled(&self) -> bool {
+    self.keep_last > 0 || self.keep_daily > 0 || self.keep_weekly > 0
+  }
 }
 
 impl Default for AppConfig {
@@ -25,10 +91,51 @@ impl Default for AppConfig {
       unknown_skill_install_permission: false,
       backup_folder: None,
       last_backup_time: None,
+      backup_mode: BackupMode::default(),
+      backup_retention: BackupRetention::default(),
+      backup_schedule: BackupSchedule::default(),
+      update_check_hours: 0,
       openrouter_api_key: None,
       translate_target_language: String::new(),
       translate_model: String::new(),
+      source_hosts: Vec::new(),
+      workspace_max_mb: 1024,
+      agent_registry_url: None,
+    }
+  }
+}
+
+impl AppConfig {
+  /// This config without the OpenRouter key and git host tokens, for copies that leave
+  /// the machine such as backups
+  pub fn without_secrets(&self) -> Self {
+    let mut config = self.clone();
+    config.openrouter_api_key = None;
+    for host in &mut config.source_hosts {
+      host.token = None;
+    }
+    config
+  }
+
+  /// Fill in the secrets `without_secrets` dropped from `local`: the OpenRouter key, and
+  /// the token of each git host `local` has with the same base URL
+  pub fn with_secrets_from(mut self, local: &AppConfig) -> Self {
+    if self.openrouter_api_key.is_none() {
+      self.openrouter_api_key = local.openrouter_api_key.clone();
+    }
+    for host in &mut self.source_hosts {
+      if host.token.is_some() {
+        continue;
+      }
+      host.token = local
+        .source_hosts
+        .iter()
+        .find(|local_host| {
+          local_host.base_url.trim_end_matches('/') == host.base_url.trim_end_matches('/')
+        })
+        .and_then(|local_host| local_host.token.clone());
     }
+    self
   }
 }
 
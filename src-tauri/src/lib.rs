@@ -0,0 +1,7 @@
+// Core library shared by the desktop app and the headless `youskill` CLI.
+// Nothing in here may depend on Tauri, so it can run on machines without a display.
+
+pub mod config;
+pub mod models;
+pub mod services;
+pub mod utils;
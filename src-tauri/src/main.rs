@@ -3,32 +3,40 @@
 use tauri::Manager;
 
 mod commands;
-mod config;
-mod models;
-mod services;
+mod scheduler;
 mod tray;
-mod utils;
+mod watcher;
+
+use you_skill::{config, models, services};
 
 use commands::{
   agent_apps::{
-    add_user_agent_app, list_local_agent_apps, refresh_agent_apps, remove_user_agent_app,
+    add_user_agent_app, export_user_agent_apps, get_agent_registry_status, import_user_agent_apps,
+    list_local_agent_apps, refresh_agent_apps, refresh_agent_registry, remove_user_agent_app,
     update_user_agent_app,
   },
   remote::{fetch_remote_skills, fetch_skills_by_names, record_skill_install},
   settings::{
-    backup_skills, get_settings, list_openrouter_models, open_backup_folder, set_backup_folder,
-    update_settings,
+    backup_skills, get_schedule_state, get_settings, list_backups, list_openrouter_models,
+    open_backup_folder, preview_backup_restore, prune_backups, restore_backup, set_backup_folder,
+    test_source_connection, update_settings,
   },
   skill::{
-    check_skill_version, check_skills_updates, delete_skill, detect_folder, detect_github_auto,
-    detect_github_manual, detect_zip, install_from_github, install_from_native,
-    install_from_unknown, list_skill_directory, list_skills, manage_skill_agent_apps,
+    apply_doctor_fixes, check_project_drift, check_skill_version, check_skills_updates,
+    delete_skill, detect_folder, detect_github_auto, detect_github_manual, detect_zip,
+    export_skill_pack, install_from_github, install_from_native, install_from_unknown,
+    lint_installed_skills, lint_skill, list_skill_directory, list_skills, manage_skill_agent_apps,
     open_in_file_manager, read_skill_file, read_skill_relative_file,
-    read_skill_relative_file_bytes, translate_skill_markdown,
+    read_skill_relative_file_bytes, release_detect_session, relock_project_skill,
+    restore_project_skill, run_doctor, sync_project_from_lock, translate_skill_markdown,
+    update_all_skills, update_skill,
   },
+  tray::set_tray_skill_updates,
   user_projects::{add_user_project, list_user_projects, remove_user_project, update_user_project},
 };
+use scheduler::setup_scheduler;
 use tray::setup_tray;
+use watcher::setup_watcher;
 
 #[tauri::command]
 fn ping() -> String {
@@ -62,6 +70,23 @@ fn main() {
         eprintln!("Failed to setup tray: {}", e);
       }
 
+      // Clear detect sessions left over from earlier runs
+      std::thread::spawn(|| {
+        if let Err(e) = services::workspace_service::sweep_workspace() {
+          tracing::warn!("Failed to sweep workspace: {}", e);
+        }
+      });
+
+      // Push skill folder changes to the UI
+      if let Err(e) = setup_watcher(app.handle()) {
+        eprintln!("Failed to start skill watcher: {}", e);
+      }
+
+      // Scheduled backups and update checks
+      if let Err(e) = setup_scheduler(app.handle()) {
+        eprintln!("Failed to start scheduler: {}", e);
+      }
+
       // Handle window close event - hide to tray instead of quitting
       let main_window = app.get_webview_window("main").unwrap();
       let window_clone = main_window.clone();
@@ -82,15 +107,21 @@ fn main() {
       list_skills,
       delete_skill,
       get_settings,
+      get_schedule_state,
       update_settings,
+      test_source_connection,
       fetch_remote_skills,
       fetch_skills_by_names,
       record_skill_install,
       list_local_agent_apps,
       refresh_agent_apps,
+      get_agent_registry_status,
+      refresh_agent_registry,
       add_user_agent_app,
       remove_user_agent_app,
       update_user_agent_app,
+      export_user_agent_apps,
+      import_user_agent_apps,
       detect_zip,
       detect_folder,
       detect_github_manual,
@@ -103,6 +134,10 @@ fn main() {
       set_backup_folder,
       open_backup_folder,
       backup_skills,
+      list_backups,
+      prune_backups,
+      preview_backup_restore,
+      restore_backup,
       list_openrouter_models,
       read_skill_file,
       list_skill_directory,
@@ -111,6 +146,19 @@ fn main() {
       translate_skill_markdown,
       open_in_file_manager,
       check_skills_updates,
+      set_tray_skill_updates,
+      update_skill,
+      update_all_skills,
+      sync_project_from_lock,
+      check_project_drift,
+      release_detect_session,
+      relock_project_skill,
+      restore_project_skill,
+      lint_skill,
+      lint_installed_skills,
+      run_doctor,
+      apply_doctor_fixes,
+      export_skill_pack,
       list_user_projects,
       add_user_project,
       update_user_project,
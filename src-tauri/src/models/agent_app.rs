@@ -1,11 +1,188 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct AgentApp {
   pub id: String,
   pub display_name: String,
   pub project_path: Option<String>,
+  /// Preferred global skills folder; `~/`, `${VAR}` and `${VAR:-default}` are expanded
   pub global_path: Option<String>,
+  /// Other project skills folders the agent also reads, in order of preference
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub project_paths: Vec<String>,
+  /// Other global skills folders the agent also reads, in order of preference
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub global_paths: Vec<String>,
   #[serde(default)]
   pub is_user_custom: bool,
+  /// Detection rules from the agent registry
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub detection: Option<AgentDetection>,
+  /// Native format the agent loads skills from, written next to the linked folder
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub adapter: Option<SkillAdapterKind>,
+  /// Why the agent counts as installed, set on detected apps
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub detected_by: Option<AgentDetectionMatch>,
+  /// Version printed by the agent's version command
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub detected_version: Option<String>,
+  /// Global skills folder in use, expanded, set on detected apps
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub global_root: Option<String>,
+}
+
+/// How to tell an agent is installed besides its skills folder. Any rule that
+/// matches is enough.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct AgentDetection {
+  /// Executables looked up on PATH
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub binaries: Vec<String>,
+  /// Files or folders, `~` expanded, such as app bundles or settings files
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub config_files: Vec<String>,
+  /// Arguments that make the first binary found print its version
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub version_args: Vec<String>,
+}
+
+/// Agent-specific file a skill is rendered into for agents that do not load `SKILL.md`
+/// folders on their own.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillAdapterKind {
+  /// `.cursor/rules/<name>.mdc`
+  CursorRules,
+  /// `.windsurf/rules/<name>.md`
+  WindsurfRules,
+  /// `.github/instructions/<name>.instructions.md`
+  CopilotInstructions,
+  /// `.continue/prompts/<name>.prompt`
+  ContinuePrompt,
+  /// `.roo/rules/<name>.md`
+  RooRules,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentDetectionKind {
+  /// An executable from the detection rules is on PATH
+  Binary,
+  /// A config file from the detection rules exists
+  ConfigFile,
+  /// The global skills folder exists
+  SkillsFolder,
+  /// The folder holding the global skills folder exists; only used for agents without
+  /// detection rules
+  AgentFolder,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AgentDetectionMatch {
+  pub kind: AgentDetectionKind,
+  /// The binary, file or folder that matched
+  pub path: String,
+}
+
+/// Versioned document listing the built-in agent apps. One copy is bundled with the
+/// app; a newer revision can be fetched from the configured registry source.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentRegistry {
+  /// Schema version; documents with a version this build does not know are rejected
+  pub version: u32,
+  /// Bumped with every data change
+  pub revision: u32,
+  pub agents: Vec<AgentRegistryEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentRegistryEntry {
+  pub id: String,
+  pub display_name: String,
+  /// Skills folder relative to a project root
+  #[serde(default)]
+  pub project_path: Option<String>,
+  /// Skills folder for the user, `~` and `${VAR:-default}` expanded
+  #[serde(default)]
+  pub global_path: Option<String>,
+  /// See [`AgentApp::project_paths`]
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub project_paths: Vec<String>,
+  /// See [`AgentApp::global_paths`]
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub global_paths: Vec<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub detection: Option<AgentDetection>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub adapter: Option<SkillAdapterKind>,
+}
+
+impl From<AgentRegistryEntry> for AgentApp {
+  fn from(entry: AgentRegistryEntry) -> Self {
+    AgentApp {
+      id: entry.id,
+      display_name: entry.display_name,
+      project_path: entry.project_path,
+      global_path: entry.global_path,
+      project_paths: entry.project_paths,
+      global_paths: entry.global_paths,
+      is_user_custom: false,
+      detection: entry.detection,
+      adapter: entry.adapter,
+      ..Default::default()
+    }
+  }
+}
+
+impl From<AgentApp> for AgentRegistryEntry {
+  fn from(app: AgentApp) -> Self {
+    AgentRegistryEntry {
+      id: app.id,
+      display_name: app.display_name,
+      project_path: app.project_path,
+      global_path: app.global_path,
+      project_paths: app.project_paths,
+      global_paths: app.global_paths,
+      detection: app.detection,
+      adapter: app.adapter,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentRegistrySource {
+  Bundled,
+  Refreshed,
+}
+
+/// Which registry the built-in agent apps currently come from.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentRegistryStatus {
+  pub source: AgentRegistrySource,
+  pub revision: u32,
+  pub bundled_revision: u32,
+  pub agent_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentAppImportStatus {
+  Added,
+  /// Replaced the custom agent with the same id, or with the same global path
+  Updated,
+  /// Identical to the custom agent already defined
+  Unchanged,
+  /// Clashes with a built-in agent or with the folders of another agent
+  Skipped,
+}
+
+/// What happened to one agent definition from an imported file.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentAppImportResult {
+  pub id: String,
+  pub display_name: String,
+  pub status: AgentAppImportStatus,
+  pub message: Option<String>,
 }
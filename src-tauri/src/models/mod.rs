@@ -1,12 +1,24 @@
 pub mod agent_app;
+pub mod schedule;
 pub mod skill;
 pub mod user_project;
 
-pub use agent_app::AgentApp;
+pub use agent_app::{
+  AgentApp, AgentAppImportResult, AgentAppImportStatus, AgentDetection, AgentDetectionKind,
+  AgentDetectionMatch, AgentRegistry, AgentRegistryEntry, AgentRegistrySource, AgentRegistryStatus,
+  SkillAdapterKind,
+};
+pub use schedule::{ScheduleState, ScheduledJob, ScheduledJobRun};
 pub use skill::{
-  DetectedSkill, InstallGithubRequest, InstallMethod, InstallNativeRequest, InstallResult,
-  InstallScope, InstallTarget, InstallUnknownRequest, InstalledAgentApp, LocalSkill,
-  ManageSkillAgentAppsRequest, RemoteSkill, RemoteSkillsResponse, SelectedAgentPath,
-  SkillDirectoryEntry, SkillUpdateCheckItem, SourceCheckResult, SourceType, SourceVersionGroup,
+  AgentChange, DetectedSkill, DoctorFinding, DoctorFix, DoctorFixResult, DoctorIssueKind,
+  ExportSkillPackRequest, FileChangeKind, GitRefKind, InstallGithubRequest, InstallMethod,
+  InstallNativeRequest, InstallResult, InstallScope, InstallTarget, InstallUnknownRequest,
+  InstalledAgentApp, LintSeverity, LocalSkill, ManageSkillAgentAppsRequest, ProjectLockSyncResult,
+  ProjectLockSyncStatus, ProviderKind, RateLimitStatus, RemoteSkill, RemoteSkillsResponse,
+  SelectedAgentPath, SkillChange, SkillDiagnostic, SkillDirectoryEntry, SkillDriftItem,
+  SkillDriftStatus, SkillFileChange, SkillFrontmatter, SkillLintReport, SkillPackAgentLink,
+  SkillPackExportResult, SkillPackOrigin, SkillUpdateCheckItem, SkillUpdateResult, SkillWatchEvent,
+  SourceCheckResult, SourceConnectionStatus, SourceRef, SourceType, SourceVersionGroup,
+  SyncProjectLockRequest,
 };
 pub use user_project::UserProject;
@@ -0,0 +1,33 @@
+use super::RemoteSkill;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledJob {
+  Backup,
+  UpdateCheck,
+}
+
+/// Outcome of one scheduled run, persisted and sent to the UI.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduledJobRun {
+  pub job: ScheduledJob,
+  /// RFC 3339 time the run finished
+  pub finished_at: String,
+  pub success: bool,
+  pub message: Option<String>,
+  /// Backup file or snapshot written by a backup run
+  #[serde(default)]
+  pub backup_path: Option<String>,
+  /// Global skills with a newer version, from an update check run
+  #[serde(default)]
+  pub updates: Vec<RemoteSkill>,
+}
+
+/// Last run of each scheduled job, kept across restarts.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ScheduleState {
+  pub backup: Option<ScheduledJobRun>,
+  pub update_check: Option<ScheduledJobRun>,
+}
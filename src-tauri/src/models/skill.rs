@@ -1,4 +1,6 @@
+use super::SkillAdapterKind;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -8,16 +10,97 @@ pub struct LocalSkill {
   pub root_folder: Option<String>,
   pub installed_agent_apps: Vec<InstalledAgentApp>,
   pub source_type: SourceType,
+  pub frontmatter: Option<SkillFrontmatter>,
+}
+
+/// Parsed SKILL.md frontmatter. Keys outside the known fields are kept in `extra`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SkillFrontmatter {
+  pub name: Option<String>,
+  pub description: Option<String>,
+  pub license: Option<String>,
+  /// Top-level `version`, or `metadata.version` as the spec recommends
+  pub version: Option<String>,
+  pub compatibility: Option<String>,
+  /// Tools from `allowed-tools`
+  pub allowed_tools: Vec<String>,
+  pub metadata: BTreeMap<String, serde_json::Value>,
+  pub extra: BTreeMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum SourceType {
   Github,
+  Gitlab,
+  Gitea,
+  Bitbucket,
   Native,
   Unknown,
 }
 
+impl SourceType {
+  /// The git host a remote skill came from, or `None` for local sources.
+  pub fn provider(&self) -> Option<ProviderKind> {
+    match self {
+      SourceType::Github => Some(ProviderKind::Github),
+      SourceType::Gitlab => Some(ProviderKind::Gitlab),
+      SourceType::Gitea => Some(ProviderKind::Gitea),
+      SourceType::Bitbucket => Some(ProviderKind::Bitbucket),
+      SourceType::Native | SourceType::Unknown => None,
+    }
+  }
+
+  pub fn is_remote(&self) -> bool {
+    self.provider().is_some()
+  }
+}
+
+impl From<ProviderKind> for SourceType {
+  fn from(kind: ProviderKind) -> Self {
+    match kind {
+      ProviderKind::Github => SourceType::Github,
+      ProviderKind::Gitlab => SourceType::Gitlab,
+      ProviderKind::Gitea => SourceType::Gitea,
+      ProviderKind::Bitbucket => SourceType::Bitbucket,
+    }
+  }
+}
+
+/// Git hosting service a remote skill is downloaded from. Gitea also covers Forgejo,
+/// which serves the same API.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+  #[default]
+  Github,
+  Gitlab,
+  Gitea,
+  Bitbucket,
+}
+
+impl ProviderKind {
+  /// Value stored in the `sourceType` field of lock files.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ProviderKind::Github => "github",
+      ProviderKind::Gitlab => "gitlab",
+      ProviderKind::Gitea => "gitea",
+      ProviderKind::Bitbucket => "bitbucket",
+    }
+  }
+
+  pub fn from_lock_value(value: &str) -> Option<Self> {
+    match value.trim() {
+      "github" => Some(ProviderKind::Github),
+      "gitlab" => Some(ProviderKind::Gitlab),
+      "gitea" | "forgejo" => Some(ProviderKind::Gitea),
+      "bitbucket" => Some(ProviderKind::Bitbucket),
+      _ => None,
+    }
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InstalledAgentApp {
   pub id: String,
@@ -59,13 +142,152 @@ pub struct InstallResult {
   pub stdout: String,
   pub stderr: String,
   pub message: String,
+  /// Lint findings for the installed skill
+  #[serde(default)]
+  pub diagnostics: Vec<SkillDiagnostic>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+  Error,
+  Warning,
+  Info,
+}
+
+/// One finding from linting a skill folder.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkillDiagnostic {
+  pub severity: LintSeverity,
+  /// Stable identifier of the check, e.g. `name-format`
+  pub code: String,
+  pub message: String,
+  /// File relative to the skill folder
+  pub file: Option<String>,
+  /// 1-based line number
+  pub line: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkillLintReport {
+  pub name: Option<String>,
+  pub path: String,
+  /// Set when the report comes from linting installed skills
+  pub scope: Option<InstallScope>,
+  pub project_path: Option<String>,
+  pub diagnostics: Vec<SkillDiagnostic>,
+}
+
+impl SkillLintReport {
+  pub fn has_errors(&self) -> bool {
+    self
+      .diagnostics
+      .iter()
+      .any(|d| d.severity == LintSeverity::Error)
+  }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DetectedSkill {
   pub name: String,
-  pub tmp_path: String,
+  /// Workspace session holding the staged copy, passed back to install
+  pub session_id: String,
+  /// Path of SKILL.md relative to the source root
   pub skill_path: String,
+  /// Git ref the skill was detected at (repository sources only)
+  #[serde(default)]
+  pub source_ref: Option<SourceRef>,
+  #[serde(default)]
+  pub frontmatter: Option<SkillFrontmatter>,
+  #[serde(default)]
+  pub diagnostics: Vec<SkillDiagnostic>,
+  /// Provenance recorded for the skill when it comes from an exported skill pack
+  #[serde(default)]
+  pub pack_origin: Option<SkillPackOrigin>,
+}
+
+/// Where a skill in a skill pack was installed from and which agents used it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkillPackOrigin {
+  pub source_type: SourceType,
+  /// Repository the skill was installed from; `None` for local skills
+  pub source: Option<String>,
+  pub agent_apps: Vec<SkillPackAgentLink>,
+  /// Content differs from the export, so the skill installs as a local one
+  #[serde(default)]
+  pub modified: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkillPackAgentLink {
+  pub id: String,
+  pub method: InstallMethod,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportSkillPackRequest {
+  pub names: Vec<String>,
+  #[serde(default)]
+  pub scope: InstallScope,
+  #[serde(default)]
+  pub project_path: Option<String>,
+  /// ZIP file to write
+  pub output_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkillPackExportResult {
+  pub path: String,
+  pub skills: Vec<String>,
+}
+
+/// Result of probing a git host with its configured credentials.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SourceConnectionStatus {
+  pub kind: ProviderKind,
+  pub base_url: String,
+  /// Whether the request was made with a token
+  pub authenticated: bool,
+  /// Account the token belongs to
+  pub user: Option<String>,
+  /// Token scopes, when the host reports them
+  pub scopes: Vec<String>,
+  pub rate_limit: Option<RateLimitStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RateLimitStatus {
+  pub limit: i64,
+  pub remaining: Option<i64>,
+  /// RFC 3339 time at which the quota resets
+  pub reset_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitRefKind {
+  Branch,
+  Tag,
+  Commit,
+}
+
+/// Git ref a GitHub skill is installed from. A `git_ref` of `None` follows the
+/// repository's default branch.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct SourceRef {
+  #[serde(default)]
+  pub git_ref: Option<String>,
+  #[serde(default)]
+  pub ref_kind: Option<GitRefKind>,
+  #[serde(default)]
+  pub resolved_commit: Option<String>,
+}
+
+impl SourceRef {
+  /// Tags and commits do not move, so skills pinned to them are never updated.
+  pub fn is_pinned(&self) -> bool {
+    matches!(self.ref_kind, Some(GitRefKind::Tag | GitRefKind::Commit))
+  }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -141,7 +363,8 @@ impl InstallTarget {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InstallNativeRequest {
   pub name: String,
-  pub tmp_path: String,
+  /// Detect session the skill was staged in
+  pub session_id: String,
   pub skill_path: String,
   pub agent_apps: Vec<String>,
   pub method: InstallMethod,
@@ -149,15 +372,23 @@ pub struct InstallNativeRequest {
   pub scope: InstallScope,
   #[serde(default)]
   pub project_path: Option<String>,
+  /// For skills from a skill pack: record the original source in the lock file and
+  /// also link the agents the skill was exported from
+  #[serde(default)]
+  pub restore_pack_origin: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InstallGithubRequest {
   pub name: String,
-  pub tmp_path: String,
+  /// Detect session the skill was staged in
+  pub session_id: String,
   pub skill_path: String,
   pub source_url: String,
   pub skill_folder_hash: Option<String>,
+  /// Branch, tag or commit to install from; the detected ref is passed back here
+  #[serde(default)]
+  pub source_ref: Option<SourceRef>,
   pub agent_apps: Vec<String>,
   pub method: InstallMethod,
   #[serde(default)]
@@ -209,6 +440,7 @@ pub struct ManageSkillAgentAppsRequest {
 pub struct SelectedAgentPath {
   pub display_name: String,
   pub install_root: PathBuf,
+  pub adapter: Option<SkillAdapterKind>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -222,4 +454,199 @@ pub struct SkillUpdateCheckItem {
   pub name: String,
   pub source: String,
   pub remote_sha: String,
+  /// Branch the remote SHA was computed on
+  #[serde(default)]
+  pub branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SyncProjectLockRequest {
+  pub project_path: String,
+  /// Agent apps to link into; empty means every agent whose skills folder already
+  /// exists in the project
+  #[serde(default)]
+  pub agent_apps: Vec<String>,
+  pub method: InstallMethod,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectLockSyncStatus {
+  /// Fetched from its source and linked
+  Installed,
+  /// Already present with the locked hash; only the links were refreshed
+  UpToDate,
+  /// The fetched content does not match `computedHash`, so nothing was installed
+  HashMismatch,
+  /// The entry has no source to fetch from, e.g. a native skill
+  Unsatisfiable,
+  Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectLockSyncResult {
+  pub name: String,
+  pub status: ProjectLockSyncStatus,
+  pub source: String,
+  pub expected_hash: String,
+  pub actual_hash: Option<String>,
+  pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillDriftStatus {
+  /// Matches the hash in skills-lock.json
+  Clean,
+  /// Present on disk with content that differs from the lock
+  Modified,
+  /// Listed in skills-lock.json but not on disk
+  Missing,
+  /// On disk without a skills-lock.json entry
+  Untracked,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+  Added,
+  Removed,
+  Modified,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkillFileChange {
+  /// Path relative to the skill folder
+  pub path: String,
+  pub change: FileChangeKind,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkillDriftItem {
+  pub name: String,
+  pub status: SkillDriftStatus,
+  pub path: Option<String>,
+  pub source: Option<String>,
+  pub expected_hash: Option<String>,
+  pub actual_hash: Option<String>,
+  /// Changed files; empty for modified skills locked before per-file hashes were recorded
+  pub changes: Vec<SkillFileChange>,
+  /// Whether the locked content can be fetched again from a repository
+  pub restorable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorIssueKind {
+  /// Symlink whose target no longer exists
+  BrokenLink,
+  /// Agent symlink pointing somewhere other than the skill's canonical folder
+  LinkOutsideStore,
+  /// Lock entry whose canonical folder is gone
+  MissingLockedFolder,
+  /// Canonical skill that no lock file records
+  UnlockedSkill,
+  /// Canonical skill that no agent links to
+  UnlinkedSkill,
+  /// Folder with the same skill name as the canonical one but different content
+  DuplicateName,
+  /// SKILL.md that is missing, unreadable or has no valid name
+  UnreadableSkillMd,
+}
+
+/// The change that resolves a doctor finding.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DoctorFix {
+  /// Delete a dangling symlink
+  RemoveLink { path: String },
+  /// Point an agent entry at the canonical folder
+  RelinkToStore { path: String, name: String },
+  /// Copy a folder into the canonical store and point symlinks at it
+  AdoptIntoStore { path: String, name: String },
+  /// Drop a lock entry
+  RemoveLockEntry { name: String },
+  /// Record a canonical skill as a native install
+  LockAsNative { name: String },
+  /// Symlink a canonical skill into every detected agent
+  LinkToAgents { name: String },
+  /// Overwrite an agent copy with the canonical content
+  ReplaceWithStoreCopy { path: String, name: String },
+  /// Move a folder that cannot be repaired into the doctor's quarantine folder
+  QuarantineFolder { path: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DoctorFinding {
+  /// Stable key for the finding within one scan
+  pub id: String,
+  pub kind: DoctorIssueKind,
+  pub scope: InstallScope,
+  pub project_path: Option<String>,
+  pub skill_name: Option<String>,
+  pub path: Option<String>,
+  /// Agent whose folder holds `path`, if any
+  pub agent_id: Option<String>,
+  pub message: String,
+  pub fix: DoctorFix,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DoctorFixResult {
+  pub id: String,
+  pub success: bool,
+  pub message: String,
+}
+
+/// A skill that appeared, disappeared or changed on disk.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SkillChange {
+  pub name: String,
+  pub scope: InstallScope,
+  pub project_path: Option<String>,
+}
+
+/// An agent app whose folder appeared or disappeared.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AgentChange {
+  pub id: String,
+  pub display_name: String,
+}
+
+/// A change noticed by the skill folder watcher.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SkillWatchEvent {
+  SkillAdded(SkillChange),
+  SkillRemoved(SkillChange),
+  /// Content or agent links changed
+  SkillModified(SkillChange),
+  AgentDetected(AgentChange),
+  AgentRemoved(AgentChange),
+}
+
+impl SkillWatchEvent {
+  /// Name of the event sent to the UI.
+  pub fn event_name(&self) -> &'static str {
+    match self {
+      SkillWatchEvent::SkillAdded(_) => "skill-added",
+      SkillWatchEvent::SkillRemoved(_) => "skill-removed",
+      SkillWatchEvent::SkillModified(_) => "skill-modified",
+      SkillWatchEvent::AgentDetected(_) => "agent-detected",
+      SkillWatchEvent::AgentRemoved(_) => "agent-removed",
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkillUpdateResult {
+  pub name: String,
+  pub success: bool,
+  /// False when the skill was already up to date or the update failed
+  pub updated: bool,
+  pub previous_hash: Option<String>,
+  pub current_hash: Option<String>,
+  /// Agent apps whose links were carried over to the updated skill
+  pub agent_apps: Vec<String>,
+  pub message: String,
 }
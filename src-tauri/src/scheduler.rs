@@ -0,0 +1,21 @@
+use tauri::{AppHandle, Emitter, Manager};
+use you_skill::models::ScheduledJob;
+use you_skill::services::scheduler_service::{start_scheduler, SCHEDULED_JOB_EVENT};
+
+use crate::tray::set_tray_updates;
+
+/// Start scheduled backups and update checks and forward each run as an app event.
+pub fn setup_scheduler(app: &AppHandle) -> Result<(), String> {
+  let handle = app.clone();
+  let scheduler = start_scheduler(move |run| {
+    if let Err(e) = handle.emit(SCHEDULED_JOB_EVENT, &run) {
+      tracing::warn!("Failed to emit {}: {}", SCHEDULED_JOB_EVENT, e);
+    }
+    if run.job == ScheduledJob::UpdateCheck && run.success {
+      set_tray_updates(&handle, run.updates);
+    }
+  })?;
+  // Managed state lives as long as the app, which keeps the scheduler running.
+  app.manage(scheduler);
+  Ok(())
+}
@@ -1,52 +1,64 @@
-use crate::models::{AgentApp, InstallTarget, SelectedAgentPath};
-use crate::utils::path::expand_home;
+use crate::models::{
+  AgentApp, AgentAppImportResult, AgentAppImportStatus, AgentRegistry, AgentRegistryEntry,
+  InstallTarget, SelectedAgentPath,
+};
+use crate::services::{agent_detection_service, agent_registry_service};
+use crate::utils::path::expand_path;
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::sync::RwLock;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-// Global cache for local agent apps
-static LOCAL_AGENT_APPS: RwLock<Option<Vec<AgentApp>>> = RwLock::new(None);
-
-// Check if the app's base directory exists (parent of the last folder in global_path)
-fn check_app_base_exists(global_path: &str) -> bool {
-  let expanded_path = expand_home(global_path);
-  // If the path itself exists, return true
-  if expanded_path.exists() {
-    return true;
-  }
-  // Check if the parent directory exists (the last folder might not be created yet)
-  if let Some(parent) = expanded_path.parent() {
-    if parent.exists() {
-      return true;
-    }
-  }
-  false
-}
+/// Folders under the home folder that hold credentials; no agent's skills folder may be
+/// inside one, or installing a skill could overwrite or expose them.
+const PROTECTED_HOME_FOLDERS: &[&str] = &[
+  ".ssh",
+  ".gnupg",
+  ".aws",
+  ".azure",
+  ".kube",
+  ".docker",
+  ".password-store",
+  ".config/gcloud",
+  ".config/gh",
+];
+
+// Global cache for local agent apps, with the time they were detected
+static LOCAL_AGENT_APPS: RwLock<Option<(Instant, Vec<AgentApp>)>> = RwLock::new(None);
+
+/// Agents come and go without touching any watched folder, so detection is re-run
+/// once the cached result is this old.
+const DETECTION_TTL: Duration = Duration::from_secs(5 * 60);
 
 // Get local agent apps (actually installed on the system)
 pub fn local_agent_apps() -> Vec<AgentApp> {
   // Check cache first
   if let Ok(cache) = LOCAL_AGENT_APPS.read() {
-    if let Some(ref cached) = *cache {
-      return cached.clone();
+    if let Some((detected_at, cached)) = cache.as_ref() {
+      if detected_at.elapsed() < DETECTION_TTL {
+        return cached.clone();
+      }
     }
   }
 
+  // Version commands can take a moment, so agents are checked side by side
   let all_apps = all_agent_apps();
-  let mut local_apps = Vec::new();
-
-  for app in all_apps {
-    if let Some(global_path) = &app.global_path {
-      if check_app_base_exists(global_path) {
-        local_apps.push(app);
-      }
-    }
-  }
+  let local_apps: Vec<AgentApp> = std::thread::scope(|scope| {
+    let handles: Vec<_> = all_apps
+      .iter()
+      .map(|app| scope.spawn(move || agent_detection_service::detect_agent(app)))
+      .collect();
+    handles
+      .into_iter()
+      .filter_map(|handle| handle.join().ok().flatten())
+      .collect()
+  });
 
   // Update cache
   if let Ok(mut cache) = LOCAL_AGENT_APPS.write() {
-    *cache = Some(local_apps.clone());
+    *cache = Some((Instant::now(), local_apps.clone()));
   }
 
   tracing::info!("local_apps: {:?}", local_apps);
@@ -85,6 +97,7 @@ pub fn resolve_selected_apps_paths(
     selected.push(SelectedAgentPath {
       display_name: app.display_name.clone(),
       install_root,
+      adapter: app.adapter,
     });
   }
 
@@ -98,6 +111,7 @@ pub fn resolve_selected_apps_paths(
   Ok(selected)
 }
 
+/// The skills folder each detected agent installs into for `install_target`.
 pub fn resolve_all_available_apps_paths(
   install_target: &InstallTarget,
 ) -> Result<Vec<SelectedAgentPath>, String> {
@@ -109,32 +123,80 @@ pub fn resolve_all_available_apps_paths(
         .map(|install_root| SelectedAgentPath {
           display_name: app.display_name,
           install_root,
+          adapter: app.adapter,
         })
     })
     .collect();
   Ok(all_apps)
 }
 
+/// Every skills folder of every detected agent for `install_target`, so skills in a
+/// fallback folder are found, updated and removed as well.
+pub fn resolve_all_agent_skill_folders(install_target: &InstallTarget) -> Vec<SelectedAgentPath> {
+  local_agent_apps()
+    .into_iter()
+    .flat_map(|app| {
+      agent_skill_roots(&app, install_target)
+        .into_iter()
+        .map(move |install_root| SelectedAgentPath {
+          display_name: app.display_name.clone(),
+          install_root,
+          adapter: app.adapter,
+        })
+    })
+    .collect()
+}
+
+/// The folder new skills for `app` go into: the first of its skills folders that
+/// exists, or the preferred one when none does yet.
 pub fn root_folder_from_install_target(
   app: &AgentApp,
   install_target: &InstallTarget,
 ) -> Result<PathBuf, String> {
-  match install_target {
-    InstallTarget::Global => {
-      let global_path = app
-        .global_path
-        .as_deref()
-        .ok_or("has no global path".to_string())?;
-      Ok(expand_home(global_path))
-    },
-    InstallTarget::Project(project_root) => {
-      let project_path = app
-        .project_path
-        .as_deref()
-        .ok_or("has no project path".to_string())?;
-      Ok(project_root.join(project_path))
-    },
+  let roots = agent_skill_roots(app, install_target);
+  let preferred = roots.first().cloned().ok_or(match install_target {
+    InstallTarget::Global => "has no global path".to_string(),
+    InstallTarget::Project(_) => "has no project path".to_string(),
+  })?;
+  Ok(
+    roots
+      .into_iter()
+      .find(|root| root.is_dir())
+      .unwrap_or(preferred),
+  )
+}
+
+/// Every skills folder `app` reads for `install_target`, most preferred first, with
+/// variables and `~` expanded.
+pub fn agent_skill_roots(app: &AgentApp, install_target: &InstallTarget) -> Vec<PathBuf> {
+  let candidates: Vec<PathBuf> = match install_target {
+    InstallTarget::Global => app
+      .global_path
+      .iter()
+      .chain(&app.global_paths)
+      .filter_map(|path| match expand_path(path) {
+        Ok(path) => Some(path),
+        // An unset variable would leave a path such as `/skills`, so the root is dropped.
+        Err(err) => {
+          tracing::debug!("Skipping global path of {}: {}", app.id, err);
+          None
+        },
+      })
+      .collect(),
+    InstallTarget::Project(project_root) => app
+      .project_path
+      .iter()
+      .chain(&app.project_paths)
+      .map(|path| project_root.join(path))
+      .collect(),
+  };
+  let mut roots: Vec<PathBuf> = Vec::new();
+  for candidate in candidates {
+    if !roots.contains(&candidate) {
+      roots.push(candidate);
+    }
   }
+  roots
 }
 
 pub fn create_user_agent_app(
@@ -149,12 +211,19 @@ pub fn create_user_agent_app(
   validate_user_agent_app(&display_name, &global_path, project_path.as_deref(), None)?;
 
   let id = generate_id_from_display_name(&display_name);
+  if let Some(taken) = all_agent_apps().into_iter().find(|app| app.id == id) {
+    return Err(format!(
+      "Agent id '{}' is already used by {}; choose another display name",
+      id, taken.display_name
+    ));
+  }
   let user_app = AgentApp {
     id: id.clone(),
     display_name,
     global_path: Some(global_path),
     project_path,
     is_user_custom: true,
+    ..Default::default()
   };
 
   let mut apps = load_user_agent_apps().unwrap_or_default();
@@ -205,6 +274,7 @@ pub fn update_user_agent_app_detail(
     global_path: Some(global_path),
     project_path,
     is_user_custom: true,
+    ..Default::default()
   };
 
   update_user_agent_app(&id, user_app)?;
@@ -214,299 +284,7 @@ pub fn update_user_agent_app_detail(
   Ok(updated_app)
 }
 
-fn internal_agent_apps() -> Vec<AgentApp> {
-  vec![
-    AgentApp {
-      id: "claude-code".to_string(),
-      display_name: "Claude Code".to_string(),
-      project_path: Some(".claude/skills".to_string()),
-      global_path: Some("~/.claude/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "codex".to_string(),
-      display_name: "Codex".to_string(),
-      project_path: Some(".agents/skills".to_string()),
-      global_path: Some("~/.codex/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "cursor".to_string(),
-      display_name: "Cursor".to_string(),
-      project_path: Some(".agents/skills".to_string()),
-      global_path: Some("~/.cursor/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "cline".to_string(),
-      display_name: "Cline".to_string(),
-      project_path: Some(".cline/skills".to_string()),
-      global_path: Some("~/.cline/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "opencode".to_string(),
-      display_name: "OpenCode".to_string(),
-      project_path: Some(".agents/skills".to_string()),
-      global_path: Some("~/.config/opencode/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "openhands".to_string(),
-      display_name: "OpenHands".to_string(),
-      project_path: Some(".openhands/skills".to_string()),
-      global_path: Some("~/.openhands/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "github-copilot".to_string(),
-      display_name: "GitHub Copilot".to_string(),
-      project_path: Some(".agents/skills".to_string()),
-      global_path: Some("~/.copilot/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "continue".to_string(),
-      display_name: "Continue".to_string(),
-      project_path: Some(".continue/skills".to_string()),
-      global_path: Some("~/.continue/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "gemini-cli".to_string(),
-      display_name: "Gemini CLI".to_string(),
-      project_path: Some(".agents/skills".to_string()),
-      global_path: Some("~/.gemini/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "goose".to_string(),
-      display_name: "Goose".to_string(),
-      project_path: Some(".goose/skills".to_string()),
-      global_path: Some("~/.config/goose/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "windsurf".to_string(),
-      display_name: "Windsurf".to_string(),
-      project_path: Some(".windsurf/skills".to_string()),
-      global_path: Some("~/.codeium/windsurf/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "roo".to_string(),
-      display_name: "Roo Code".to_string(),
-      project_path: Some(".roo/skills".to_string()),
-      global_path: Some("~/.roo/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "kiro-cli".to_string(),
-      display_name: "Kiro CLI".to_string(),
-      project_path: Some(".kiro/skills".to_string()),
-      global_path: Some("~/.kiro/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "qwen-code".to_string(),
-      display_name: "Qwen Code".to_string(),
-      project_path: Some(".qwen/skills".to_string()),
-      global_path: Some("~/.qwen/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "amp".to_string(),
-      display_name: "AMP".to_string(),
-      project_path: Some(".agents/skills".to_string()),
-      global_path: Some("~/.config/agents/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "antigravity".to_string(),
-      display_name: "Antigravity".to_string(),
-      project_path: Some(".agent/skills".to_string()),
-      global_path: Some("~/.gemini/antigravity/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "command-code".to_string(),
-      display_name: "Command Code".to_string(),
-      project_path: Some(".commandcode/skills".to_string()),
-      global_path: Some("~/.commandcode/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "crush".to_string(),
-      display_name: "Crush".to_string(),
-      project_path: Some(".crush/skills".to_string()),
-      global_path: Some("~/.config/crush/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "trae".to_string(),
-      display_name: "Trae".to_string(),
-      project_path: Some(".trae/skills".to_string()),
-      global_path: Some("~/.trae/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "trae-cn".to_string(),
-      display_name: "Trae CN".to_string(),
-      project_path: Some(".trae/skills".to_string()),
-      global_path: Some("~/.trae-cn/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "vscode".to_string(),
-      display_name: "VSCode".to_string(),
-      project_path: Some(".agents/skills".to_string()),
-      global_path: Some("~/.github/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "augment".to_string(),
-      display_name: "Augment".to_string(),
-      project_path: Some(".augment/skills".to_string()),
-      global_path: Some("~/.augment/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "openclaw".to_string(),
-      display_name: "OpenClaw".to_string(),
-      project_path: Some("skills".to_string()),
-      global_path: Some("~/.openclaw/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "codebuddy".to_string(),
-      display_name: "CodeBuddy".to_string(),
-      project_path: Some(".codebuddy/skills".to_string()),
-      global_path: Some("~/.codebuddy/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "cortex".to_string(),
-      display_name: "Cortex Code".to_string(),
-      project_path: Some(".cortex/skills".to_string()),
-      global_path: Some("~/.snowflake/cortex/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "droid".to_string(),
-      display_name: "Droid".to_string(),
-      project_path: Some(".factory/skills".to_string()),
-      global_path: Some("~/.factory/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "junie".to_string(),
-      display_name: "Junie".to_string(),
-      project_path: Some(".junie/skills".to_string()),
-      global_path: Some("~/.junie/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "iflow-cli".to_string(),
-      display_name: "iFlow CLI".to_string(),
-      project_path: Some(".iflow/skills".to_string()),
-      global_path: Some("~/.iflow/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "kilo".to_string(),
-      display_name: "Kilo Code".to_string(),
-      project_path: Some(".kilocode/skills".to_string()),
-      global_path: Some("~/.kilocode/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "kimi-cli".to_string(),
-      display_name: "Kimi Code CLI".to_string(),
-      project_path: Some(".agents/skills".to_string()),
-      global_path: Some("~/.config/agents/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "kode".to_string(),
-      display_name: "Kode".to_string(),
-      project_path: Some(".kode/skills".to_string()),
-      global_path: Some("~/.kode/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "mcpjam".to_string(),
-      display_name: "MCPJam".to_string(),
-      project_path: Some(".mcpjam/skills".to_string()),
-      global_path: Some("~/.mcpjam/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "mistral-vibe".to_string(),
-      display_name: "Mistral Vibe".to_string(),
-      project_path: Some(".vibe/skills".to_string()),
-      global_path: Some("~/.vibe/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "mux".to_string(),
-      display_name: "Mux".to_string(),
-      project_path: Some(".mux/skills".to_string()),
-      global_path: Some("~/.mux/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "pi".to_string(),
-      display_name: "Pi".to_string(),
-      project_path: Some(".pi/skills".to_string()),
-      global_path: Some("~/.pi/agent/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "qoder".to_string(),
-      display_name: "Qoder".to_string(),
-      project_path: Some(".qoder/skills".to_string()),
-      global_path: Some("~/.qoder/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "replit".to_string(),
-      display_name: "Replit".to_string(),
-      project_path: Some(".agents/skills".to_string()),
-      global_path: Some("~/.config/agents/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "zenencoder".to_string(),
-      display_name: "Zencoder".to_string(),
-      project_path: Some(".zencoder/skills".to_string()),
-      global_path: Some("~/.zencoder/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "neovate".to_string(),
-      display_name: "Neovate".to_string(),
-      project_path: Some(".neovate/skills".to_string()),
-      global_path: Some("~/.neovate/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "pochi".to_string(),
-      display_name: "Pochi".to_string(),
-      project_path: Some(".pochi/skills".to_string()),
-      global_path: Some("~/.pochi/skills".to_string()),
-      is_user_custom: false,
-    },
-    AgentApp {
-      id: "adal".to_string(),
-      display_name: "AdaL".to_string(),
-      project_path: Some(".adal/skills".to_string()),
-      global_path: Some("~/.adal/skills".to_string()),
-      is_user_custom: false,
-    },
-  ]
-}
-
-fn user_agent_apps_path() -> Result<PathBuf, String> {
+pub fn user_agent_apps_path() -> Result<PathBuf, String> {
   let config_dir = dirs_next::config_dir().ok_or("无法获取配置目录")?;
   Ok(config_dir.join("youskill").join("user_agent_apps.json"))
 }
@@ -551,37 +329,107 @@ fn update_user_agent_app(id: &str, app: AgentApp) -> Result<(), String> {
   save_user_agent_apps(&apps)
 }
 
-fn all_agent_apps() -> Vec<AgentApp> {
-  let internal = internal_agent_apps();
-  let user_apps = load_user_agent_apps().unwrap_or_default();
-
-  let internal_global_paths: std::collections::HashSet<String> = internal
-    .iter()
-    .filter_map(|app| app.global_path.clone())
-    .collect();
-  let mut result: Vec<AgentApp> = internal;
+// All known agent apps, installed or not
+pub fn all_agent_apps() -> Vec<AgentApp> {
+  merge_agent_apps(
+    agent_registry_service::registry_agent_apps(),
+    load_user_agent_apps().unwrap_or_default(),
+  )
+}
 
+/// Combine built-in and user agent apps. Built-ins keep registry order; a user app
+/// replaces the built-in with the same id or the same global path, since it is the
+/// user's own definition of that agent, and is otherwise appended. Among user apps
+/// the later entry for an id wins. Entries without an id or display name are skipped.
+fn merge_agent_apps(registry: Vec<AgentApp>, user_apps: Vec<AgentApp>) -> Vec<AgentApp> {
+  let mut result = registry;
   for mut user_app in user_apps {
-    let user_global_path = user_app.global_path.clone();
+    if user_app.id.trim().is_empty() || user_app.display_name.trim().is_empty() {
+      tracing::warn!("Skipping invalid user agent app: {:?}", user_app);
+      continue;
+    }
+    let user_global = user_app
+      .global_path
+      .as_deref()
+      .and_then(|path| expand_path(path).ok());
     result.retain(|app| {
       app.id != user_app.id
-        && match &user_global_path {
-          Some(path) => {
-            !internal_global_paths.contains(path) || app.global_path.as_ref() != Some(path)
-          },
-          None => true,
-        }
+        && (app.is_user_custom
+          || user_global.is_none()
+          || app
+            .global_path
+            .as_deref()
+            .and_then(|path| expand_path(path).ok())
+            != user_global)
     });
     user_app.is_user_custom = true;
     result.push(user_app);
   }
-
   result
 }
 
 fn check_global_path_exists(global_path: &str) -> bool {
-  let expanded_path = expand_home(global_path);
-  expanded_path.exists()
+  expand_path(global_path).is_ok_and(|path| path.exists())
+}
+
+/// Check that `global_path` expands to a folder inside the home folder, other than the
+/// home folder itself and the folders in `PROTECTED_HOME_FOLDERS`.
+fn check_global_root(global_path: &str) -> Result<(), String> {
+  let root = expand_path(global_path)?;
+  let home = dirs_next::home_dir().ok_or("Could not find home directory")?;
+  let inside_home = root.strip_prefix(&home).is_ok_and(|relative| {
+    relative.components().next().is_some()
+      && relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+  });
+  if !inside_home {
+    return Err(format!(
+      "Global path '{}' must be a folder inside the home folder",
+      global_path
+    ));
+  }
+  if PROTECTED_HOME_FOLDERS
+    .iter()
+    .any(|folder| root.starts_with(home.join(folder)))
+  {
+    return Err(format!(
+      "Global path '{}' is inside a folder that holds credentials",
+      global_path
+    ));
+  }
+  Ok(())
+}
+
+/// The path checks every custom agent passes, whether created by hand or imported: a
+/// global and a project path, global folders inside the home folder, project folders
+/// inside the project, and an existing preferred global folder.
+fn validate_agent_paths(app: &AgentApp) -> Result<(), String> {
+  let global_path = app.global_path.as_deref().unwrap_or_default();
+  if global_path.is_empty() {
+    return Err("Global path is required".to_string());
+  }
+  if app.project_path.as_deref().unwrap_or_default().is_empty() {
+    return Err("Project path is required".to_string());
+  }
+  for path in app.global_path.iter().chain(&app.global_paths) {
+    check_global_root(path)?;
+  }
+  for path in app.project_path.iter().chain(&app.project_paths) {
+    if !agent_registry_service::is_project_relative(path) {
+      return Err(format!(
+        "Project path '{}' must be relative to the project",
+        path
+      ));
+    }
+  }
+  if !check_global_path_exists(global_path) {
+    return Err(format!(
+      "Global path folder does not exist: {}",
+      global_path
+    ));
+  }
+  Ok(())
 }
 
 fn get_agent_app(id: &str) -> Option<AgentApp> {
@@ -589,8 +437,249 @@ fn get_agent_app(id: &str) -> Option<AgentApp> {
   apps.into_iter().find(|app| app.id == id)
 }
 
-fn generate_id_from_display_name(_display_name: &str) -> String {
-  Uuid::new_v4().to_string()
+/// Stable id for a custom agent, so the same agent gets the same id on every machine
+/// and imports can recognise it. Names without ASCII letters or digits fall back to a
+/// hash of the name.
+fn generate_id_from_display_name(display_name: &str) -> String {
+  let mut slug = String::new();
+  for c in display_name.trim().chars() {
+    if c.is_ascii_alphanumeric() {
+      slug.push(c.to_ascii_lowercase());
+    } else if !slug.is_empty() && !slug.ends_with('-') {
+      slug.push('-');
+    }
+  }
+  let slug = slug.trim_end_matches('-');
+  if !slug.is_empty() {
+    return slug.to_string();
+  }
+  let digest = Sha256::digest(display_name.trim().as_bytes());
+  let hash: String = digest[..4].iter().map(|b| format!("{:02x}", b)).collect();
+  format!("agent-{}", hash)
+}
+
+/// Id to share a custom agent under. Agents created before ids were slugs have a random
+/// UUID, which would never match between machines.
+fn shareable_id(app: &AgentApp) -> String {
+  if Uuid::parse_str(&app.id).is_ok() {
+    generate_id_from_display_name(&app.display_name)
+  } else {
+    app.id.clone()
+  }
+}
+
+/// Write custom agents (every one when `ids` is empty) to `path` in the agent registry
+/// format, for teammates to import. Returns how many were written.
+pub fn export_user_agent_apps(ids: Vec<String>, path: String) -> Result<usize, String> {
+  let agents: Vec<AgentRegistryEntry> = load_user_agent_apps()?
+    .into_iter()
+    .filter(|app| ids.is_empty() || ids.contains(&app.id))
+    .map(|app| AgentRegistryEntry {
+      id: shareable_id(&app),
+      ..AgentRegistryEntry::from(app)
+    })
+    .collect();
+  if agents.is_empty() {
+    return Err("No custom agent apps to export".to_string());
+  }
+
+  let count = agents.len();
+  let document = AgentRegistry {
+    version: agent_registry_service::REGISTRY_VERSION,
+    revision: 1,
+    agents,
+  };
+  let content = serde_json::to_string_pretty(&document).map_err(|e| e.to_string())?;
+  agent_registry_service::parse_agent_registry(&content)?;
+  fs::write(&path, content).map_err(|e| format!("Failed to write agent apps file: {}", e))?;
+  Ok(count)
+}
+
+/// Add the custom agents from a file written by [`export_user_agent_apps`], read from a
+/// local path or an http(s) URL.
+///
+/// A definition replaces the custom agent with the same id, or the one at the same
+/// global path, since that is the same agent created by hand. Definitions that reuse a
+/// built-in id, or a display name or folder of another agent, are skipped.
+pub async fn import_user_agent_apps(source: String) -> Result<Vec<AgentAppImportResult>, String> {
+  let content = agent_registry_service::read_registry_document(source.trim()).await?;
+  let document = agent_registry_service::parse_agent_registry(&content)?;
+
+  let built_in_ids: Vec<String> = agent_registry_service::registry_agent_apps()
+    .into_iter()
+    .map(|app| app.id)
+    .collect();
+  let detected_built_ins: Vec<AgentApp> = local_agent_apps()
+    .into_iter()
+    .filter(|app| !app.is_user_custom)
+    .collect();
+  let mut user_apps = load_user_agent_apps()?;
+  let mut imported_ids = Vec::new();
+  let results: Vec<AgentAppImportResult> = document
+    .agents
+    .into_iter()
+    .map(|entry| {
+      // Detection rules run binaries, so only the built-in registry may bring them
+      let imported = AgentApp {
+        is_user_custom: true,
+        detection: None,
+        ..AgentApp::from(entry)
+      };
+      if let Err(err) = validate_agent_paths(&imported) {
+        return AgentAppImportResult {
+          id: imported.id,
+          display_name: imported.display_name,
+          status: AgentAppImportStatus::Skipped,
+          message: Some(err),
+        };
+      }
+      import_user_agent_app(
+        imported,
+        &built_in_ids,
+        &detected_built_ins,
+        &mut user_apps,
+        &mut imported_ids,
+      )
+    })
+    .collect();
+
+  if results.iter().any(|result| {
+    matches!(
+      result.status,
+      AgentAppImportStatus::Added | AgentAppImportStatus::Updated
+    )
+  }) {
+    save_user_agent_apps(&user_apps)?;
+    refresh_local_agent_apps();
+  }
+  Ok(results)
+}
+
+fn import_user_agent_app(
+  imported: AgentApp,
+  built_in_ids: &[String],
+  detected_built_ins: &[AgentApp],
+  user_apps: &mut Vec<AgentApp>,
+  imported_ids: &mut Vec<String>,
+) -> AgentAppImportResult {
+  let result = |status, message: Option<String>| AgentAppImportResult {
+    id: imported.id.clone(),
+    display_name: imported.display_name.clone(),
+    status,
+    message,
+  };
+  if built_in_ids.contains(&imported.id) {
+    return result(
+      AgentAppImportStatus::Skipped,
+      Some(format!("'{}' is the id of a built-in agent", imported.id)),
+    );
+  }
+
+  // An agent taken by an earlier definition in the same file is a conflict, not a match
+  let global_roots = agent_skill_roots(&imported, &InstallTarget::Global);
+  let replaceable = |app: &AgentApp| !imported_ids.contains(&app.id);
+  let existing = user_apps
+    .iter()
+    .position(|app| replaceable(app) && app.id == imported.id)
+    .or_else(|| {
+      user_apps.iter().position(|app| {
+        replaceable(app)
+          && agent_skill_roots(app, &InstallTarget::Global)
+            .first()
+            .is_some_and(|root| global_roots.first() == Some(root))
+      })
+    });
+  let others = detected_built_ins.iter().chain(
+    user_apps
+      .iter()
+      .enumerate()
+      .filter(|(index, _)| Some(*index) != existing)
+      .map(|(_, app)| app),
+  );
+  if let Some(conflict) = find_agent_conflict(&imported, others) {
+    return result(AgentAppImportStatus::Skipped, Some(conflict));
+  }
+  imported_ids.push(imported.id.clone());
+
+  match existing {
+    Some(index) if same_definition(&user_apps[index], &imported) => {
+      result(AgentAppImportStatus::Unchanged, None)
+    },
+    Some(index) => {
+      let replaced = std::mem::replace(&mut user_apps[index], imported.clone());
+      let message = (replaced.id != imported.id)
+        .then(|| format!("Replaces '{}' ({})", replaced.display_name, replaced.id));
+      result(AgentAppImportStatus::Updated, message)
+    },
+    None => {
+      user_apps.push(imported.clone());
+      result(AgentAppImportStatus::Added, None)
+    },
+  }
+}
+
+fn same_definition(a: &AgentApp, b: &AgentApp) -> bool {
+  a.id == b.id
+    && a.display_name == b.display_name
+    && a.global_path == b.global_path
+    && a.project_path == b.project_path
+    && a.global_paths == b.global_paths
+    && a.project_paths == b.project_paths
+    && a.detection == b.detection
+    && a.adapter == b.adapter
+}
+
+/// The first way `app` clashes with `others`: the same display name, or a global or
+/// project skills folder already in use. Folders are compared expanded, across every
+/// candidate folder of both agents.
+fn find_agent_conflict<'a>(
+  app: &AgentApp,
+  others: impl IntoIterator<Item = &'a AgentApp>,
+) -> Option<String> {
+  // Project folders are compared relative to the project root
+  let project = InstallTarget::Project(PathBuf::new());
+  let global_roots = agent_skill_roots(app, &InstallTarget::Global);
+  let project_roots = agent_skill_roots(app, &project);
+  for other in others {
+    if other.display_name.eq_ignore_ascii_case(&app.display_name) {
+      return Some(format!(
+        "Display name '{}' already exists",
+        app.display_name
+      ));
+    }
+    if let Some(root) = agent_skill_roots(other, &InstallTarget::Global)
+      .into_iter()
+      .find(|root| global_roots.contains(root))
+    {
+      return Some(format!(
+        "Global path '{}' is already used by {}",
+        root.to_string_lossy(),
+        other.display_name
+      ));
+    }
+    if let Some(root) = agent_skill_roots(other, &project)
+      .into_iter()
+      .find(|root| project_roots.iter().any(|own| same_project_path(own, root)))
+    {
+      return Some(format!(
+        "Project path '{}' is already used by {}",
+        root.to_string_lossy(),
+        other.display_name
+      ));
+    }
+  }
+  None
+}
+
+/// `.agents/skills`, `./.agents/skills` and `.agents/skills/` are the same folder.
+fn same_project_path(a: &Path, b: &Path) -> bool {
+  without_cur_dir(a).eq(without_cur_dir(b))
+}
+
+fn without_cur_dir(path: &Path) -> impl Iterator<Item = Component<'_>> {
+  path
+    .components()
+    .filter(|component| !matches!(component, Component::CurDir))
 }
 
 fn validate_user_agent_app(
@@ -599,52 +688,197 @@ fn validate_user_agent_app(
   project_path: Option<&str>,
   current_id: Option<&str>,
 ) -> Result<(), String> {
-  let project_path = project_path.unwrap_or_default();
-
   if display_name.is_empty() {
     return Err("Display name is required".to_string());
   }
 
-  if global_path.is_empty() {
-    return Err("Global path is required".to_string());
-  }
+  let candidate = AgentApp {
+    display_name: display_name.to_string(),
+    global_path: Some(global_path.to_string()),
+    project_path: project_path.map(str::to_string),
+    ..Default::default()
+  };
+  validate_agent_paths(&candidate)?;
 
-  if project_path.is_empty() {
-    return Err("Project path is required".to_string());
+  // Detected built-ins and every custom agent, other than the one being edited
+  let mut others: Vec<AgentApp> = local_agent_apps()
+    .into_iter()
+    .filter(|app| !app.is_user_custom)
+    .collect();
+  others.extend(load_user_agent_apps().unwrap_or_default());
+  others.retain(|app| Some(app.id.as_str()) != current_id);
+  if let Some(conflict) = find_agent_conflict(&candidate, &others) {
+    return Err(conflict);
   }
 
-  let local_apps = local_agent_apps();
+  Ok(())
+}
 
-  if local_apps
-    .iter()
-    .filter(|app| Some(app.id.as_str()) != current_id)
-    .any(|app| app.display_name.eq_ignore_ascii_case(display_name))
-  {
-    return Err(format!("Display name '{}' already exists", display_name));
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn app(id: &str, global_path: &str, is_user_custom: bool) -> AgentApp {
+    AgentApp {
+      id: id.to_string(),
+      display_name: id.to_uppercase(),
+      project_path: Some(".agents/skills".to_string()),
+      global_path: Some(global_path.to_string()),
+      is_user_custom,
+      ..Default::default()
+    }
   }
 
-  if local_apps
-    .iter()
-    .filter(|app| Some(app.id.as_str()) != current_id)
-    .any(|app| app.global_path.as_deref() == Some(global_path))
-  {
-    return Err(format!("Global path '{}' already exists", global_path));
+  #[test]
+  fn user_apps_override_built_ins_by_id_or_global_path() {
+    let registry = vec![
+      app("codex", "~/.codex/skills", false),
+      app("amp", "~/.config/agents/skills", false),
+      app("cursor", "~/.cursor/skills", false),
+    ];
+    let user_apps = vec![
+      app("codex", "~/.codex/custom", false),
+      app("mine", "~/.cursor/skills", false),
+      app("extra", "~/.extra/skills", false),
+      app("extra", "~/.extra/v2", false),
+      app("", "~/.broken", false),
+    ];
+
+    let merged = merge_agent_apps(registry, user_apps);
+    let summary: Vec<(&str, &str, bool)> = merged
+      .iter()
+      .map(|app| {
+        (
+          app.id.as_str(),
+          app.global_path.as_deref().unwrap(),
+          app.is_user_custom,
+        )
+      })
+      .collect();
+    assert_eq!(
+      summary,
+      vec![
+        ("amp", "~/.config/agents/skills", false),
+        ("codex", "~/.codex/custom", true),
+        ("mine", "~/.cursor/skills", true),
+        ("extra", "~/.extra/v2", true),
+      ]
+    );
   }
 
-  if local_apps
-    .iter()
-    .filter(|app| Some(app.id.as_str()) != current_id)
-    .any(|app| app.project_path.as_deref() == Some(project_path))
-  {
-    return Err(format!("Project path '{}' already exists", project_path));
+  #[test]
+  fn global_roots_stay_inside_home_and_out_of_credentials() {
+    assert!(check_global_root("~/.acme/skills").is_ok());
+    for path in [
+      "/",
+      "/etc/skills",
+      "~",
+      "~/../skills",
+      "~/.ssh",
+      "~/.aws/skills",
+      "${YOU_SKILL_TEST_UNSET_HOME}/skills",
+    ] {
+      assert!(check_global_root(path).is_err(), "{}", path);
+    }
+
+    let mut imported = app("acme", "~/.acme/skills", true);
+    imported.project_paths = vec!["../outside".to_string()];
+    imported.global_paths = vec!["/".to_string()];
+    let err = validate_agent_paths(&imported).unwrap_err();
+    assert!(err.contains("inside the home folder"), "{}", err);
+    imported.global_paths.clear();
+    let err = validate_agent_paths(&imported).unwrap_err();
+    assert!(err.contains("relative to the project"), "{}", err);
   }
 
-  if !check_global_path_exists(global_path) {
-    return Err(format!(
-      "Global path folder does not exist: {}",
-      global_path
-    ));
+  #[test]
+  fn slug_ids_are_stable() {
+    assert_eq!(
+      generate_id_from_display_name("  My AI Editor 2 "),
+      "my-ai-editor-2"
+    );
+    assert_eq!(
+      generate_id_from_display_name("Acme/Coder (beta)"),
+      "acme-coder-beta"
+    );
+    let fallback = generate_id_from_display_name("内部助手");
+    assert!(fallback.starts_with("agent-") && fallback.len() == "agent-".len() + 8);
+    assert_eq!(fallback, generate_id_from_display_name("内部助手"));
   }
 
-  Ok(())
+  #[test]
+  fn imports_dedupe_by_id_or_global_path_and_skip_conflicts() {
+    let built_ins = vec![app("cursor", "~/.cursor/skills", false)];
+    let built_in_ids = vec!["cursor".to_string(), "codex".to_string()];
+    let mut by_hand = app(
+      "5f0c9a62-5d0f-4c39-9a3e-1f3b7c1d2e4a",
+      "~/.acme/skills",
+      true,
+    );
+    by_hand.project_path = Some(".acme/skills".to_string());
+    let mut user_apps = vec![by_hand];
+
+    let mut acme = app("acme", "~/.acme/skills", true);
+    acme.project_path = Some("./.acme/skills".to_string());
+    // Each call is a separate import run
+    let mut import = |imported: AgentApp| {
+      let result = import_user_agent_app(
+        imported,
+        &built_in_ids,
+        &built_ins,
+        &mut user_apps,
+        &mut Vec::new(),
+      );
+      (result.status, result.message.unwrap_or_default())
+    };
+
+    // Created by hand at the same folder: replaced and given the slug id
+    let (status, message) = import(acme.clone());
+    assert_eq!(status, AgentAppImportStatus::Updated);
+    assert!(message.contains("5f0c9a62"));
+    assert_eq!(import(acme).0, AgentAppImportStatus::Unchanged);
+
+    let (status, message) = import(app("codex", "~/.codex2/skills", true));
+    assert_eq!(status, AgentAppImportStatus::Skipped);
+    assert!(message.contains("built-in"));
+
+    let mut clash = app("other", "~/.cursor/skills", true);
+    clash.project_path = Some(".other/skills".to_string());
+    let (status, message) = import(clash);
+    assert_eq!(status, AgentAppImportStatus::Skipped);
+    assert!(message.contains("already used by CURSOR"));
+
+    let mut clash = app("other", "~/.other/skills", true);
+    clash.project_path = Some(".acme/skills/".to_string());
+    let (status, message) = import(clash);
+    assert_eq!(status, AgentAppImportStatus::Skipped);
+    assert!(message.contains("Project path"));
+
+    let mut fresh = app("other", "~/.other/skills", true);
+    fresh.project_path = Some(".other/skills".to_string());
+    assert_eq!(import(fresh).0, AgentAppImportStatus::Added);
+
+    // Two definitions of one folder in the same file: the first one wins
+    let mut imported_ids = Vec::new();
+    let mut first = app("same-a", "~/.same/skills", true);
+    first.project_path = Some(".same-a/skills".to_string());
+    let mut second = app("same-b", "~/.same/skills", true);
+    second.project_path = Some(".same-b/skills".to_string());
+    for (imported, expected) in [
+      (first, AgentAppImportStatus::Added),
+      (second, AgentAppImportStatus::Skipped),
+    ] {
+      let result = import_user_agent_app(
+        imported,
+        &built_in_ids,
+        &built_ins,
+        &mut user_apps,
+        &mut imported_ids,
+      );
+      assert_eq!(result.status, expected);
+    }
+
+    let ids: Vec<&str> = user_apps.iter().map(|app| app.id.as_str()).collect();
+    assert_eq!(ids, vec!["acme", "other", "same-a"]);
+  }
 }
@@ -0,0 +1,305 @@
+use crate::models::{AgentApp, AgentDetectionKind, AgentDetectionMatch, InstallTarget};
+use crate::services::agent_apps_service;
+use crate::utils::path::{expand_home, expand_path};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Apps started from the Finder or a desktop launcher get a minimal PATH, so the
+/// usual install locations of CLI agents are searched as well.
+const EXTRA_BIN_DIRS: &[&str] = &[
+  "~/.local/bin",
+  "~/.npm-global/bin",
+  "~/.bun/bin",
+  "~/.volta/bin",
+  "~/.cargo/bin",
+  "/opt/homebrew/bin",
+  "/usr/local/bin",
+];
+const VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+/// The only arguments a detection rule may pass to an agent's CLI
+pub const VERSION_ARGS: &[&str] = &["--version", "-V", "-v", "version"];
+/// Shells and interpreters run whatever their arguments say, so they never count as an
+/// agent's binary.
+const DENIED_BINARIES: &[&str] = &[
+  "sh",
+  "bash",
+  "zsh",
+  "fish",
+  "dash",
+  "ksh",
+  "csh",
+  "tcsh",
+  "pwsh",
+  "powershell",
+  "cmd",
+  "python",
+  "node",
+  "ruby",
+  "perl",
+  "osascript",
+  "env",
+  "deno",
+  "bun",
+  "php",
+  "lua",
+];
+
+/// Binary modification time and the version it printed
+type CachedVersion = (Option<SystemTime>, Option<String>);
+
+// Version per binary, re-run only when the binary changes
+static VERSION_CACHE: Mutex<Option<HashMap<PathBuf, CachedVersion>>> = Mutex::new(None);
+
+/// Check whether `app` is installed and fill in `detected_by` and `detected_version`.
+///
+/// Agents with detection rules count as installed when one of their binaries is on
+/// PATH, one of their config files exists or one of their global skills folders exists;
+/// a leftover agent folder alone is not enough. Agents without rules, such as custom
+/// apps, keep the looser check where the folder holding the skills folder is enough.
+pub fn detect_agent(app: &AgentApp) -> Option<AgentApp> {
+  let rules = app.detection.clone().unwrap_or_default();
+  let binary = rules
+    .binaries
+    .iter()
+    .filter(|name| is_allowed_binary(name))
+    .find_map(|name| find_binary(name));
+  let global_roots = agent_apps_service::agent_skill_roots(app, &InstallTarget::Global);
+
+  let detected_by = if let Some(binary) = &binary {
+    Some(detection_match(AgentDetectionKind::Binary, binary))
+  } else if let Some(config) = rules
+    .config_files
+    .iter()
+    .filter_map(|file| expand_path(file).ok())
+    .find(|file| file.exists())
+  {
+    Some(detection_match(AgentDetectionKind::ConfigFile, &config))
+  } else if let Some(skills_folder) = global_roots.iter().find(|root| root.exists()) {
+    Some(detection_match(
+      AgentDetectionKind::SkillsFolder,
+      skills_folder,
+    ))
+  } else {
+    global_roots
+      .first()
+      .and_then(|preferred| preferred.parent())
+      .filter(|agent_folder| app.detection.is_none() && agent_folder.exists())
+      .map(|agent_folder| detection_match(AgentDetectionKind::AgentFolder, agent_folder))
+  }?;
+
+  let detected_version = match &binary {
+    Some(binary) if is_allowed_version_args(&rules.version_args) => {
+      cached_version(binary, &rules.version_args)
+    },
+    _ => None,
+  };
+  let global_root =
+    agent_apps_service::root_folder_from_install_target(app, &InstallTarget::Global)
+      .ok()
+      .map(|root| root.to_string_lossy().to_string());
+  Some(AgentApp {
+    detected_by: Some(detected_by),
+    detected_version,
+    global_root,
+    ..app.clone()
+  })
+}
+
+/// Whether `name` may be a detection binary: a bare name that is not a shell or an
+/// interpreter, also under versioned or Windows names such as `python3.12` or `bash.exe`
+pub fn is_allowed_binary(name: &str) -> bool {
+  if name.is_empty() || name.contains(['/', '\\']) {
+    return false;
+  }
+  let name = name.to_ascii_lowercase();
+  let stem = [".exe", ".cmd", ".bat"]
+    .iter()
+    .find_map(|extension| name.strip_suffix(extension))
+    .unwrap_or(&name)
+    .trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+  !DENIED_BINARIES.contains(&stem)
+}
+
+/// Whether `args` may be run to ask a binary for its version: some, and only
+/// arguments from `VERSION_ARGS`
+pub fn is_allowed_version_args(args: &[String]) -> bool {
+  !args.is_empty() && args.iter().all(|arg| VERSION_ARGS.contains(&arg.as_str()))
+}
+
+fn detection_match(kind: AgentDetectionKind, path: &Path) -> AgentDetectionMatch {
+  AgentDetectionMatch {
+    kind,
+    path: path.to_string_lossy().to_string(),
+  }
+}
+
+fn find_binary(name: &str) -> Option<PathBuf> {
+  let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+    .map(|path| std::env::split_paths(&path).collect())
+    .unwrap_or_default();
+  dirs.extend(EXTRA_BIN_DIRS.iter().map(|dir| expand_home(dir)));
+
+  let file_names: Vec<String> = if cfg!(windows) {
+    ["exe", "cmd", "bat"]
+      .iter()
+      .map(|extension| format!("{}.{}", name, extension))
+      .collect()
+  } else {
+    vec![name.to_string()]
+  };
+  dirs.iter().find_map(|dir| {
+    file_names.iter().find_map(|file_name| {
+      let candidate = dir.join(file_name);
+      is_executable(&candidate).then_some(candidate)
+    })
+  })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+  use std::os::unix::fs::PermissionsExt;
+  path
+    .metadata()
+    .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+    .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+  path.is_file()
+}
+
+fn cached_version(binary: &Path, args: &[String]) -> Option<String> {
+  let modified = binary.metadata().and_then(|meta| meta.modified()).ok();
+  if let Ok(cache) = VERSION_CACHE.lock() {
+    if let Some((cached_at, version)) = cache.as_ref().and_then(|cache| cache.get(binary)) {
+      if *cached_at == modified {
+        return version.clone();
+      }
+    }
+  }
+
+  let version = run_version_command(binary, args);
+  if let Ok(mut cache) = VERSION_CACHE.lock() {
+    cache
+      .get_or_insert_with(HashMap::new)
+      .insert(binary.to_path_buf(), (modified, version.clone()));
+  }
+  version
+}
+
+fn run_version_command(binary: &Path, args: &[String]) -> Option<String> {
+  let mut command = Command::new(binary);
+  command
+    .args(args)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+  #[cfg(windows)]
+  {
+    use std::os::windows::process::CommandExt;
+    // CREATE_NO_WINDOW, so no console flashes up
+    command.creation_flags(0x0800_0000);
+  }
+  let mut child = command.spawn().ok()?;
+
+  let started = Instant::now();
+  loop {
+    match child.try_wait() {
+      Ok(Some(_)) => break,
+      Ok(None) if started.elapsed() < VERSION_TIMEOUT => {
+        std::thread::sleep(Duration::from_millis(25));
+      },
+      _ => {
+        tracing::warn!("Version command timed out: {}", binary.display());
+        let _ = child.kill();
+        let _ = child.wait();
+        return None;
+      },
+    }
+  }
+
+  let mut output = String::new();
+  if let Some(mut stdout) = child.stdout.take() {
+    let _ = stdout.read_to_string(&mut output);
+  }
+  if let Some(mut stderr) = child.stderr.take() {
+    let _ = stderr.read_to_string(&mut output);
+  }
+  parse_version(&output)
+}
+
+/// First dotted version number in `output`, e.g. `1.0.98` from `1.0.98 (Claude Code)`
+/// or `0.21.0` from `codex-cli v0.21.0`.
+fn parse_version(output: &str) -> Option<String> {
+  output
+    .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | ',' | ';' | '"' | '\''))
+    .map(|word| word.strip_prefix('v').unwrap_or(word))
+    .map(|word| word.trim_end_matches(['.', ':']))
+    .find(|word| {
+      word.starts_with(|c: char| c.is_ascii_digit())
+        && word.contains('.')
+        && word
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+    })
+    .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_versions_from_common_outputs() {
+    assert_eq!(
+      parse_version("1.0.98 (Claude Code)\n").as_deref(),
+      Some("1.0.98")
+    );
+    assert_eq!(
+      parse_version("codex-cli v0.21.0").as_deref(),
+      Some("0.21.0")
+    );
+    assert_eq!(
+      parse_version("goose 1.7.0-beta.1").as_deref(),
+      Some("1.7.0-beta.1")
+    );
+    assert_eq!(parse_version("command not found"), None);
+    assert_eq!(parse_version("build 42"), None);
+  }
+
+  #[test]
+  fn rejects_shells_interpreters_and_other_arguments() {
+    for name in ["claude", "codex", "kiro-cli", "cursor-agent"] {
+      assert!(is_allowed_binary(name), "{}", name);
+    }
+    for name in [
+      "sh",
+      "bash",
+      "PowerShell.exe",
+      "python3",
+      "python3.12",
+      "node",
+      "osascript",
+      "env",
+      "/usr/bin/claude",
+      "",
+    ] {
+      assert!(!is_allowed_binary(name), "{}", name);
+    }
+
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    assert!(is_allowed_version_args(&args(&["--version"])));
+    assert!(is_allowed_version_args(&args(&["version"])));
+    assert!(!is_allowed_version_args(&args(&[])));
+    assert!(!is_allowed_version_args(&args(&["-c", "touch /tmp/x"])));
+    assert!(!is_allowed_version_args(&args(&[
+      "--version",
+      "--config=/tmp/x"
+    ])));
+  }
+}
@@ -0,0 +1,297 @@
+use crate::config::load_config;
+use crate::models::{AgentApp, AgentRegistry, AgentRegistrySource, AgentRegistryStatus};
+use crate::services::agent_apps_service;
+use crate::services::agent_detection_service::{
+  is_allowed_binary, is_allowed_version_args, VERSION_ARGS,
+};
+use crate::utils::path::check_env_vars;
+use reqwest::Client;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Built-in agent apps shipped with this build.
+const BUNDLED_REGISTRY: &str = include_str!("../../data/agent-registry.json");
+/// Registry schema this build understands
+pub const REGISTRY_VERSION: u32 = 1;
+const DEFAULT_REGISTRY_URL: &str =
+  "https://raw.githubusercontent.com/wanggang316/you-skill/main/src-tauri/data/agent-registry.json";
+
+// Effective registry, cleared by a refresh
+static REGISTRY: RwLock<Option<(AgentRegistry, AgentRegistrySource)>> = RwLock::new(None);
+
+/// Built-in agent apps from the effective registry, in registry order.
+pub fn registry_agent_apps() -> Vec<AgentApp> {
+  let (registry, _) = effective_registry();
+  registry.agents.into_iter().map(AgentApp::from).collect()
+}
+
+pub fn agent_registry_status() -> AgentRegistryStatus {
+  let (registry, source) = effective_registry();
+  AgentRegistryStatus {
+    source,
+    revision: registry.revision,
+    bundled_revision: bundled_registry().revision,
+    agent_count: registry.agents.len(),
+  }
+}
+
+/// Fetch the registry from `url` (or the configured source), validate it and keep it
+/// for later runs. A local file path works as well as an http(s) URL. The refreshed
+/// copy only takes over while its revision is newer than the bundled one, so an app
+/// update never falls back to older data.
+pub async fn refresh_agent_registry(url: Option<String>) -> Result<AgentRegistryStatus, String> {
+  let url = match url.filter(|url| !url.trim().is_empty()) {
+    Some(url) => url,
+    None => load_config()?
+      .agent_registry_url
+      .filter(|url| !url.trim().is_empty())
+      .unwrap_or_else(|| DEFAULT_REGISTRY_URL.to_string()),
+  };
+  let registry = parse_agent_registry(&read_registry_document(&url).await?)?;
+
+  let path = cached_registry_path()?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create config folder: {}", e))?;
+  }
+  let content = serde_json::to_string_pretty(&registry).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| format!("Failed to save agent registry: {}", e))?;
+
+  if let Ok(mut cache) = REGISTRY.write() {
+    *cache = None;
+  }
+  agent_apps_service::refresh_local_agent_apps();
+  Ok(agent_registry_status())
+}
+
+/// Read a registry document from an http(s) URL or a local file.
+pub async fn read_registry_document(location: &str) -> Result<String, String> {
+  if location.starts_with("http://") || location.starts_with("https://") {
+    fetch_registry(location).await
+  } else {
+    fs::read_to_string(location).map_err(|e| format!("Failed to read agent registry: {}", e))
+  }
+}
+
+/// Parse a registry document and check it against the schema: a known version, and
+/// agents with unique ids made of lowercase letters, digits and dashes, a display
+/// name, at least one skills folder, global paths that start with `~/`, `${VAR}` or
+/// `/` and only hold well-formed variable references, relative project paths that
+/// stay inside the project, and detection binaries given as bare names that are not
+/// shells or interpreters, with version arguments from `VERSION_ARGS`.
+pub fn parse_agent_registry(content: &str) -> Result<AgentRegistry, String> {
+  let registry: AgentRegistry =
+    serde_json::from_str(content).map_err(|e| format!("Invalid agent registry: {}", e))?;
+  if registry.version != REGISTRY_VERSION {
+    return Err(format!(
+      "Unsupported agent registry version {} (expected {})",
+      registry.version, REGISTRY_VERSION
+    ));
+  }
+
+  let mut errors = Vec::new();
+  let mut ids = HashSet::new();
+  for (index, agent) in registry.agents.iter().enumerate() {
+    let label = if agent.id.is_empty() {
+      format!("agents[{}]", index)
+    } else {
+      agent.id.clone()
+    };
+    if !is_valid_agent_id(&agent.id) {
+      errors.push(format!("{}: invalid id '{}'", label, agent.id));
+    } else if !ids.insert(agent.id.as_str()) {
+      errors.push(format!("{}: duplicate id", label));
+    }
+    if agent.display_name.trim().is_empty() {
+      errors.push(format!("{}: display_name is required", label));
+    }
+    if agent.global_path.is_none() && agent.project_path.is_none() {
+      errors.push(format!("{}: needs a global_path or project_path", label));
+    }
+    for global_path in agent.global_path.iter().chain(&agent.global_paths) {
+      if let Err(err) = check_env_vars(global_path) {
+        errors.push(format!("{}: {}", label, err));
+      } else if !global_path.starts_with("~/")
+        && !global_path.starts_with("${")
+        && !Path::new(global_path).is_absolute()
+      {
+        errors.push(format!(
+          "{}: global path '{}' must start with ~/, a variable or be absolute",
+          label, global_path
+        ));
+      }
+    }
+    for project_path in agent.project_path.iter().chain(&agent.project_paths) {
+      if !is_project_relative(project_path) {
+        errors.push(format!(
+          "{}: project path '{}' must be relative to the project",
+          label, project_path
+        ));
+      }
+    }
+    if let Some(detection) = &agent.detection {
+      for binary in &detection.binaries {
+        if binary.is_empty() || binary.contains(['/', '\\']) {
+          errors.push(format!(
+            "{}: binary '{}' must be a name looked up on PATH",
+            label, binary
+          ));
+        } else if !is_allowed_binary(binary) {
+          errors.push(format!(
+            "{}: binary '{}' is a shell or interpreter",
+            label, binary
+          ));
+        }
+      }
+      if !detection.version_args.is_empty() {
+        if detection.binaries.is_empty() {
+          errors.push(format!("{}: version_args need a binary", label));
+        }
+        if !is_allowed_version_args(&detection.version_args) {
+          errors.push(format!(
+            "{}: version_args must be one of {}",
+            label,
+            VERSION_ARGS.join(", ")
+          ));
+        }
+      }
+    }
+  }
+  if !errors.is_empty() {
+    return Err(format!("Invalid agent registry:\n{}", errors.join("\n")));
+  }
+  Ok(registry)
+}
+
+fn effective_registry() -> (AgentRegistry, AgentRegistrySource) {
+  if let Ok(cache) = REGISTRY.read() {
+    if let Some(cached) = cache.as_ref() {
+      return cached.clone();
+    }
+  }
+
+  let bundled = bundled_registry();
+  let effective = match read_cached_registry() {
+    Some(refreshed) if refreshed.revision > bundled.revision => {
+      (refreshed, AgentRegistrySource::Refreshed)
+    },
+    _ => (bundled, AgentRegistrySource::Bundled),
+  };
+  if let Ok(mut cache) = REGISTRY.write() {
+    *cache = Some(effective.clone());
+  }
+  effective
+}
+
+fn bundled_registry() -> AgentRegistry {
+  parse_agent_registry(BUNDLED_REGISTRY).expect("bundled agent registry is valid")
+}
+
+fn read_cached_registry() -> Option<AgentRegistry> {
+  let path = cached_registry_path().ok()?;
+  let content = fs::read_to_string(&path).ok()?;
+  match parse_agent_registry(&content) {
+    Ok(registry) => Some(registry),
+    Err(err) => {
+      tracing::warn!("Ignoring refreshed agent registry: {}", err);
+      None
+    },
+  }
+}
+
+pub fn cached_registry_path() -> Result<PathBuf, String> {
+  let config_dir = dirs_next::config_dir().ok_or("无法获取配置目录")?;
+  Ok(config_dir.join("youskill").join("agent-registry.json"))
+}
+
+async fn fetch_registry(url: &str) -> Result<String, String> {
+  let client = Client::builder()
+    .timeout(Duration::from_secs(10))
+    .build()
+    .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+  let response = client
+    .get(url)
+    .send()
+    .await
+    .map_err(|e| format!("Network connection failed: {}", e))?;
+  if !response.status().is_success() {
+    return Err(format!("Registry returned error: {}", response.status()));
+  }
+  response
+    .text()
+    .await
+    .map_err(|e| format!("Failed to read agent registry: {}", e))
+}
+
+fn is_valid_agent_id(id: &str) -> bool {
+  !id.is_empty()
+    && id
+      .chars()
+      .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Whether `path` names a folder inside the project it is joined to
+pub fn is_project_relative(path: &str) -> bool {
+  let path = Path::new(path);
+  !path.as_os_str().is_empty()
+    && path
+      .components()
+      .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bundled_registry_passes_validation() {
+    let registry = parse_agent_registry(BUNDLED_REGISTRY).unwrap();
+    assert!(registry
+      .agents
+      .iter()
+      .any(|agent| agent.id == "claude-code"));
+  }
+
+  #[test]
+  fn rejects_unknown_versions_and_bad_entries() {
+    let err = parse_agent_registry(r#"{"version": 2, "revision": 1, "agents": []}"#).unwrap_err();
+    assert!(err.contains("Unsupported"));
+
+    let err = parse_agent_registry(
+      r#"{"version": 1, "revision": 1, "agents": [
+        {"id": "a", "display_name": "A", "global_path": "~/.a/skills"},
+        {"id": "a", "display_name": "A2", "global_path": "relative/skills"},
+        {"id": "Bad Id", "display_name": " ", "project_path": "../outside"},
+        {"id": "c", "display_name": "C"},
+        {"id": "d", "display_name": "D", "global_path": "~/.d/skills",
+         "detection": {"binaries": ["/usr/bin/d"], "config_files": [], "version_args": []}},
+        {"id": "e", "display_name": "E", "global_path": "~/.e/skills",
+         "detection": {"version_args": ["--version"]}},
+        {"id": "f", "display_name": "F", "global_path": "${F_HOME:-~/.f}/skills",
+         "global_paths": ["${F_HOME/skills"], "project_paths": ["/abs/skills"]},
+        {"id": "g", "display_name": "G", "global_path": "~/.g/skills",
+         "detection": {"binaries": ["bash", "python3"], "version_args": ["-c", "id"]}}
+      ]}"#,
+    )
+    .unwrap_err();
+    for expected in [
+      "a: duplicate id",
+      "must start with ~/, a variable",
+      "f: Unterminated variable reference in '${F_HOME/skills'",
+      "project path '/abs/skills' must be relative",
+      "invalid id 'Bad Id'",
+      "display_name is required",
+      "must be relative to the project",
+      "c: needs a global_path or project_path",
+      "d: binary '/usr/bin/d' must be a name",
+      "e: version_args need a binary",
+      "g: binary 'bash' is a shell or interpreter",
+      "g: binary 'python3' is a shell or interpreter",
+      "g: version_args must be one of --version, -V, -v, version",
+    ] {
+      assert!(err.contains(expected), "missing '{}' in {}", expected, err);
+    }
+  }
+}
@@ -1,8 +1,43 @@
-use crate::config::{load_config, save_config};
+use crate::config::{
+  config_path, load_config, save_config, AppConfig, BackupMode, BackupRetention,
+};
+use crate::models::{InstallMethod, InstallScope, InstallTarget, LocalSkill};
+use crate::services::agent_apps_service::{
+  refresh_local_agent_apps, resolve_selected_apps_paths, user_agent_apps_path,
+};
+use crate::services::backup_store_service;
+use crate::services::native_skill_lock_service::{
+  add_skill_to_native_lock, native_skill_lock_path,
+};
+use crate::services::project_skill_lock_service::project_lock_path;
+use crate::services::skill_lock_service::{
+  add_skill_to_lock, read_skill_lock_internal, skill_lock_path, SkillLockEntry, SkillLockFile,
+};
+use crate::services::skill_service;
+use crate::services::user_projects_service::{
+  list_user_projects, update_user_project, user_projects_path,
+};
+use crate::services::workspace_service::{self, WorkspaceSession};
+use crate::utils::folder::{replace_dir_sync, FolderHelper};
 use crate::utils::path::canonical_skills_root;
-use chrono::Local;
+use crate::utils::zip::ZipHelper;
+use chrono::{Datelike, Local, NaiveDateTime, TimeZone};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+const BACKUP_FILE_PREFIX: &str = "skills_backup_";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S";
+const BACKUP_MANIFEST_FILE: &str = "backup-manifest.json";
+const BACKUP_MANIFEST_VERSION: i64 = 2;
+/// Everything besides the global skills sits under this folder in a backup.
+const BACKUP_EXTRA_DIR: &str = ".youskill";
+const BACKUP_STATE_DIR: &str = "state";
+const BACKUP_PROJECTS_DIR: &str = "projects";
+const BACKUP_PROJECT_SKILLS_DIR: &str = "skills";
+const BACKUP_AGENT_SKILLS_DIR: &str = "agent-skills";
+const SKILL_MD_FILE_NAME: &str = "SKILL.md";
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct BackupResult {
@@ -12,19 +47,144 @@ pub struct BackupResult {
   pub backup_time: Option<String>,
 }
 
-/// Backup skills directory to a ZIP file
-pub fn backup_skills_sync(backup_folder: String) -> Result<BackupResult, String> {
-  let skills_path = canonical_skills_root()?;
+/// Stored at the root of every backup so a restore can re-link skills to agents and put
+/// projects and app state back. Records which scopes and agent links were captured.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
+pub struct BackupManifest {
+  pub version: i64,
+  pub created_at: String,
+  /// Global skills in `~/.agents/skills`, stored at the root of the backup
+  #[serde(default)]
+  pub skills: Vec<BackupSkillRecord>,
+  /// Global skills that only exist inside an agent's skills folder
+  #[serde(default)]
+  pub agent_skills: Vec<BackupSkillRecord>,
+  #[serde(default)]
+  pub projects: Vec<BackupProjectRecord>,
+  /// Lock, agent app, project and settings files, by file name under `.youskill/state`
+  #[serde(default)]
+  pub state_files: Vec<String>,
+}
 
-  if !skills_path.exists() {
-    return Ok(BackupResult {
-      success: false,
-      message: "Skills 目录不存在".to_string(),
-      backup_path: None,
-      backup_time: None,
-    });
-  }
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct BackupSkillRecord {
+  pub name: String,
+  #[serde(default)]
+  pub agent_apps: Vec<BackupAgentLink>,
+  #[serde(default)]
+  pub lock: Option<SkillLockEntry>,
+  /// Folder of the skill in the backup, for skills kept outside their scope's skills folder
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub path: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct BackupProjectRecord {
+  pub name: String,
+  /// Project root on the machine the backup was made on
+  pub path: String,
+  /// Folder with the project's skills (`skills/`) and lock file in the backup
+  pub backup_dir: String,
+  #[serde(default)]
+  pub skills: Vec<BackupSkillRecord>,
+  #[serde(default)]
+  pub agent_skills: Vec<BackupSkillRecord>,
+  #[serde(default)]
+  pub has_lock: bool,
+}
+
+/// A file or folder captured by a backup, and where it goes inside the backup
+#[derive(Debug, Clone)]
+pub struct BackupSource {
+  pub path: PathBuf,
+  /// `/`-separated; empty for the backup root
+  pub archive_path: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct BackupAgentLink {
+  pub id: String,
+  pub method: InstallMethod,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct BackupInfo {
+  pub file_name: String,
+  pub path: String,
+  pub mode: BackupMode,
+  /// ZIP file size, or the size of the backed up files for a deduplicated backup
+  pub size: u64,
+  pub backup_time: String,
+  pub skill_count: usize,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
+pub struct BackupRestorePreview {
+  /// In the backup but not installed
+  pub added: Vec<String>,
+  /// Installed with different content than the backup
+  pub overwritten: Vec<String>,
+  /// Installed but missing from the backup
+  pub removed: Vec<String>,
+  pub unchanged: Vec<String>,
+  /// Global skills captured from agent folders only
+  #[serde(default)]
+  pub agent_skills: Vec<String>,
+  /// Project roots captured in the backup
+  #[serde(default)]
+  pub projects: Vec<String>,
+  /// Backed up project roots with no folder on this machine, only restored when mapped
+  /// to a new root
+  #[serde(default)]
+  pub missing_projects: Vec<String>,
+  #[serde(default)]
+  pub state_files: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
+pub struct BackupPruneResult {
+  /// Backups the retention rules dropped, deleted unless this was a dry run
+  pub removed: Vec<BackupInfo>,
+  pub kept: usize,
+  /// Bytes freed on disk; unreferenced objects are only counted when actually deleted
+  pub freed_bytes: u64,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct RestoreBackupRequest {
+  pub backup_path: String,
+  /// Skills to restore; every skill in the backup when empty
+  #[serde(default)]
+  pub skills: Vec<String>,
+  /// Also delete installed skills that are not in the backup
+  #[serde(default)]
+  pub remove_missing: bool,
+  /// Also put back lock files, custom agent apps, registered projects and settings
+  #[serde(default)]
+  pub include_state: bool,
+  /// Also restore the skills and lock file of every backed up project whose folder exists
+  #[serde(default)]
+  pub include_projects: bool,
+  /// New root of each backed up project that lives elsewhere on this machine, keyed by
+  /// its root in the backup
+  #[serde(default)]
+  pub project_roots: BTreeMap<String, String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
+pub struct BackupRestoreResult {
+  pub restored: Vec<String>,
+  pub removed: Vec<String>,
+  pub errors: Vec<String>,
+  #[serde(default)]
+  pub projects: Vec<String>,
+  #[serde(default)]
+  pub state_files: Vec<String>,
+}
 
+/// Backup global and project skills, locks and app state to a ZIP file, or into the
+/// deduplicating store
+pub fn backup_skills_sync(backup_folder: String, mode: BackupMode) -> Result<BackupResult, String> {
   // Create backup directory
   let backup_path = Path::new(&backup_folder);
   if !backup_path.exists() {
@@ -33,22 +193,29 @@ pub fn backup_skills_sync(backup_folder: String) -> Result<BackupResult, String>
 
   // Generate timestamped filename (yyyyMMddHHmmss)
   let now = Local::now();
-  let timestamp = now.format("%Y%m%d%H%M%S").to_string();
-  let filename = format!("skills_backup_{}.zip", timestamp);
-  let backup_file_path = backup_path.join(&filename);
-
-  // Create ZIP file
-  let file = fs::File::create(&backup_file_path).map_err(|e| format!("创建备份文件失败: {}", e))?;
-  let mut zip = zip::ZipWriter::new(file);
-  let options: zip::write::FileOptions<()> =
-    zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
-
-  // Add skills directory to ZIP
-  add_dir_to_zip(&mut zip, &skills_path, &skills_path, options)?;
-
-  zip
-    .finish()
-    .map_err(|e| format!("完成 ZIP 文件失败: {}", e))?;
+  let timestamp = now.format(BACKUP_TIMESTAMP_FORMAT).to_string();
+  let file_stem = format!("{}{}", BACKUP_FILE_PREFIX, timestamp);
+  // Holds files the backup stores in a changed form, such as the config without secrets.
+  let staging = workspace_service::create_session("backup")?;
+  let (manifest, sources) = collect_backup(&now.to_rfc3339(), staging.path())?;
+  if sources.is_empty() {
+    return Ok(BackupResult {
+      success: false,
+      message: "没有可备份的内容".to_string(),
+      backup_path: None,
+      backup_time: None,
+    });
+  }
+  let backup_file_path = match mode {
+    BackupMode::Zip => {
+      let backup_file_path = backup_path.join(format!("{}.zip", file_stem));
+      write_zip_backup(&backup_file_path, &sources, &manifest)?;
+      backup_file_path
+    },
+    BackupMode::Dedup => {
+      backup_store_service::write_snapshot(backup_path, &file_stem, &sources, manifest)?
+    },
+  };
 
   // Format backup time for display
   let backup_time_str = now.format("%Y-%m-%d %H:%M:%S").to_string();
@@ -61,6 +228,49 @@ pub fn backup_skills_sync(backup_folder: String) -> Result<BackupResult, String>
   })
 }
 
+fn write_zip_backup(
+  backup_file_path: &Path,
+  sources: &[BackupSource],
+  manifest: &BackupManifest,
+) -> Result<(), String> {
+  // Create ZIP file
+  let file = fs::File::create(backup_file_path).map_err(|e| format!("创建备份文件失败: {}", e))?;
+  let mut zip = zip::ZipWriter::new(file);
+  let options: zip::write::FileOptions<()> =
+    zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+  for source in sources {
+    if source.path.is_dir() {
+      if !source.archive_path.is_empty() {
+        zip
+          .add_directory(source.archive_path.as_str(), options)
+          .map_err(|e| format!("添加目录到 ZIP 失败: {}", e))?;
+      }
+      ZipHelper::add_dir(&mut zip, &source.path, &source.archive_path, options)?;
+    } else {
+      let mut file = fs::File::open(&source.path).map_err(|e| format!("打开文件失败: {}", e))?;
+      zip
+        .start_file(source.archive_path.as_str(), options)
+        .map_err(|e| format!("添加文件到 ZIP 失败: {}", e))?;
+      io::copy(&mut file, &mut zip).map_err(|e| format!("写入文件到 ZIP 失败: {}", e))?;
+    }
+  }
+
+  let manifest_content =
+    serde_json::to_string_pretty(manifest).map_err(|e| format!("序列化备份清单失败: {}", e))?;
+  zip
+    .start_file(BACKUP_MANIFEST_FILE, options)
+    .map_err(|e| format!("添加文件到 ZIP 失败: {}", e))?;
+  zip
+    .write_all(manifest_content.as_bytes())
+    .map_err(|e| format!("写入文件到 ZIP 失败: {}", e))?;
+
+  zip
+    .finish()
+    .map_err(|e| format!("完成 ZIP 文件失败: {}", e))?;
+  Ok(())
+}
+
 pub fn open_backup_folder(path: String) -> Result<(), String> {
   let backup_path = Path::new(&path);
   if !backup_path.exists() {
@@ -95,7 +305,11 @@ pub fn open_backup_folder(path: String) -> Result<(), String> {
 }
 
 pub async fn backup_skills(backup_folder: String) -> Result<BackupResult, String> {
-  let result = tokio::task::spawn_blocking(move || backup_skills_sync(backup_folder))
+  let config = load_config()?;
+  let mode = config.backup_mode;
+  let retention = config.backup_retention;
+  let folder = backup_folder.clone();
+  let result = tokio::task::spawn_blocking(move || backup_skills_sync(folder, mode))
     .await
     .map_err(|e| format!("备份任务执行失败: {}", e))??;
 
@@ -104,41 +318,908 @@ pub async fn backup_skills(backup_folder: String) -> Result<BackupResult, String
       config.last_backup_time = result.backup_time.clone();
       let _ = save_config(&config);
     }
+    if retention.is_enabled() {
+      let pruned =
+        tokio::task::spawn_blocking(move || prune_backups_in(&backup_folder, &retention, false))
+          .await
+          .map_err(|e| format!("清理备份任务执行失败: {}", e))?;
+      if let Err(err) = pruned {
+        tracing::warn!("Failed to prune backups: {}", err);
+      }
+    }
   }
 
   Ok(result)
 }
 
-fn add_dir_to_zip<P: AsRef<Path>>(
-  zip: &mut zip::ZipWriter<fs::File>,
-  base_path: P,
-  current_path: P,
-  options: zip::write::FileOptions<()>,
-) -> Result<(), String> {
-  let base_path = base_path.as_ref();
-  let current_path = current_path.as_ref();
+/// List backup ZIPs and deduplicated snapshots in `backup_folder` (or the configured
+/// folder), newest first
+pub fn list_backups(backup_folder: Option<String>) -> Result<Vec<BackupInfo>, String> {
+  match resolve_backup_folder(backup_folder)? {
+    Some(folder) => list_backups_in(Path::new(&folder)),
+    None => Ok(Vec::new()),
+  }
+}
 
-  for entry in fs::read_dir(current_path).map_err(|e| format!("读取目录失败: {}", e))? {
-    let entry = entry.map_err(|e| format!("读取条目失败: {}", e))?;
+/// Delete the backups the configured retention rules do not keep, then drop store objects
+/// no remaining snapshot uses. `dry_run` only reports what would go.
+pub fn prune_backups(
+  backup_folder: Option<String>,
+  dry_run: bool,
+) -> Result<BackupPruneResult, String> {
+  let retention = load_config()?.backup_retention;
+  if !retention.is_enabled() {
+    return Err("未配置备份保留规则".to_string());
+  }
+  match resolve_backup_folder(backup_folder)? {
+    Some(folder) => prune_backups_in(&folder, &retention, dry_run),
+    None => Err("未设置备份目录".to_string()),
+  }
+}
+
+fn prune_backups_in(
+  backup_folder: &str,
+  retention: &BackupRetention,
+  dry_run: bool,
+) -> Result<BackupPruneResult, String> {
+  let backup_path = Path::new(backup_folder);
+  let backups = list_backups_in(backup_path)?;
+  let times: Vec<Option<NaiveDateTime>> = backups
+    .iter()
+    .map(|backup| backup_timestamp(&backup.file_name))
+    .collect();
+  let retained = retained_backups(&times, retention);
+
+  let mut result = BackupPruneResult::default();
+  for (index, backup) in backups.into_iter().enumerate() {
+    if retained.contains(&index) {
+      result.kept += 1;
+      continue;
+    }
+    if !dry_run {
+      let size = fs::metadata(&backup.path)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+      fs::remove_file(&backup.path).map_err(|e| format!("删除备份失败: {}", e))?;
+      result.freed_bytes += size;
+    } else if backup.mode == BackupMode::Zip {
+      result.freed_bytes += backup.size;
+    }
+    result.removed.push(backup);
+  }
+
+  if !dry_run {
+    let (_, freed) = backup_store_service::collect_garbage(backup_path)?;
+    result.freed_bytes += freed;
+  }
+  Ok(result)
+}
+
+/// Indices of the backups that survive `retention`. `times` is ordered newest first;
+/// backups without a readable time are always kept.
+fn retained_backups(
+  times: &[Option<NaiveDateTime>],
+  retention: &BackupRetention,
+) -> BTreeSet<usize> {
+  let mut retained: BTreeSet<usize> = times
+    .iter()
+    .enumerate()
+    .filter(|(_, time)| time.is_none())
+    .map(|(index, _)| index)
+    .collect();
+  let dated: Vec<(usize, NaiveDateTime)> = times
+    .iter()
+    .enumerate()
+    .filter_map(|(index, time)| time.map(|time| (index, time)))
+    .collect();
+
+  retained.extend(
+    dated
+      .iter()
+      .take(retention.keep_last as usize)
+      .map(|(index, _)| *index),
+  );
+  // Newest backup of each period, for as many distinct periods as the rule allows
+  let mut keep_per_period = |limit: u32, period: &dyn Fn(&NaiveDateTime) -> (i32, u32)| {
+    let mut last_period = None;
+    let mut periods = 0;
+    for (index, time) in &dated {
+      if periods >= limit {
+        break;
+      }
+      let current = period(time);
+      if last_period != Some(current) {
+        last_period = Some(current);
+        periods += 1;
+        retained.insert(*index);
+      }
+    }
+  };
+  keep_per_period(retention.keep_daily, &|time| (time.year(), time.ordinal()));
+  keep_per_period(retention.keep_weekly, &|time| {
+    let week = time.iso_week();
+    (week.year(), week.week())
+  });
+  retained
+}
+
+fn resolve_backup_folder(backup_folder: Option<String>) -> Result<Option<String>, String> {
+  match backup_folder.filter(|folder| !folder.trim().is_empty()) {
+    Some(folder) => Ok(Some(folder)),
+    None => Ok(load_config()?.backup_folder),
+  }
+}
+
+fn list_backups_in(backup_path: &Path) -> Result<Vec<BackupInfo>, String> {
+  if !backup_path.is_dir() {
+    return Ok(Vec::new());
+  }
+
+  let mut backups = Vec::new();
+  for entry in fs::read_dir(backup_path).map_err(|e| format!("读取目录失败: {}", e))? {
+    let Ok(entry) = entry else {
+      continue;
+    };
     let path = entry.path();
-    let name = path
-      .strip_prefix(base_path)
-      .map_err(|_| "路径处理失败".to_string())?
-      .to_string_lossy()
-      .to_string();
+    let file_name = entry.file_name().to_string_lossy().to_string();
+    if !path.is_file() || !file_name.starts_with(BACKUP_FILE_PREFIX) || !file_name.ends_with(".zip")
+    {
+      continue;
+    }
+    let metadata = entry
+      .metadata()
+      .map_err(|e| format!("读取文件信息失败: {}", e))?;
+    let skill_count = match backup_skill_names(&path) {
+      Ok(names) => names.len(),
+      Err(err) => {
+        tracing::warn!("Skipping unreadable backup {}: {}", file_name, err);
+        continue;
+      },
+    };
+    backups.push(BackupInfo {
+      backup_time: backup_time_from_file(&file_name, &metadata),
+      file_name,
+      path: path.to_string_lossy().to_string(),
+      mode: BackupMode::Zip,
+      size: metadata.len(),
+      skill_count,
+    });
+  }
 
-    if path.is_file() {
-      let mut file = fs::File::open(&path).map_err(|e| format!("打开文件失败: {}", e))?;
-      zip
-        .start_file(name, options)
-        .map_err(|e| format!("添加文件到 ZIP 失败: {}", e))?;
-      std::io::copy(&mut file, zip).map_err(|e| format!("写入文件到 ZIP 失败: {}", e))?;
-    } else if path.is_dir() {
-      zip
-        .add_directory(name.clone(), options)
-        .map_err(|e| format!("添加目录到 ZIP 失败: {}", e))?;
-      add_dir_to_zip(zip, base_path, &path, options)?;
+  for path in backup_store_service::list_snapshot_paths(backup_path)? {
+    let file_name = path
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let snapshot = match backup_store_service::read_snapshot(&path) {
+      Ok(snapshot) => snapshot,
+      Err(err) => {
+        tracing::warn!("Skipping unreadable backup {}: {}", file_name, err);
+        continue;
+      },
+    };
+    let metadata = fs::metadata(&path).map_err(|e| format!("读取文件信息失败: {}", e))?;
+    backups.push(BackupInfo {
+      backup_time: backup_time_from_file(&file_name, &metadata),
+      file_name,
+      path: path.to_string_lossy().to_string(),
+      mode: BackupMode::Dedup,
+      size: snapshot.total_size(),
+      skill_count: snapshot.skill_names().len(),
+    });
+  }
+
+  // Newest first, whichever kind of backup they are
+  backups.sort_by(|a, b| backup_stem(&b.file_name).cmp(backup_stem(&a.file_name)));
+  Ok(backups)
+}
+
+/// Compare a backup with `~/.agents/skills` without changing anything
+pub fn preview_backup_restore(backup_path: String) -> Result<BackupRestorePreview, String> {
+  let session = extract_backup(&backup_path)?;
+  let mut preview = compare_with_installed(session.path())?;
+  if let Some(manifest) = read_backup_manifest(session.path()) {
+    preview.agent_skills = manifest
+      .agent_skills
+      .into_iter()
+      .map(|record| record.name)
+      .collect();
+    preview.projects = manifest
+      .projects
+      .into_iter()
+      .map(|project| project.path)
+      .collect();
+    preview.missing_projects = preview
+      .projects
+      .iter()
+      .filter(|path| !Path::new(path).is_dir())
+      .cloned()
+      .collect();
+    preview.state_files = manifest.state_files;
+  }
+  Ok(preview)
+}
+
+pub fn restore_backup(request: RestoreBackupRequest) -> Result<BackupRestoreResult, String> {
+  let session = extract_backup(&request.backup_path)?;
+  restore_from_extracted(session.path(), &request)
+}
+
+fn restore_from_extracted(
+  extract_dir: &Path,
+  request: &RestoreBackupRequest,
+) -> Result<BackupRestoreResult, String> {
+  let manifest = read_backup_manifest(extract_dir);
+  let preview = compare_with_installed(extract_dir)?;
+  let available: BTreeSet<String> = extracted_skill_names(extract_dir)?.into_iter().collect();
+  let agent_only: BTreeSet<&str> = manifest
+    .iter()
+    .flat_map(|manifest| manifest.agent_skills.iter())
+    .map(|record| record.name.as_str())
+    .collect();
+
+  let selected: Vec<String> = if request.skills.is_empty() {
+    available.iter().cloned().collect()
+  } else {
+    let missing: Vec<&str> = request
+      .skills
+      .iter()
+      .filter(|name| !available.contains(*name) && !agent_only.contains(name.as_str()))
+      .map(|name| name.as_str())
+      .collect();
+    if !missing.is_empty() {
+      return Err(format!("备份中不存在技能: {}", missing.join(", ")));
+    }
+    request
+      .skills
+      .iter()
+      .filter(|name| available.contains(*name))
+      .cloned()
+      .collect()
+  };
+
+  let mut result = BackupRestoreResult::default();
+  // State goes first: restored agent apps and projects are what the links below point at.
+  if request.include_state {
+    if let Some(manifest) = &manifest {
+      restore_state_files(extract_dir, &manifest.state_files, &mut result);
+      move_registered_projects(&request.project_roots, &mut result);
     }
   }
+
+  let skills_root = canonical_skills_root()?;
+  fs::create_dir_all(&skills_root).map_err(|e| format!("创建目录失败: {}", e))?;
+  let github_lock = read_skill_lock_internal().unwrap_or_default();
+
+  for name in selected {
+    let record = manifest
+      .as_ref()
+      .and_then(|manifest| manifest.skills.iter().find(|record| record.name == name));
+    match restore_skill(extract_dir, &skills_root, &name, record, &github_lock) {
+      Ok(()) => result.restored.push(name),
+      Err(err) => result.errors.push(format!("{}: {}", name, err)),
+    }
+  }
+
+  if let Some(manifest) = &manifest {
+    for record in &manifest.agent_skills {
+      if !request.skills.is_empty() && !request.skills.contains(&record.name) {
+        continue;
+      }
+      match restore_agent_skill(extract_dir, record, &InstallTarget::Global) {
+        Ok(()) => result.restored.push(record.name.clone()),
+        Err(err) => result.errors.push(format!("{}: {}", record.name, err)),
+      }
+    }
+
+    if request.include_projects {
+      for project in &manifest.projects {
+        let root = request
+          .project_roots
+          .get(&project.path)
+          .unwrap_or(&project.path);
+        let errors = restore_project(extract_dir, project, Path::new(root));
+        if errors.is_empty() {
+          result.projects.push(root.clone());
+        }
+        result.errors.extend(errors);
+      }
+    }
+  }
+
+  if request.remove_missing {
+    for name in preview.removed {
+      match skill_service::delete_skill(name.clone(), InstallScope::Global, None) {
+        Ok(()) => result.removed.push(name),
+        Err(err) => result.errors.push(format!("{}: {}", name, err)),
+      }
+    }
+  }
+
+  Ok(result)
+}
+
+fn restore_skill(
+  extract_dir: &Path,
+  skills_root: &Path,
+  name: &str,
+  record: Option<&BackupSkillRecord>,
+  github_lock: &SkillLockFile,
+) -> Result<(), String> {
+  replace_dir_sync(&extract_dir.join(name), &skills_root.join(name))?;
+
+  match record.and_then(|record| record.lock.clone()) {
+    Some(entry) => add_skill_to_lock(name.to_string(), entry)?,
+    None if !github_lock.skills.contains_key(name) => add_skill_to_native_lock(name.to_string())?,
+    None => {},
+  }
+
+  // Backups made before manifests existed carry no links; existing symlinks into
+  // the canonical folder keep working once the content is back.
+  match record {
+    Some(record) => link_recorded_apps(name, record, &InstallTarget::Global),
+    None => Ok(()),
+  }
+}
+
+fn link_recorded_apps(
+  name: &str,
+  record: &BackupSkillRecord,
+  install_target: &InstallTarget,
+) -> Result<(), String> {
+  let links: Vec<(String, InstallMethod)> = record
+    .agent_apps
+    .iter()
+    .map(|link| (link.id.clone(), link.method.clone()))
+    .collect();
+  if links.is_empty() {
+    return Ok(());
+  }
+  skill_service::link_skill_to_apps(name, &links, install_target)
+}
+
+/// Copy a skill that was only in agent folders back into each of those agents' folders.
+fn restore_agent_skill(
+  extract_dir: &Path,
+  record: &BackupSkillRecord,
+  install_target: &InstallTarget,
+) -> Result<(), String> {
+  let source = record
+    .path
+    .as_deref()
+    .and_then(|path| backup_path_in(extract_dir, path))
+    .ok_or("备份清单缺少技能路径")?;
+  let name = backup_path_in(Path::new(""), &record.name).ok_or("备份清单包含非法技能名")?;
+  let mut errors = Vec::new();
+  for link in &record.agent_apps {
+    match resolve_selected_apps_paths(std::slice::from_ref(&link.id), install_target) {
+      Ok(selected) => {
+        for app in selected {
+          if let Err(err) = replace_dir_sync(&source, &app.install_root.join(&name)) {
+            errors.push(err);
+          }
+        }
+      },
+      Err(err) => errors.push(err),
+    }
+  }
+  if !errors.is_empty() {
+    return Err(errors.join("\n"));
+  }
   Ok(())
 }
+
+/// Put a project's skills, agent links and lock file back into `root`, its folder on this
+/// machine. Returns the problems found; nothing is restored when that folder does not exist.
+fn restore_project(extract_dir: &Path, project: &BackupProjectRecord, root: &Path) -> Vec<String> {
+  let label = root.to_string_lossy().to_string();
+  if !root.is_dir() {
+    return vec![format!("{}: 项目目录不存在", label)];
+  }
+  let Some(backup_dir) = backup_path_in(extract_dir, &project.backup_dir) else {
+    return vec![format!("{}: 备份清单包含非法路径", label)];
+  };
+  let install_target = InstallTarget::Project(root.to_path_buf());
+  let skills_root = match install_target.skill_folder_path() {
+    Ok(path) => path,
+    Err(err) => return vec![format!("{}: {}", label, err)],
+  };
+
+  let mut errors = Vec::new();
+  if project.has_lock {
+    if let Err(err) = fs::copy(project_lock_path(&backup_dir), project_lock_path(root)) {
+      errors.push(format!("{}: 恢复锁文件失败: {}", label, err));
+    }
+  }
+  for record in &project.skills {
+    let restored = backup_path_in(Path::new(""), &record.name)
+      .ok_or_else(|| "备份清单包含非法技能名".to_string())
+      .and_then(|name| {
+        replace_dir_sync(
+          &backup_dir.join(BACKUP_PROJECT_SKILLS_DIR).join(&name),
+          &skills_root.join(&name),
+        )
+      })
+      .and_then(|_| link_recorded_apps(&record.name, record, &install_target));
+    if let Err(err) = restored {
+      errors.push(format!("{}: {}: {}", label, record.name, err));
+    }
+  }
+  for record in &project.agent_skills {
+    if let Err(err) = restore_agent_skill(extract_dir, record, &install_target) {
+      errors.push(format!("{}: {}: {}", label, record.name, err));
+    }
+  }
+  errors
+}
+
+/// Point restored project registrations at the new roots of moved projects.
+fn move_registered_projects(
+  project_roots: &BTreeMap<String, String>,
+  result: &mut BackupRestoreResult,
+) {
+  if project_roots.is_empty() {
+    return;
+  }
+  let projects = match list_user_projects() {
+    Ok(projects) => projects,
+    Err(err) => {
+      result.errors.push(err);
+      return;
+    },
+  };
+  for project in projects {
+    let Some(root) = project_roots.get(&project.path) else {
+      continue;
+    };
+    if let Err(err) = update_user_project(project.name.clone(), project.name, root.clone()) {
+      result.errors.push(format!("{}: {}", project.path, err));
+    }
+  }
+}
+
+fn restore_state_files(extract_dir: &Path, names: &[String], result: &mut BackupRestoreResult) {
+  let targets = match state_file_paths() {
+    Ok(targets) => targets,
+    Err(err) => {
+      result.errors.push(err);
+      return;
+    },
+  };
+  let config_file = match config_path() {
+    Ok(path) => path,
+    Err(err) => {
+      result.errors.push(err);
+      return;
+    },
+  };
+  let state_dir = extract_dir.join(BACKUP_EXTRA_DIR).join(BACKUP_STATE_DIR);
+  for name in names {
+    let Some(target) = targets.iter().find(|path| {
+      path
+        .file_name()
+        .is_some_and(|file_name| file_name == name.as_str())
+    }) else {
+      result.errors.push(format!("{}: 未知的状态文件", name));
+      continue;
+    };
+    let restored = if *target == config_file {
+      restore_config(&state_dir.join(name))
+    } else {
+      target
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::copy(state_dir.join(name), target))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    };
+    match restored {
+      Ok(()) => result.state_files.push(name.clone()),
+      Err(err) => result.errors.push(format!("{}: {}", name, err)),
+    }
+  }
+  refresh_local_agent_apps();
+}
+
+/// Backups keep no secrets, so the API key and git host tokens already set up on this
+/// machine survive restoring the backed up settings.
+fn restore_config(backup_config: &Path) -> Result<(), String> {
+  let content = fs::read_to_string(backup_config).map_err(|e| e.to_string())?;
+  let restored: AppConfig =
+    serde_json::from_str(&content).map_err(|e| format!("解析设置失败: {}", e))?;
+  let local = load_config().unwrap_or_default();
+  save_config(&restored.with_secrets_from(&local))
+}
+
+fn write_config_file(path: &Path, config: &AppConfig) -> Result<(), String> {
+  let content =
+    serde_json::to_string_pretty(config).map_err(|e| format!("序列化设置失败: {}", e))?;
+  fs::write(path, content).map_err(|e| format!("写入设置失败: {}", e))
+}
+
+/// Lock, agent app, project and settings files a backup captures
+fn state_file_paths() -> Result<Vec<PathBuf>, String> {
+  Ok(vec![
+    skill_lock_path()?,
+    native_skill_lock_path()?,
+    user_agent_apps_path()?,
+    user_projects_path()?,
+    config_path()?,
+  ])
+}
+
+/// The manifest of a new backup, and the files and folders it captures: global skills,
+/// skills only found in agent folders, every registered project and the app state files.
+/// The config is written to `staging` without its API key and git host tokens.
+fn collect_backup(
+  created_at: &str,
+  staging: &Path,
+) -> Result<(BackupManifest, Vec<BackupSource>), String> {
+  let mut sources = Vec::new();
+  let skills_root = canonical_skills_root()?;
+  if skills_root.is_dir() {
+    sources.push(BackupSource {
+      path: skills_root,
+      archive_path: String::new(),
+    });
+  }
+
+  let github_lock = read_skill_lock_internal().unwrap_or_default();
+  let (skills, agent_skills) = scope_records(
+    skill_service::list_skills(InstallScope::Global, None)?,
+    &format!("{}/{}", BACKUP_EXTRA_DIR, BACKUP_AGENT_SKILLS_DIR),
+    &mut sources,
+    |skill| {
+      if skill.source_type.is_remote() {
+        github_lock.skills.get(&skill.name).cloned()
+      } else {
+        None
+      }
+    },
+  );
+
+  let mut projects = Vec::new();
+  for (index, project) in list_user_projects()?.into_iter().enumerate() {
+    let root = PathBuf::from(&project.path);
+    if !root.is_dir() {
+      tracing::warn!("Skipping missing project {} in backup", project.path);
+      continue;
+    }
+    let listed = match skill_service::list_skills(InstallScope::Project, Some(project.path.clone()))
+    {
+      Ok(listed) => listed,
+      Err(err) => {
+        tracing::warn!("Skipping project {} in backup: {}", project.path, err);
+        continue;
+      },
+    };
+    let backup_dir = format!("{}/{}/{}", BACKUP_EXTRA_DIR, BACKUP_PROJECTS_DIR, index);
+    let project_skills_root = InstallTarget::Project(root.clone()).skill_folder_path()?;
+    if project_skills_root.is_dir() {
+      sources.push(BackupSource {
+        path: project_skills_root,
+        archive_path: format!("{}/{}", backup_dir, BACKUP_PROJECT_SKILLS_DIR),
+      });
+    }
+    let lock_path = project_lock_path(&root);
+    let has_lock = lock_path.is_file();
+    if has_lock {
+      sources.push(BackupSource {
+        archive_path: archive_path_for(&project_lock_path(Path::new(&backup_dir))),
+        path: lock_path,
+      });
+    }
+    let (skills, agent_skills) = scope_records(
+      listed,
+      &format!("{}/{}", backup_dir, BACKUP_AGENT_SKILLS_DIR),
+      &mut sources,
+      // The project's skills-lock.json travels whole, so records carry no lock entry.
+      |_| None,
+    );
+    projects.push(BackupProjectRecord {
+      name: project.name,
+      path: project.path,
+      backup_dir,
+      skills,
+      agent_skills,
+      has_lock,
+    });
+  }
+
+  let mut state_files = Vec::new();
+  let config_file = config_path()?;
+  for mut path in state_file_paths()? {
+    let Some(name) = path
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+    else {
+      continue;
+    };
+    if path.is_file() {
+      if path == config_file {
+        path = staging.join(&name);
+        write_config_file(&path, &load_config()?.without_secrets())?;
+      }
+      sources.push(BackupSource {
+        path,
+        archive_path: format!("{}/{}/{}", BACKUP_EXTRA_DIR, BACKUP_STATE_DIR, name),
+      });
+      state_files.push(name);
+    }
+  }
+
+  let manifest = BackupManifest {
+    version: BACKUP_MANIFEST_VERSION,
+    created_at: created_at.to_string(),
+    skills,
+    agent_skills,
+    projects,
+    state_files,
+  };
+  Ok((manifest, sources))
+}
+
+/// Records for one scope's skills. Skills without a folder in the scope's skills folder
+/// are captured from their first agent folder into `agent_skills_dir`.
+fn scope_records(
+  listed: Vec<LocalSkill>,
+  agent_skills_dir: &str,
+  sources: &mut Vec<BackupSource>,
+  lock_for: impl Fn(&LocalSkill) -> Option<SkillLockEntry>,
+) -> (Vec<BackupSkillRecord>, Vec<BackupSkillRecord>) {
+  let mut skills = Vec::new();
+  let mut agent_skills = Vec::new();
+  for skill in listed {
+    let mut record = BackupSkillRecord {
+      name: skill.name.clone(),
+      agent_apps: skill
+        .installed_agent_apps
+        .iter()
+        .map(|app| BackupAgentLink {
+          id: app.id.clone(),
+          method: app.method.clone(),
+        })
+        .collect(),
+      lock: lock_for(&skill),
+      path: None,
+    };
+    if skill.root_folder.is_some() {
+      skills.push(record);
+      continue;
+    }
+    let Some(app) = skill.installed_agent_apps.first() else {
+      continue;
+    };
+    let path = format!("{}/{}/{}", agent_skills_dir, app.id, skill.name);
+    sources.push(BackupSource {
+      path: PathBuf::from(&app.skill_folder),
+      archive_path: path.clone(),
+    });
+    record.path = Some(path);
+    agent_skills.push(record);
+  }
+  (skills, agent_skills)
+}
+
+fn archive_path_for(path: &Path) -> String {
+  path
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// `path` from a backup manifest, resolved inside `root`; `None` when it would leave it
+fn backup_path_in(root: &Path, path: &str) -> Option<PathBuf> {
+  let relative = Path::new(path);
+  let safe = !path.is_empty()
+    && relative
+      .components()
+      .all(|component| matches!(component, Component::Normal(_)));
+  safe.then(|| root.join(relative))
+}
+
+fn compare_with_installed(extract_dir: &Path) -> Result<BackupRestorePreview, String> {
+  let skills_root = canonical_skills_root()?;
+  let backup_names = extracted_skill_names(extract_dir)?;
+  let mut installed_names = BTreeSet::new();
+  if skills_root.is_dir() {
+    for entry in fs::read_dir(&skills_root).map_err(|e| format!("读取目录失败: {}", e))? {
+      let Ok(entry) = entry else {
+        continue;
+      };
+      let name = entry.file_name().to_string_lossy().to_string();
+      if !name.starts_with('.') && entry.path().join(SKILL_MD_FILE_NAME).exists() {
+        installed_names.insert(name);
+      }
+    }
+  }
+
+  let mut preview = BackupRestorePreview::default();
+  for name in &backup_names {
+    if !installed_names.contains(name) {
+      preview.added.push(name.clone());
+      continue;
+    }
+    let backup_hash = FolderHelper::compute_skill_folder_hash(&extract_dir.join(name))?;
+    let installed_hash = FolderHelper::compute_skill_folder_hash(&skills_root.join(name))?;
+    if backup_hash == installed_hash {
+      preview.unchanged.push(name.clone());
+    } else {
+      preview.overwritten.push(name.clone());
+    }
+  }
+  preview.removed = installed_names
+    .into_iter()
+    .filter(|name| !backup_names.contains(name))
+    .collect();
+  Ok(preview)
+}
+
+fn extract_backup(backup_path: &str) -> Result<WorkspaceSession, String> {
+  let path = Path::new(backup_path);
+  if !path.is_file() {
+    return Err(format!("备份文件不存在: {}", backup_path));
+  }
+  let session = workspace_service::create_session("restore")?;
+  if is_snapshot(path) {
+    // Laid out like an extracted ZIP backup, manifest included, so restores share one path.
+    let snapshot = backup_store_service::materialize_snapshot(path, session.path())?;
+    let manifest_content = serde_json::to_string_pretty(&snapshot.manifest)
+      .map_err(|e| format!("序列化备份清单失败: {}", e))?;
+    fs::write(session.path().join(BACKUP_MANIFEST_FILE), manifest_content)
+      .map_err(|e| format!("写入备份清单失败: {}", e))?;
+  } else {
+    ZipHelper::extract_to_dir(backup_path, session.path())?;
+  }
+  Ok(session)
+}
+
+fn read_backup_manifest(extract_dir: &Path) -> Option<BackupManifest> {
+  let content = fs::read_to_string(extract_dir.join(BACKUP_MANIFEST_FILE)).ok()?;
+  serde_json::from_str(&content).ok()
+}
+
+/// Top-level skill folders in an extracted backup, sorted by name
+fn extracted_skill_names(extract_dir: &Path) -> Result<Vec<String>, String> {
+  let mut names = Vec::new();
+  for entry in fs::read_dir(extract_dir).map_err(|e| format!("读取目录失败: {}", e))? {
+    let entry = entry.map_err(|e| format!("读取条目失败: {}", e))?;
+    if entry.path().join(SKILL_MD_FILE_NAME).is_file() {
+      names.push(entry.file_name().to_string_lossy().to_string());
+    }
+  }
+  names.sort();
+  Ok(names)
+}
+
+/// Top-level skill folders in a backup ZIP, read from the entry names only
+fn backup_skill_names(zip_path: &Path) -> Result<BTreeSet<String>, String> {
+  let file = fs::File::open(zip_path).map_err(|e| format!("打开备份文件失败: {}", e))?;
+  let archive = zip::ZipArchive::new(file).map_err(|e| format!("读取备份文件失败: {}", e))?;
+  Ok(
+    archive
+      .file_names()
+      .filter_map(|name| {
+        let (skill, rest) = name.split_once('/')?;
+        (rest == SKILL_MD_FILE_NAME).then(|| skill.to_string())
+      })
+      .collect(),
+  )
+}
+
+fn is_snapshot(path: &Path) -> bool {
+  path.extension().is_some_and(|ext| ext == "json")
+}
+
+fn backup_stem(file_name: &str) -> &str {
+  file_name
+    .strip_suffix(".zip")
+    .or_else(|| file_name.strip_suffix(".json"))
+    .unwrap_or(file_name)
+}
+
+fn backup_timestamp(file_name: &str) -> Option<NaiveDateTime> {
+  let timestamp = backup_stem(file_name).strip_prefix(BACKUP_FILE_PREFIX)?;
+  NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).ok()
+}
+
+fn backup_time_from_file(file_name: &str, metadata: &fs::Metadata) -> String {
+  if let Some(time) =
+    backup_timestamp(file_name).and_then(|time| Local.from_local_datetime(&time).single())
+  {
+    return time.format("%Y-%m-%d %H:%M:%S").to_string();
+  }
+  metadata
+    .modified()
+    .map(|time| {
+      chrono::DateTime::<Local>::from(time)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+    })
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn retention_keeps_last_daily_and_weekly_backups() {
+    let names = [
+      "skills_backup_20260318180000.zip",
+      "skills_backup_20260318090000.json",
+      "skills_backup_20260317090000.zip",
+      "skills_backup_20260316090000.json",
+      "skills_backup_20260310090000.zip",
+      "skills_backup_20260302090000.zip",
+      "skills_backup_20260301090000.zip",
+      "notes.zip",
+    ];
+    let times: Vec<Option<NaiveDateTime>> =
+      names.iter().map(|name| backup_timestamp(name)).collect();
+    let retention = BackupRetention {
+      keep_last: 1,
+      keep_daily: 2,
+      keep_weekly: 3,
+    };
+
+    let retained: Vec<&str> = retained_backups(&times, &retention)
+      .into_iter()
+      .map(|index| names[index])
+      .collect();
+
+    // Newest overall, newest of the last two days, newest of the last three ISO weeks,
+    // and the file whose time cannot be read.
+    assert_eq!(
+      retained,
+      vec![
+        "skills_backup_20260318180000.zip",
+        "skills_backup_20260317090000.zip",
+        "skills_backup_20260310090000.zip",
+        "skills_backup_20260302090000.zip",
+        "notes.zip",
+      ]
+    );
+  }
+
+  #[test]
+  fn backed_up_config_keeps_no_secrets_and_restores_local_ones() {
+    let host = |base_url: &str, token: Option<&str>| crate::config::SourceHostConfig {
+      kind: crate::models::ProviderKind::Gitlab,
+      base_url: base_url.to_string(),
+      api_url: None,
+      token: token.map(str::to_string),
+    };
+    let backed_up = AppConfig {
+      language: "zh".to_string(),
+      openrouter_api_key: Some("sk-or-old".to_string()),
+      source_hosts: vec![
+        host("https://git.example.com", Some("old-token")),
+        host("https://gitlab.example.org", Some("other-token")),
+      ],
+      ..AppConfig::default()
+    }
+    .without_secrets();
+    assert_eq!(backed_up.openrouter_api_key, None);
+    assert!(backed_up
+      .source_hosts
+      .iter()
+      .all(|host| host.token.is_none()));
+
+    let local = AppConfig {
+      openrouter_api_key: Some("sk-or-local".to_string()),
+      source_hosts: vec![host("https://git.example.com/", Some("local-token"))],
+      ..AppConfig::default()
+    };
+    let restored = backed_up.with_secrets_from(&local);
+    assert_eq!(restored.language, "zh");
+    assert_eq!(restored.openrouter_api_key.as_deref(), Some("sk-or-local"));
+    let tokens: Vec<Option<&str>> = restored
+      .source_hosts
+      .iter()
+      .map(|host| host.token.as_deref())
+      .collect();
+    assert_eq!(tokens, vec![Some("local-token"), None]);
+  }
+}
@@ -0,0 +1,460 @@
+use crate::services::backup_service::{BackupManifest, BackupSource};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Content-addressed backups live in this folder inside the backup folder.
+const STORE_DIR: &str = "skills-store";
+/// File blobs, stored once each at `objects/<first two hex chars>/<rest of the hash>`
+const OBJECTS_DIR: &str = "objects";
+/// One small JSON manifest per backup
+const SNAPSHOTS_DIR: &str = "snapshots";
+const SNAPSHOT_VERSION: i64 = 1;
+/// Held by `write_snapshot` and `collect_garbage`. A backup reuses blobs that already
+/// exist and lists them in its snapshot only at the end, so a collection running in
+/// between would take them for unreferenced.
+const LOCK_FILE: &str = ".lock";
+/// A lock this old was left behind by a run that crashed
+const STALE_LOCK: Duration = Duration::from_secs(6 * 60 * 60);
+/// How long a backup waits for a running backup or collection to finish
+const LOCK_WAIT: Duration = Duration::from_secs(10 * 60);
+const LOCK_POLL: Duration = Duration::from_millis(200);
+const SKILL_MD_FILE_NAME: &str = "SKILL.md";
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct BackupSnapshot {
+  pub version: i64,
+  pub created_at: String,
+  #[serde(default)]
+  pub files: Vec<SnapshotFile>,
+  /// Agent links and lock provenance, as stored in a ZIP backup
+  #[serde(default)]
+  pub manifest: BackupManifest,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct SnapshotFile {
+  /// Relative to the skills folder, `/`-separated
+  pub path: String,
+  /// SHA-256 of the content, which is also the object name
+  pub hash: String,
+  pub size: u64,
+  /// Unix permission bits
+  #[serde(default)]
+  pub mode: Option<u32>,
+}
+
+impl BackupSnapshot {
+  /// Top-level skill folders in the snapshot
+  pub fn skill_names(&self) -> BTreeSet<String> {
+    self
+      .files
+      .iter()
+      .filter_map(|file| {
+        let (skill, rest) = file.path.split_once('/')?;
+        (rest == SKILL_MD_FILE_NAME).then(|| skill.to_string())
+      })
+      .collect()
+  }
+
+  /// Size of the backed up files, before deduplication
+  pub fn total_size(&self) -> u64 {
+    self.files.iter().map(|file| file.size).sum()
+  }
+}
+
+pub fn snapshots_dir(backup_folder: &Path) -> PathBuf {
+  backup_folder.join(STORE_DIR).join(SNAPSHOTS_DIR)
+}
+
+fn objects_dir(backup_folder: &Path) -> PathBuf {
+  backup_folder.join(STORE_DIR).join(OBJECTS_DIR)
+}
+
+/// Store every captured file that is not already in the store and write a snapshot named
+/// `<file_stem>.json` listing them.
+pub fn write_snapshot(
+  backup_folder: &Path,
+  file_stem: &str,
+  sources: &[BackupSource],
+  manifest: BackupManifest,
+) -> Result<PathBuf, String> {
+  let objects = objects_dir(backup_folder);
+  let snapshots = snapshots_dir(backup_folder);
+  fs::create_dir_all(&objects).map_err(|e| format!("创建备份目录失败: {}", e))?;
+  fs::create_dir_all(&snapshots).map_err(|e| format!("创建备份目录失败: {}", e))?;
+  let _lock = StoreLock::acquire(backup_folder, LOCK_WAIT)?
+    .ok_or("备份仓库正被另一个备份或清理占用，请稍后重试")?;
+
+  let mut files = Vec::new();
+  for source in sources {
+    if source.path.is_dir() {
+      store_dir_files(&source.path, &source.archive_path, &objects, &mut files)?;
+    } else {
+      files.push(store_file(
+        &source.path,
+        source.archive_path.clone(),
+        &objects,
+      )?);
+    }
+  }
+  files.sort_by(|a, b| a.path.cmp(&b.path));
+
+  let snapshot = BackupSnapshot {
+    version: SNAPSHOT_VERSION,
+    created_at: manifest.created_at.clone(),
+    files,
+    manifest,
+  };
+  let content =
+    serde_json::to_string_pretty(&snapshot).map_err(|e| format!("序列化备份清单失败: {}", e))?;
+  let path = snapshots.join(format!("{}.json", file_stem));
+  // Objects are written first, so a snapshot never refers to a blob that is not stored yet.
+  let partial = path.with_extension("json.partial");
+  fs::write(&partial, content).map_err(|e| format!("写入备份清单失败: {}", e))?;
+  fs::rename(&partial, &path).map_err(|e| {
+    let _ = fs::remove_file(&partial);
+    format!("写入备份清单失败: {}", e)
+  })?;
+  Ok(path)
+}
+
+pub fn read_snapshot(path: &Path) -> Result<BackupSnapshot, String> {
+  let content = fs::read_to_string(path).map_err(|e| format!("读取备份清单失败: {}", e))?;
+  serde_json::from_str(&content).map_err(|e| format!("解析备份清单失败: {}", e))
+}
+
+/// Snapshot manifests in the store, in no particular order
+pub fn list_snapshot_paths(backup_folder: &Path) -> Result<Vec<PathBuf>, String> {
+  let dir = snapshots_dir(backup_folder);
+  if !dir.is_dir() {
+    return Ok(Vec::new());
+  }
+  let mut paths = Vec::new();
+  for entry in fs::read_dir(&dir).map_err(|e| format!("读取目录失败: {}", e))? {
+    let Ok(entry) = entry else {
+      continue;
+    };
+    let path = entry.path();
+    if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+      paths.push(path);
+    }
+  }
+  Ok(paths)
+}
+
+/// Rebuild the skills folder recorded in a snapshot under `dest`, checking every blob
+/// against its hash on the way.
+pub fn materialize_snapshot(snapshot_path: &Path, dest: &Path) -> Result<BackupSnapshot, String> {
+  let snapshot = read_snapshot(snapshot_path)?;
+  let objects = snapshot_path
+    .parent()
+    .and_then(Path::parent)
+    .map(|store| store.join(OBJECTS_DIR))
+    .ok_or_else(|| format!("备份清单不在备份仓库中: {}", snapshot_path.display()))?;
+
+  for file in &snapshot.files {
+    let relative = safe_relative_path(&file.path)
+      .ok_or_else(|| format!("备份清单包含非法路径: {}", file.path))?;
+    let content = fs::read(object_path(&objects, &file.hash)?)
+      .map_err(|e| format!("备份文件缺失 {}: {}", file.path, e))?;
+    if hash_bytes(&content) != file.hash {
+      return Err(format!("备份文件已损坏: {}", file.path));
+    }
+    let out_path = dest.join(relative);
+    if let Some(parent) = out_path.parent() {
+      fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    fs::write(&out_path, content).map_err(|e| format!("写入文件失败: {}", e))?;
+    #[cfg(unix)]
+    if let Some(mode) = file.mode {
+      use std::os::unix::fs::PermissionsExt;
+      fs::set_permissions(&out_path, fs::Permissions::from_mode(mode & 0o777))
+        .map_err(|e| format!("设置文件权限失败: {}", e))?;
+    }
+  }
+  Ok(snapshot)
+}
+
+/// Delete objects that no snapshot refers to. Returns the number of objects and bytes
+/// freed. Nothing is deleted when a snapshot cannot be read, since its objects are unknown,
+/// or while a backup is being written; the next collection picks those objects up.
+pub fn collect_garbage(backup_folder: &Path) -> Result<(usize, u64), String> {
+  let objects = objects_dir(backup_folder);
+  if !objects.is_dir() {
+    return Ok((0, 0));
+  }
+  let Some(_lock) = StoreLock::acquire(backup_folder, Duration::ZERO)? else {
+    tracing::info!("Skipping backup store cleanup while a backup is running");
+    return Ok((0, 0));
+  };
+  let mut referenced = HashSet::new();
+  for path in list_snapshot_paths(backup_folder)? {
+    referenced.extend(
+      read_snapshot(&path)?
+        .files
+        .into_iter()
+        .map(|file| file.hash),
+    );
+  }
+
+  let mut freed = (0, 0);
+  for shard in fs::read_dir(&objects).map_err(|e| format!("读取目录失败: {}", e))? {
+    let Ok(shard) = shard else {
+      continue;
+    };
+    let shard_path = shard.path();
+    if !shard_path.is_dir() {
+      continue;
+    }
+    let prefix = shard.file_name().to_string_lossy().to_string();
+    for entry in fs::read_dir(&shard_path).map_err(|e| format!("读取目录失败: {}", e))? {
+      let Ok(entry) = entry else {
+        continue;
+      };
+      let name = entry.file_name().to_string_lossy().to_string();
+      // Dot-prefixed files are blobs still being written by a running backup.
+      if name.starts_with('.') || referenced.contains(&format!("{}{}", prefix, name)) {
+        continue;
+      }
+      let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+      fs::remove_file(entry.path()).map_err(|e| format!("删除备份文件失败: {}", e))?;
+      freed.0 += 1;
+      freed.1 += size;
+    }
+    // Only succeeds once the shard is empty.
+    let _ = fs::remove_dir(&shard_path);
+  }
+  Ok(freed)
+}
+
+/// Lock file in the store, removed when dropped.
+struct StoreLock(PathBuf);
+
+impl StoreLock {
+  /// Take the store lock, waiting up to `wait` for its holder. `None` when it stays taken.
+  fn acquire(backup_folder: &Path, wait: Duration) -> Result<Option<Self>, String> {
+    let store = backup_folder.join(STORE_DIR);
+    fs::create_dir_all(&store).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    let path = store.join(LOCK_FILE);
+    let started = SystemTime::now();
+    loop {
+      match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+      {
+        Ok(_) => return Ok(Some(Self(path))),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {},
+        Err(e) => return Err(format!("锁定备份仓库失败: {}", e)),
+      }
+      let stale = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > STALE_LOCK);
+      if stale {
+        tracing::warn!("Removing stale backup store lock {}", path.display());
+        let _ = fs::remove_file(&path);
+        continue;
+      }
+      if started.elapsed().unwrap_or_default() >= wait {
+        return Ok(None);
+      }
+      std::thread::sleep(LOCK_POLL);
+    }
+  }
+}
+
+impl Drop for StoreLock {
+  fn drop(&mut self) {
+    let _ = fs::remove_file(&self.0);
+  }
+}
+
+fn store_dir_files(
+  dir: &Path,
+  prefix: &str,
+  objects: &Path,
+  files: &mut Vec<SnapshotFile>,
+) -> Result<(), String> {
+  for entry in fs::read_dir(dir).map_err(|e| format!("读取目录失败: {}", e))? {
+    let entry = entry.map_err(|e| format!("读取条目失败: {}", e))?;
+    let path = entry.path();
+    let file_name = entry.file_name().to_string_lossy().to_string();
+    let name = if prefix.is_empty() {
+      file_name
+    } else {
+      format!("{}/{}", prefix, file_name)
+    };
+
+    if path.is_file() {
+      files.push(store_file(&path, name, objects)?);
+    } else if path.is_dir() {
+      store_dir_files(&path, &name, objects, files)?;
+    }
+  }
+  Ok(())
+}
+
+fn store_file(path: &Path, name: String, objects: &Path) -> Result<SnapshotFile, String> {
+  let content = fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+  let hash = hash_bytes(&content);
+  store_object(objects, &hash, &content)?;
+  Ok(SnapshotFile {
+    path: name,
+    hash,
+    size: content.len() as u64,
+    mode: file_mode(path),
+  })
+}
+
+fn store_object(objects: &Path, hash: &str, content: &[u8]) -> Result<(), String> {
+  let path = object_path(objects, hash)?;
+  if path.is_file() {
+    return Ok(());
+  }
+  let parent = path.parent().unwrap_or(objects);
+  fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+  let partial = parent.join(format!(".{}.partial", &hash[2..]));
+  fs::write(&partial, content).map_err(|e| format!("写入备份文件失败: {}", e))?;
+  fs::rename(&partial, &path).map_err(|e| {
+    let _ = fs::remove_file(&partial);
+    format!("写入备份文件失败: {}", e)
+  })
+}
+
+fn object_path(objects: &Path, hash: &str) -> Result<PathBuf, String> {
+  if hash.len() != 64 || !hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+    return Err(format!("备份清单包含非法哈希: {}", hash));
+  }
+  Ok(objects.join(&hash[..2]).join(&hash[2..]))
+}
+
+fn hash_bytes(content: &[u8]) -> String {
+  format!("{:x}", Sha256::digest(content))
+}
+
+fn safe_relative_path(path: &str) -> Option<PathBuf> {
+  let relative = PathBuf::from(path);
+  let safe = !path.is_empty()
+    && relative
+      .components()
+      .all(|component| matches!(component, Component::Normal(_)));
+  safe.then_some(relative)
+}
+
+#[cfg(unix)]
+fn file_mode(path: &Path) -> Option<u32> {
+  use std::os::unix::fs::PermissionsExt;
+  fs::metadata(path)
+    .ok()
+    .map(|metadata| metadata.permissions().mode() & 0o777)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_path: &Path) -> Option<u32> {
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn snapshots_share_blobs_and_garbage_collection_keeps_referenced_ones() {
+    let root = std::env::temp_dir().join(format!("youskill-store-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let skills = root.join("skills");
+    let backups = root.join("backups");
+    fs::create_dir_all(skills.join("demo")).unwrap();
+    fs::write(
+      skills.join("demo").join("SKILL.md"),
+      "---\nname: demo\n---\n",
+    )
+    .unwrap();
+    fs::write(skills.join("demo").join("notes.md"), "v1").unwrap();
+
+    let sources = [BackupSource {
+      path: skills.clone(),
+      archive_path: String::new(),
+    }];
+
+    let first = write_snapshot(&backups, "a", &sources, BackupManifest::default()).unwrap();
+    fs::write(skills.join("demo").join("notes.md"), "v2").unwrap();
+    let second = write_snapshot(&backups, "b", &sources, BackupManifest::default()).unwrap();
+    let object_count = || {
+      walkdir::WalkDir::new(objects_dir(&backups))
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .count()
+    };
+    let stored = object_count();
+
+    fs::remove_file(&first).unwrap();
+    let (freed, _) = collect_garbage(&backups).unwrap();
+    let restored = root.join("restored");
+    let snapshot = materialize_snapshot(&second, &restored).unwrap();
+    let notes = fs::read_to_string(restored.join("demo").join("notes.md")).unwrap();
+    let _ = fs::remove_dir_all(&root);
+
+    // SKILL.md is shared, so two snapshots need three blobs; dropping the first frees one.
+    assert_eq!(stored, 3);
+    assert_eq!(freed, 1);
+    assert_eq!(notes, "v2");
+    assert_eq!(snapshot.skill_names(), BTreeSet::from(["demo".to_string()]));
+  }
+
+  #[test]
+  fn garbage_collection_leaves_a_running_backup_alone() {
+    let root = std::env::temp_dir().join(format!("youskill-store-lock-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let skills = root.join("skills");
+    let backups = root.join("backups");
+    fs::create_dir_all(skills.join("demo")).unwrap();
+    fs::write(
+      skills.join("demo").join("SKILL.md"),
+      "---\nname: demo\n---\n",
+    )
+    .unwrap();
+    let sources = [BackupSource {
+      path: skills.clone(),
+      archive_path: String::new(),
+    }];
+    let old = write_snapshot(&backups, "old", &sources, BackupManifest::default()).unwrap();
+
+    // A backup has reused the stored SKILL.md but not written its snapshot yet, while
+    // the old snapshot is pruned
+    let running = StoreLock::acquire(&backups, Duration::ZERO)
+      .unwrap()
+      .unwrap();
+    fs::remove_file(&old).unwrap();
+    let during = collect_garbage(&backups).unwrap();
+    let blocked = StoreLock::acquire(&backups, Duration::ZERO)
+      .unwrap()
+      .is_none();
+    let writer = {
+      let backups = backups.clone();
+      let sources = sources.clone();
+      std::thread::spawn(move || {
+        write_snapshot(&backups, "new", &sources, BackupManifest::default())
+      })
+    };
+    std::thread::sleep(LOCK_POLL * 2);
+    let written_early = snapshots_dir(&backups).join("new.json").exists();
+    drop(running);
+    let new = writer.join().unwrap().unwrap();
+    let after = collect_garbage(&backups).unwrap();
+    let restored = materialize_snapshot(&new, &root.join("restored"));
+    let _ = fs::remove_dir_all(&root);
+
+    assert_eq!(during, (0, 0));
+    assert!(blocked);
+    assert!(!written_early);
+    assert_eq!(after, (0, 0));
+    assert!(restored.is_ok());
+  }
+}
@@ -0,0 +1,886 @@
+use crate::config::load_config;
+use crate::models::{
+  DoctorFinding, DoctorFix, DoctorFixResult, DoctorIssueKind, InstallMethod, InstallScope,
+  InstallTarget, SourceType,
+};
+use crate::services::agent_apps_service::{agent_skill_roots, local_agent_apps};
+use crate::services::native_skill_lock_service::{
+  add_skill_to_native_lock, read_native_skill_lock_internal, remove_skill_from_native_lock,
+};
+use crate::services::project_skill_lock_service::{
+  add_skill_to_project_lock, read_project_skill_lock_internal, remove_skill_from_project_lock,
+};
+use crate::services::skill_lock_service::{
+  read_skill_lock_internal, remove_skill_from_global_lock,
+};
+use crate::services::{skill_service, user_projects_service};
+use crate::utils::file::FileHelper;
+use crate::utils::folder::{copy_dir_all_sync, FolderHelper};
+use crate::utils::path::{canonical_skill_folder_by_name, remove_path_any};
+use crate::utils::transaction::FsTransaction;
+use chrono::Local;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SKILL_MD_FILE_NAME: &str = "SKILL.md";
+
+/// Check the global scope and every registered project for broken links, orphaned or
+/// unlocked skills and other state the app cannot make sense of.
+pub fn run_doctor() -> Result<Vec<DoctorFinding>, String> {
+  let mut findings = DoctorScope::new(&InstallScope::Global, None)?.scan()?;
+  for project in user_projects_service::list_user_projects()? {
+    // Projects whose folder is gone have nothing on disk to check.
+    match DoctorScope::new(&InstallScope::Project, Some(project.path.clone())) {
+      Ok(scope) => findings.extend(scope.scan()?),
+      Err(err) => tracing::warn!("Skipping project {}: {}", project.path, err),
+    }
+  }
+  Ok(findings)
+}
+
+/// Apply the fix of each finding in order. A failed fix does not stop the others.
+pub fn apply_doctor_fixes(findings: Vec<DoctorFinding>) -> Vec<DoctorFixResult> {
+  findings
+    .into_iter()
+    .map(|finding| {
+      let result = DoctorScope::new(&finding.scope, finding.project_path.clone())
+        .and_then(|scope| scope.apply(&finding.fix));
+      DoctorFixResult {
+        id: finding.id,
+        success: result.is_ok(),
+        message: result.unwrap_or_else(|err| err),
+      }
+    })
+    .collect()
+}
+
+/// The canonical store of one scope and the agent folders around it.
+struct DoctorScope {
+  scope: InstallScope,
+  project_path: Option<String>,
+  target: InstallTarget,
+  store: PathBuf,
+  /// Agent skill folders other than the store, with the agents reading each
+  agent_roots: Vec<(PathBuf, Vec<String>)>,
+  /// Whether some agent reads the store directly, so every stored skill is in use
+  store_is_agent_root: bool,
+  /// Where fixes move folders they take out of a skills folder
+  quarantine: PathBuf,
+  /// How skills are put into agent folders, from the sync mode setting
+  link_method: InstallMethod,
+}
+
+impl DoctorScope {
+  fn new(scope: &InstallScope, project_path: Option<String>) -> Result<Self, String> {
+    let target = InstallTarget::from_scope(scope, &project_path)?;
+    let store = target.skill_folder_path()?;
+    let mut agent_roots: Vec<(PathBuf, Vec<String>)> = Vec::new();
+    let mut store_is_agent_root = false;
+    for app in local_agent_apps() {
+      for root in agent_skill_roots(&app, &target) {
+        if root == store {
+          store_is_agent_root = true;
+          continue;
+        }
+        match agent_roots.iter_mut().find(|(path, _)| *path == root) {
+          Some((_, ids)) => ids.push(app.id.clone()),
+          None => agent_roots.push((root, vec![app.id.clone()])),
+        }
+      }
+    }
+    Ok(Self {
+      scope: scope.clone(),
+      project_path,
+      target,
+      store,
+      agent_roots,
+      store_is_agent_root,
+      quarantine: quarantine_dir()?,
+      link_method: match load_config().map(|config| config.sync_mode).as_deref() {
+        Ok("copy") => InstallMethod::Copy,
+        _ => InstallMethod::Symlink,
+      },
+    })
+  }
+
+  fn scan(&self) -> Result<Vec<DoctorFinding>, String> {
+    let mut findings = Vec::new();
+    let mut hashes = HashCache::default();
+
+    // Canonical store. A skill's canonical folder is the one named after it.
+    let mut stored: Vec<(String, PathBuf)> = Vec::new();
+    for path in skill_entries(&self.store) {
+      if is_broken_link(&path) {
+        findings.push(self.finding(
+          DoctorIssueKind::BrokenLink,
+          None,
+          &path,
+          None,
+          format!(
+            "Link {} points to a folder that no longer exists",
+            display(&path)
+          ),
+          DoctorFix::RemoveLink {
+            path: display(&path),
+          },
+        ));
+        continue;
+      }
+      if !path.is_dir() {
+        continue;
+      }
+      match read_skill_name(&path) {
+        Ok(name) => stored.push((name, path)),
+        Err(reason) => findings.push(self.finding(
+          DoctorIssueKind::UnreadableSkillMd,
+          None,
+          &path,
+          None,
+          reason,
+          DoctorFix::QuarantineFolder {
+            path: display(&path),
+          },
+        )),
+      }
+    }
+    let mut store_skills: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for (name, path) in &stored {
+      if entry_name(path) == *name || !store_skills.contains_key(name) {
+        store_skills.insert(name.clone(), path.clone());
+      }
+    }
+    for (name, path) in &stored {
+      let canonical = &store_skills[name];
+      if path != canonical && hashes.get(path) != hashes.get(canonical) {
+        findings.push(self.finding(
+          DoctorIssueKind::DuplicateName,
+          Some(name),
+          path,
+          None,
+          format!(
+            "{} declares skill '{}' but differs from {}",
+            display(path),
+            name,
+            display(canonical)
+          ),
+          DoctorFix::QuarantineFolder {
+            path: display(path),
+          },
+        ));
+      }
+    }
+
+    // Agent folders
+    let mut linked: BTreeSet<String> = BTreeSet::new();
+    let mut unmanaged: BTreeMap<String, Vec<(PathBuf, Option<String>)>> = BTreeMap::new();
+    let mut foreign_links: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for (root, agent_ids) in &self.agent_roots {
+      let agent_id = agent_ids.first();
+      for path in skill_entries(root) {
+        let name = entry_name(&path);
+        let canonical = store_skills
+          .get(&name)
+          .filter(|canonical| entry_name(canonical) == name);
+
+        if path.is_symlink() {
+          if is_broken_link(&path) {
+            let fix = match canonical {
+              Some(_) => DoctorFix::RelinkToStore {
+                path: display(&path),
+                name: name.clone(),
+              },
+              None => DoctorFix::RemoveLink {
+                path: display(&path),
+              },
+            };
+            findings.push(self.finding(
+              DoctorIssueKind::BrokenLink,
+              Some(&name),
+              &path,
+              agent_id,
+              format!(
+                "Link {} points to a folder that no longer exists",
+                display(&path)
+              ),
+              fix,
+            ));
+            continue;
+          }
+          if canonical.is_some_and(|canonical| same_dir(&path, canonical)) {
+            linked.insert(name);
+            continue;
+          }
+          if !path.is_dir() {
+            continue;
+          }
+          let fix = match canonical {
+            Some(_) => DoctorFix::RelinkToStore {
+              path: display(&path),
+              name: name.clone(),
+            },
+            None => DoctorFix::AdoptIntoStore {
+              path: display(&path),
+              name: name.clone(),
+            },
+          };
+          let destination = fs::read_link(&path)
+            .map(|link| display(&link))
+            .unwrap_or_default();
+          findings.push(self.finding(
+            DoctorIssueKind::LinkOutsideStore,
+            Some(&name),
+            &path,
+            agent_id,
+            format!(
+              "{} links to {} instead of the shared skills folder",
+              display(&path),
+              destination
+            ),
+            fix,
+          ));
+          foreign_links.entry(name).or_insert(path);
+          continue;
+        }
+
+        // Agents keep their own data and category folders next to skills
+        if !path.is_dir() || !path.join(SKILL_MD_FILE_NAME).is_file() {
+          continue;
+        }
+        let skill_name = match read_skill_name(&path) {
+          Ok(skill_name) => skill_name,
+          Err(reason) => {
+            let fix = match canonical {
+              Some(_) => DoctorFix::ReplaceWithStoreCopy {
+                path: display(&path),
+                name: name.clone(),
+              },
+              None => DoctorFix::QuarantineFolder {
+                path: display(&path),
+              },
+            };
+            findings.push(self.finding(
+              DoctorIssueKind::UnreadableSkillMd,
+              None,
+              &path,
+              agent_id,
+              reason,
+              fix,
+            ));
+            continue;
+          },
+        };
+        match store_skills.get(&skill_name) {
+          Some(canonical) if hashes.get(&path) == hashes.get(canonical) => {
+            linked.insert(skill_name);
+          },
+          // The fix puts the shared copy into this agent folder, so it counts as linked
+          Some(canonical) => {
+            findings.push(self.finding(
+              DoctorIssueKind::DuplicateName,
+              Some(&skill_name),
+              &path,
+              agent_id,
+              format!(
+                "{} differs from the shared copy at {}",
+                display(&path),
+                display(canonical)
+              ),
+              DoctorFix::ReplaceWithStoreCopy {
+                path: display(&path),
+                name: skill_name.clone(),
+              },
+            ));
+            linked.insert(skill_name);
+          },
+          None => unmanaged
+            .entry(skill_name)
+            .or_default()
+            .push((path, agent_id.cloned())),
+        }
+      }
+    }
+
+    // Agent-only copies of one skill that disagree. Adopting the first settles which
+    // content wins; the rest then show up as differing from the shared copy.
+    for (name, copies) in &unmanaged {
+      let (first, _) = &copies[0];
+      for (path, agent_id) in &copies[1..] {
+        if hashes.get(path) == hashes.get(first) {
+          continue;
+        }
+        findings.push(self.finding(
+          DoctorIssueKind::DuplicateName,
+          Some(name),
+          path,
+          agent_id.as_ref(),
+          format!(
+            "{} and {} both hold skill '{}' with different content",
+            display(first),
+            display(path),
+            name
+          ),
+          DoctorFix::AdoptIntoStore {
+            path: display(first),
+            name: name.clone(),
+          },
+        ));
+      }
+    }
+
+    // Lock files
+    let locked = self.locked_names()?;
+    for name in &locked {
+      if store_skills.contains_key(name) {
+        continue;
+      }
+      let copy = unmanaged
+        .get(name)
+        .map(|copies| copies[0].0.clone())
+        .or_else(|| foreign_links.get(name).cloned());
+      let fix = match copy {
+        Some(path) => DoctorFix::AdoptIntoStore {
+          path: display(&path),
+          name: name.clone(),
+        },
+        None => DoctorFix::RemoveLockEntry { name: name.clone() },
+      };
+      findings.push(self.finding(
+        DoctorIssueKind::MissingLockedFolder,
+        Some(name),
+        &self.store.join(name),
+        None,
+        format!(
+          "Skill '{}' is in the lock file but {} is missing",
+          name,
+          display(&self.store.join(name))
+        ),
+        fix,
+      ));
+    }
+    for (name, path) in &store_skills {
+      if !locked.contains(name) {
+        findings.push(self.finding(
+          DoctorIssueKind::UnlockedSkill,
+          Some(name),
+          path,
+          None,
+          format!("Skill '{}' is not recorded in any lock file", name),
+          DoctorFix::LockAsNative { name: name.clone() },
+        ));
+      }
+      if !self.store_is_agent_root && !self.agent_roots.is_empty() && !linked.contains(name) {
+        findings.push(self.finding(
+          DoctorIssueKind::UnlinkedSkill,
+          Some(name),
+          path,
+          None,
+          format!("Skill '{}' is not linked to any agent", name),
+          DoctorFix::LinkToAgents { name: name.clone() },
+        ));
+      }
+    }
+    Ok(findings)
+  }
+
+  fn apply(&self, fix: &DoctorFix) -> Result<String, String> {
+    match fix {
+      DoctorFix::RemoveLink { path } => {
+        let path = self.checked_path(path)?;
+        if !path.is_symlink() {
+          return Err(format!("{} is not a link", display(&path)));
+        }
+        remove_path_any(&path)?;
+        Ok(format!("Removed link {}", display(&path)))
+      },
+      DoctorFix::RelinkToStore { path, name } => {
+        let path = self.checked_path(path)?;
+        let canonical = self.stored_skill_dir(name)?;
+        let mut transaction = FsTransaction::new();
+        transaction.link(&canonical, &path, InstallMethod::Symlink);
+        transaction.apply()?.commit();
+        Ok(format!(
+          "Linked {} to {}",
+          display(&path),
+          display(&canonical)
+        ))
+      },
+      DoctorFix::AdoptIntoStore { path, name } => {
+        let source = self.checked_path(path)?;
+        if !source.is_dir() {
+          return Err(format!("{} is not a folder", display(&source)));
+        }
+        let canonical = canonical_skill_folder_by_name(name, &self.target)?;
+        let mut transaction = FsTransaction::new();
+        transaction.copy_dir(&source, &canonical);
+        if source.is_symlink() {
+          transaction.link(&canonical, &source, InstallMethod::Symlink);
+        }
+        let applied = transaction.apply()?;
+        self.lock_if_missing(name, &canonical)?;
+        applied.commit();
+        Ok(format!(
+          "Copied {} to {}",
+          display(&source),
+          display(&canonical)
+        ))
+      },
+      DoctorFix::RemoveLockEntry { name } => {
+        let canonical = canonical_skill_folder_by_name(name, &self.target)?;
+        if canonical.is_dir() {
+          return Err(format!("Skill '{}' is installed again", name));
+        }
+        match &self.target {
+          InstallTarget::Global => {
+            let _ = remove_skill_from_global_lock(name.clone())?;
+            let _ = remove_skill_from_native_lock(name.clone())?;
+          },
+          InstallTarget::Project(project_root) => {
+            let _ = remove_skill_from_project_lock(project_root, name.clone())?;
+          },
+        }
+        Ok(format!("Removed '{}' from the lock file", name))
+      },
+      DoctorFix::LockAsNative { name } => {
+        let canonical = self.stored_skill_dir(name)?;
+        self.lock_if_missing(name, &canonical)?;
+        Ok(format!("Recorded '{}' as a local skill", name))
+      },
+      DoctorFix::LinkToAgents { name } => {
+        self.stored_skill_dir(name)?;
+        let links: Vec<(String, InstallMethod)> = self
+          .agent_roots
+          .iter()
+          .flat_map(|(_, ids)| ids.iter())
+          .map(|id| (id.clone(), InstallMethod::Symlink))
+          .collect();
+        if links.is_empty() {
+          return Err("No agents found to link to".to_string());
+        }
+        skill_service::link_skill_to_apps(name, &links, &self.target)?;
+        Ok(format!("Linked '{}' to {} agent(s)", name, links.len()))
+      },
+      DoctorFix::ReplaceWithStoreCopy { path, name } => {
+        let path = self.checked_path(path)?;
+        let canonical = self.stored_skill_dir(name)?;
+        if entry_name(&path) != *name {
+          return self.replace_misnamed_copy(&path, name, &canonical);
+        }
+        // The agent copy may hold edits, so a copy of it is kept
+        let kept = if path.is_dir() && !path.is_symlink() {
+          let kept = self.quarantine_path(&path)?;
+          copy_dir_all_sync(&path, &kept)?;
+          Some(kept)
+        } else {
+          None
+        };
+        let mut transaction = FsTransaction::new();
+        transaction.link(&canonical, &path, InstallMethod::Copy);
+        transaction.apply()?.commit();
+        Ok(match kept {
+          Some(kept) => format!(
+            "Replaced {} with {}; the previous copy is in {}",
+            display(&path),
+            display(&canonical),
+            display(&kept)
+          ),
+          None => format!("Replaced {} with {}", display(&path), display(&canonical)),
+        })
+      },
+      DoctorFix::QuarantineFolder { path } => {
+        let path = self.checked_path(path)?;
+        let destination = self.quarantine_folder(&path)?;
+        Ok(format!(
+          "Moved {} to {}",
+          display(&path),
+          display(&destination)
+        ))
+      },
+    }
+  }
+
+  /// Install, update and delete only look for a skill at `<agent folder>/<name>`, so an
+  /// agent copy under another folder name is quarantined and the shared copy is put
+  /// where they expect it, unless something is there already.
+  fn replace_misnamed_copy(
+    &self,
+    path: &Path,
+    name: &str,
+    canonical: &Path,
+  ) -> Result<String, String> {
+    let destination = path.with_file_name(name);
+    let kept = self.quarantine_folder(path)?;
+    if fs::symlink_metadata(&destination).is_ok() {
+      return Ok(format!(
+        "Moved {} to {}; {} is already in place",
+        display(path),
+        display(&kept),
+        display(&destination)
+      ));
+    }
+    let mut transaction = FsTransaction::new();
+    transaction.link(canonical, &destination, self.link_method.clone());
+    transaction.apply()?.commit();
+    Ok(format!(
+      "Moved {} to {} and linked {} to {}",
+      display(path),
+      display(&kept),
+      display(&destination),
+      display(canonical)
+    ))
+  }
+
+  fn quarantine_folder(&self, path: &Path) -> Result<PathBuf, String> {
+    if !path.is_dir() || path.is_symlink() {
+      return Err(format!("{} is not a folder", display(path)));
+    }
+    let destination = self.quarantine_path(path)?;
+    if fs::rename(path, &destination).is_err() {
+      // Another volume: copy, then remove the original
+      copy_dir_all_sync(path, &destination)?;
+      remove_path_any(path)?;
+    }
+    Ok(destination)
+  }
+
+  /// Fresh folder under the quarantine for `path`, named after it and kept apart
+  /// from earlier fixes by a timestamp.
+  fn quarantine_path(&self, path: &Path) -> Result<PathBuf, String> {
+    let batch = self
+      .quarantine
+      .join(Local::now().format("%Y%m%d-%H%M%S%.3f").to_string());
+    fs::create_dir_all(&batch).map_err(|e| format!("Failed to create quarantine folder: {}", e))?;
+    let name = entry_name(path);
+    let mut destination = batch.join(&name);
+    let mut counter = 1;
+    while destination.exists() {
+      counter += 1;
+      destination = batch.join(format!("{}-{}", name, counter));
+    }
+    Ok(destination)
+  }
+
+  fn locked_names(&self) -> Result<BTreeSet<String>, String> {
+    Ok(match &self.target {
+      InstallTarget::Global => read_skill_lock_internal()?
+        .skills
+        .into_keys()
+        .chain(read_native_skill_lock_internal()?.skills.into_keys())
+        .collect(),
+      InstallTarget::Project(project_root) => read_project_skill_lock_internal(project_root)?
+        .skills
+        .into_keys()
+        .collect(),
+    })
+  }
+
+  fn lock_if_missing(&self, name: &str, skill_dir: &Path) -> Result<(), String> {
+    if self.locked_names()?.contains(name) {
+      return Ok(());
+    }
+    match &self.target {
+      InstallTarget::Global => add_skill_to_native_lock(name.to_string()),
+      InstallTarget::Project(project_root) => add_skill_to_project_lock(
+        project_root,
+        name.to_string(),
+        "native".to_string(),
+        SourceType::Native,
+        None,
+        skill_dir,
+      ),
+    }
+  }
+
+  fn stored_skill_dir(&self, name: &str) -> Result<PathBuf, String> {
+    let canonical = canonical_skill_folder_by_name(name, &self.target)?;
+    if !canonical.is_dir() {
+      return Err(format!(
+        "Skill '{}' is not in the shared skills folder",
+        name
+      ));
+    }
+    Ok(canonical)
+  }
+
+  /// Fixes only touch direct children of the store or an agent folder of this scope.
+  fn checked_path(&self, path: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(path);
+    let allowed = path.file_name().is_some()
+      && path.parent().is_some_and(|parent| {
+        parent == self.store || self.agent_roots.iter().any(|(root, _)| root == parent)
+      });
+    if !allowed {
+      return Err(format!(
+        "{} is not inside a skills folder of this scope",
+        display(&path)
+      ));
+    }
+    Ok(path)
+  }
+
+  fn finding(
+    &self,
+    kind: DoctorIssueKind,
+    skill_name: Option<&String>,
+    path: &Path,
+    agent_id: Option<&String>,
+    message: String,
+    fix: DoctorFix,
+  ) -> DoctorFinding {
+    DoctorFinding {
+      id: format!("{:?}:{}", kind, display(path)),
+      kind,
+      scope: self.scope.clone(),
+      project_path: self.project_path.clone(),
+      skill_name: skill_name.cloned(),
+      path: Some(display(path)),
+      agent_id: agent_id.cloned(),
+      message,
+      fix,
+    }
+  }
+}
+
+/// Folders the doctor takes out of skills folders, kept until the user deletes them.
+pub fn quarantine_dir() -> Result<PathBuf, String> {
+  let config_dir = dirs_next::config_dir().ok_or("无法获取配置目录")?;
+  Ok(config_dir.join("youskill").join("doctor-quarantine"))
+}
+
+#[derive(Default)]
+struct HashCache(HashMap<PathBuf, Option<String>>);
+
+impl HashCache {
+  fn get(&mut self, path: &Path) -> Option<String> {
+    self
+      .0
+      .entry(path.to_path_buf())
+      .or_insert_with(|| FolderHelper::compute_skill_folder_hash(path).ok())
+      .clone()
+  }
+}
+
+/// Entries of a skills folder, leaving out hidden ones such as transaction staging.
+fn skill_entries(root: &Path) -> Vec<PathBuf> {
+  let Ok(entries) = fs::read_dir(root) else {
+    return Vec::new();
+  };
+  let mut paths: Vec<PathBuf> = entries
+    .filter_map(Result::ok)
+    .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+    .map(|entry| entry.path())
+    .collect();
+  paths.sort();
+  paths
+}
+
+fn read_skill_name(skill_dir: &Path) -> Result<String, String> {
+  let skill_md = skill_dir.join(SKILL_MD_FILE_NAME);
+  if !skill_md.is_file() {
+    return Err(format!(
+      "{} has no {}",
+      display(skill_dir),
+      SKILL_MD_FILE_NAME
+    ));
+  }
+  let frontmatter = FileHelper::read_skill_frontmatter(&skill_md)
+    .map_err(|err| format!("{} cannot be read: {}", display(&skill_md), err))?;
+  frontmatter
+    .name
+    .filter(|name| !name.trim().is_empty())
+    .ok_or_else(|| format!("{} has no name", display(&skill_md)))
+}
+
+fn is_broken_link(path: &Path) -> bool {
+  path.is_symlink() && !path.exists()
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+  match (fs::canonicalize(a), fs::canonicalize(b)) {
+    (Ok(a), Ok(b)) => a == b,
+    _ => false,
+  }
+}
+
+fn entry_name(path: &Path) -> String {
+  path
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_default()
+}
+
+fn display(path: &Path) -> String {
+  path.to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reads_skill_names_and_reports_unreadable_folders() {
+    let root = std::env::temp_dir().join(format!("youskill-doctor-test-{}", std::process::id()));
+    let valid = root.join("valid");
+    let nameless = root.join("nameless");
+    let empty = root.join("empty");
+    for dir in [&valid, &nameless, &empty] {
+      fs::create_dir_all(dir).unwrap();
+    }
+    fs::write(valid.join("SKILL.md"), "---\nname: valid\n---\n").unwrap();
+    fs::write(
+      nameless.join("SKILL.md"),
+      "---\ndescription: no name\n---\n",
+    )
+    .unwrap();
+
+    let names: Vec<Result<String, String>> = [&valid, &nameless, &empty]
+      .into_iter()
+      .map(|dir| read_skill_name(dir))
+      .collect();
+    let entries = skill_entries(&root);
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(names[0], Ok("valid".to_string()));
+    assert!(names[1].is_err());
+    assert!(names[2].is_err());
+    assert_eq!(entries.len(), 3);
+  }
+
+  fn write_skill(dir: &Path, frontmatter: &str, body: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+      dir.join(SKILL_MD_FILE_NAME),
+      format!("---\n{}\n---\n{}", frontmatter, body),
+    )
+    .unwrap();
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn fixes_quarantine_folders_and_leave_agent_data_alone() {
+    use std::os::unix::fs::symlink;
+
+    let root = std::env::temp_dir().join(format!("youskill-doctor-fix-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let project = root.join("project");
+    let target = InstallTarget::Project(project.clone());
+    let store = target.skill_folder_path().unwrap();
+    let agent_root = project.join(".claude").join("skills");
+    let scope = DoctorScope {
+      scope: InstallScope::Project,
+      project_path: Some(display(&project)),
+      target,
+      store: store.clone(),
+      agent_roots: vec![(agent_root.clone(), vec!["claude-code".to_string()])],
+      store_is_agent_root: false,
+      quarantine: root.join("quarantine"),
+      link_method: InstallMethod::Symlink,
+    };
+
+    // Store: a skill, a differing copy of it, a typo'd SKILL.md and a dangling link
+    write_skill(&store.join("alpha"), "name: alpha", "shared");
+    write_skill(&store.join("alpha-old"), "name: alpha", "edited by hand");
+    write_skill(&store.join("typo"), "name: [unclosed", "");
+    symlink(root.join("nowhere"), store.join("dangling")).unwrap();
+    // Agent folder: an agent's own data, a dangling link and an edited copy of the
+    // shared skill under another folder name
+    fs::create_dir_all(&agent_root).unwrap();
+    fs::create_dir_all(agent_root.join("cache")).unwrap();
+    fs::write(agent_root.join("cache").join("state.json"), "{}").unwrap();
+    symlink(root.join("nowhere"), agent_root.join("stale")).unwrap();
+    write_skill(&agent_root.join("alpha-edit"), "name: alpha", "local edit");
+    // Lock: alpha, plus a skill whose folder is gone
+    add_skill_to_project_lock(
+      &project,
+      "alpha".to_string(),
+      "native".to_string(),
+      SourceType::Native,
+      None,
+      &store.join("alpha"),
+    )
+    .unwrap();
+    write_skill(&root.join("gone"), "name: gone", "");
+    add_skill_to_project_lock(
+      &project,
+      "gone".to_string(),
+      "native".to_string(),
+      SourceType::Native,
+      None,
+      &root.join("gone"),
+    )
+    .unwrap();
+
+    let findings = scope.scan().unwrap();
+    let mut fixes: Vec<(DoctorIssueKind, DoctorFix)> = findings
+      .iter()
+      .map(|finding| (finding.kind, finding.fix.clone()))
+      .collect();
+    fixes.sort_by_key(|(_, fix)| format!("{:?}", fix));
+    let mut expected = vec![
+      (
+        DoctorIssueKind::DuplicateName,
+        DoctorFix::QuarantineFolder {
+          path: display(&store.join("alpha-old")),
+        },
+      ),
+      (
+        DoctorIssueKind::UnreadableSkillMd,
+        DoctorFix::QuarantineFolder {
+          path: display(&store.join("typo")),
+        },
+      ),
+      (
+        DoctorIssueKind::BrokenLink,
+        DoctorFix::RemoveLink {
+          path: display(&agent_root.join("stale")),
+        },
+      ),
+      (
+        DoctorIssueKind::BrokenLink,
+        DoctorFix::RemoveLink {
+          path: display(&store.join("dangling")),
+        },
+      ),
+      (
+        DoctorIssueKind::MissingLockedFolder,
+        DoctorFix::RemoveLockEntry {
+          name: "gone".to_string(),
+        },
+      ),
+      (
+        DoctorIssueKind::DuplicateName,
+        DoctorFix::ReplaceWithStoreCopy {
+          path: display(&agent_root.join("alpha-edit")),
+          name: "alpha".to_string(),
+        },
+      ),
+    ];
+    expected.sort_by_key(|(_, fix)| format!("{:?}", fix));
+    assert_eq!(fixes, expected);
+
+    for finding in &findings {
+      scope.apply(&finding.fix).unwrap();
+    }
+    let remaining = scope.scan().unwrap();
+    let mut quarantined: Vec<String> = fs::read_dir(root.join("quarantine"))
+      .unwrap()
+      .filter_map(Result::ok)
+      .flat_map(|batch| fs::read_dir(batch.path()).unwrap())
+      .filter_map(Result::ok)
+      .map(|entry| entry.file_name().to_string_lossy().to_string())
+      .collect();
+    let kept_data = agent_root.join("cache").join("state.json").is_file();
+    let misnamed_left = agent_root.join("alpha-edit").exists();
+    let relinked = fs::read_link(agent_root.join("alpha"));
+    let locked = scope.locked_names().unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(remaining.is_empty(), "{:?}", remaining);
+    quarantined.sort();
+    assert_eq!(quarantined, vec!["alpha-edit", "alpha-old", "typo"]);
+    assert!(kept_data);
+    assert!(!misnamed_left);
+    assert_eq!(relinked.unwrap(), store.join("alpha"));
+    assert_eq!(locked.into_iter().collect::<Vec<_>>(), vec!["alpha"]);
+  }
+}
@@ -2,12 +2,23 @@
 // Commands should only handle Tauri API and delegate to services
 
 pub mod agent_apps_service;
+pub mod agent_detection_service;
+pub mod agent_registry_service;
 pub mod ai_service;
 pub mod backup_service;
+pub mod backup_store_service;
+pub mod doctor_service;
 pub mod native_skill_lock_service;
+pub mod project_drift_service;
 pub mod project_skill_lock_service;
 pub mod remote_service;
+pub mod scheduler_service;
+pub mod skill_lint_service;
 pub mod skill_lock_service;
+pub mod skill_pack_service;
 pub mod skill_service;
+pub mod skill_watch_service;
+pub mod source_host_service;
 pub mod translate_service;
 pub mod user_projects_service;
+pub mod workspace_service;
@@ -32,7 +32,7 @@ fn default_version() -> i64 {
   1
 }
 
-fn native_skill_lock_path() -> Result<PathBuf, String> {
+pub fn native_skill_lock_path() -> Result<PathBuf, String> {
   let config_dir = dirs_next::config_dir().ok_or("Unable to get config directory")?;
   Ok(config_dir.join("youskill").join(LOCK_FILE))
 }
@@ -0,0 +1,189 @@
+use crate::models::{
+  FileChangeKind, InstallScope, InstallTarget, SkillDriftItem, SkillDriftStatus, SkillFileChange,
+  SourceType,
+};
+use crate::services::project_skill_lock_service::{
+  add_skill_to_project_lock, read_project_skill_lock_internal, relock_skill_in_project_lock,
+  remove_skill_from_project_lock, ProjectSkillLockEntry,
+};
+use crate::services::skill_service;
+use crate::utils::folder::FolderHelper;
+use crate::utils::path::canonical_skill_folder_by_name;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Compare the skills on disk in a project with its skills-lock.json.
+pub fn check_project_drift(project_path: String) -> Result<Vec<SkillDriftItem>, String> {
+  let install_target =
+    InstallTarget::from_scope(&InstallScope::Project, &Some(project_path.clone()))?;
+  let project_root = install_target.root_path()?;
+  let lock = read_project_skill_lock_internal(&project_root)?;
+  let local_dirs = local_skill_dirs(&project_path)?;
+
+  let mut items = Vec::new();
+  for (name, entry) in &lock.skills {
+    let skill_dir = match local_dirs.get(name) {
+      Some(dir) => dir.clone(),
+      None => canonical_skill_folder_by_name(name, &install_target)?,
+    };
+    items.push(locked_skill_drift(name, entry, &skill_dir)?);
+  }
+  for (name, dir) in local_dirs {
+    if lock.skills.contains_key(&name) {
+      continue;
+    }
+    items.push(SkillDriftItem {
+      name,
+      status: SkillDriftStatus::Untracked,
+      path: Some(dir.to_string_lossy().to_string()),
+      source: None,
+      expected_hash: None,
+      actual_hash: FolderHelper::compute_skill_folder_hash(&dir).ok(),
+      changes: Vec::new(),
+      restorable: false,
+    });
+  }
+  items.sort_by(|a, b| a.name.cmp(&b.name));
+  Ok(items)
+}
+
+/// Accept the local state of a skill into skills-lock.json: modified skills get their
+/// current hash, missing skills are dropped and untracked skills are added as native.
+pub fn relock_project_skill(project_path: String, name: String) -> Result<(), String> {
+  let install_target =
+    InstallTarget::from_scope(&InstallScope::Project, &Some(project_path.clone()))?;
+  let project_root = install_target.root_path()?;
+  let lock = read_project_skill_lock_internal(&project_root)?;
+  let skill_dir = local_skill_dirs(&project_path)?.remove(&name);
+
+  match (lock.skills.contains_key(&name), skill_dir) {
+    (true, Some(dir)) => relock_skill_in_project_lock(&project_root, &name, &dir),
+    (true, None) => remove_skill_from_project_lock(&project_root, name).map(|_| ()),
+    (false, Some(dir)) => add_skill_to_project_lock(
+      &project_root,
+      name,
+      "native".to_string(),
+      SourceType::Native,
+      None,
+      &dir,
+    ),
+    (false, None) => Err(format!("Skill '{}' is not installed in this project", name)),
+  }
+}
+
+/// Folder of each skill installed in the project: the shared copy when there is one,
+/// otherwise the first agent folder holding it.
+fn local_skill_dirs(project_path: &str) -> Result<HashMap<String, PathBuf>, String> {
+  let skills = skill_service::list_skills(InstallScope::Project, Some(project_path.to_string()))?;
+  Ok(
+    skills
+      .into_iter()
+      .filter_map(|skill| {
+        let dir = skill.root_folder.or_else(|| {
+          skill
+            .installed_agent_apps
+            .first()
+            .map(|app| app.skill_folder.clone())
+        })?;
+        Some((skill.name, PathBuf::from(dir)))
+      })
+      .collect(),
+  )
+}
+
+fn locked_skill_drift(
+  name: &str,
+  entry: &ProjectSkillLockEntry,
+  skill_dir: &Path,
+) -> Result<SkillDriftItem, String> {
+  let mut item = SkillDriftItem {
+    name: name.to_string(),
+    status: SkillDriftStatus::Clean,
+    path: Some(skill_dir.to_string_lossy().to_string()),
+    source: Some(entry.source.clone()),
+    expected_hash: Some(entry.computed_hash.clone()),
+    actual_hash: None,
+    changes: Vec::new(),
+    restorable: entry.provider().is_some() && !entry.source.trim().is_empty(),
+  };
+  if !skill_dir.is_dir() {
+    item.status = SkillDriftStatus::Missing;
+    item.path = None;
+    return Ok(item);
+  }
+
+  let actual_hash = FolderHelper::compute_skill_folder_hash(skill_dir)?;
+  if actual_hash != entry.computed_hash {
+    item.status = SkillDriftStatus::Modified;
+    if !entry.file_hashes.is_empty() {
+      let actual_files = FolderHelper::compute_skill_file_hashes(skill_dir)?;
+      item.changes = diff_file_hashes(&entry.file_hashes, &actual_files);
+    }
+  }
+  item.actual_hash = Some(actual_hash);
+  Ok(item)
+}
+
+fn diff_file_hashes(
+  expected: &BTreeMap<String, String>,
+  actual: &BTreeMap<String, String>,
+) -> Vec<SkillFileChange> {
+  let mut changes: Vec<SkillFileChange> = expected
+    .iter()
+    .filter_map(|(path, hash)| {
+      let change = match actual.get(path) {
+        None => FileChangeKind::Removed,
+        Some(actual_hash) if actual_hash != hash => FileChangeKind::Modified,
+        Some(_) => return None,
+      };
+      Some(SkillFileChange {
+        path: path.clone(),
+        change,
+      })
+    })
+    .collect();
+  changes.extend(
+    actual
+      .keys()
+      .filter(|path| !expected.contains_key(*path))
+      .map(|path| SkillFileChange {
+        path: path.clone(),
+        change: FileChangeKind::Added,
+      }),
+  );
+  changes.sort_by(|a, b| a.path.cmp(&b.path));
+  changes
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn diff_reports_added_removed_and_modified_files() {
+    let expected = BTreeMap::from([
+      ("SKILL.md".to_string(), "a".to_string()),
+      ("ref/old.md".to_string(), "b".to_string()),
+      ("script.sh".to_string(), "c".to_string()),
+    ]);
+    let actual = BTreeMap::from([
+      ("SKILL.md".to_string(), "a2".to_string()),
+      ("ref/new.md".to_string(), "d".to_string()),
+      ("script.sh".to_string(), "c".to_string()),
+    ]);
+
+    let changes: Vec<(String, FileChangeKind)> = diff_file_hashes(&expected, &actual)
+      .into_iter()
+      .map(|change| (change.path, change.change))
+      .collect();
+
+    assert_eq!(
+      changes,
+      vec![
+        ("SKILL.md".to_string(), FileChangeKind::Modified),
+        ("ref/new.md".to_string(), FileChangeKind::Added),
+        ("ref/old.md".to_string(), FileChangeKind::Removed),
+      ]
+    );
+  }
+}
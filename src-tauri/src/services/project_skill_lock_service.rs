@@ -1,5 +1,6 @@
-use crate::models::SourceType;
+use crate::models::{GitRefKind, ProviderKind, SourceRef, SourceType};
 use crate::utils::folder::FolderHelper;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -12,6 +13,29 @@ pub struct ProjectSkillLockEntry {
   pub source: String,
   pub source_type: String,
   pub computed_hash: String,
+  #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+  pub git_ref: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub ref_kind: Option<GitRefKind>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub resolved_commit: Option<String>,
+  /// Per-file SHA-256 at lock time, used to name the files behind a hash change
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub file_hashes: BTreeMap<String, String>,
+}
+
+impl ProjectSkillLockEntry {
+  pub fn provider(&self) -> Option<ProviderKind> {
+    ProviderKind::from_lock_value(&self.source_type)
+  }
+
+  pub fn source_ref(&self) -> SourceRef {
+    SourceRef {
+      git_ref: self.git_ref.clone(),
+      ref_kind: self.ref_kind,
+      resolved_commit: self.resolved_commit.clone(),
+    }
+  }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -19,14 +43,14 @@ pub struct ProjectSkillLockFile {
   #[serde(default = "default_version")]
   pub version: i64,
   #[serde(default)]
-  pub skills: std::collections::BTreeMap<String, ProjectSkillLockEntry>,
+  pub skills: BTreeMap<String, ProjectSkillLockEntry>,
 }
 
 impl Default for ProjectSkillLockFile {
   fn default() -> Self {
     Self {
       version: CURRENT_VERSION,
-      skills: std::collections::BTreeMap::new(),
+      skills: BTreeMap::new(),
     }
   }
 }
@@ -35,7 +59,7 @@ fn default_version() -> i64 {
   CURRENT_VERSION
 }
 
-fn project_lock_path(project_root: &Path) -> std::path::PathBuf {
+pub fn project_lock_path(project_root: &Path) -> std::path::PathBuf {
   project_root.join(PROJECT_LOCK_FILE)
 }
 
@@ -72,11 +96,14 @@ pub fn add_skill_to_project_lock(
   skill_name: String,
   source: String,
   source_type: SourceType,
+  source_ref: Option<&SourceRef>,
   skill_dir: &Path,
 ) -> Result<(), String> {
   let mut lock = read_project_skill_lock_internal(project_root)?;
   lock.version = CURRENT_VERSION;
   let computed_hash = FolderHelper::compute_skill_folder_hash(skill_dir)?;
+  let file_hashes = FolderHelper::compute_skill_file_hashes(skill_dir)?;
+  let source_ref = source_ref.cloned().unwrap_or_default();
 
   lock.skills.insert(
     skill_name,
@@ -84,11 +111,32 @@ pub fn add_skill_to_project_lock(
       source,
       source_type: source_type_to_lock_value(&source_type).to_string(),
       computed_hash,
+      git_ref: source_ref.git_ref,
+      ref_kind: source_ref.ref_kind,
+      resolved_commit: source_ref.resolved_commit,
+      file_hashes,
     },
   );
   write_project_skill_lock_internal(project_root, &lock)
 }
 
+/// Record the current content of a locked skill as its expected state, keeping the
+/// recorded source and ref.
+pub fn relock_skill_in_project_lock(
+  project_root: &Path,
+  skill_name: &str,
+  skill_dir: &Path,
+) -> Result<(), String> {
+  let mut lock = read_project_skill_lock_internal(project_root)?;
+  let entry = lock
+    .skills
+    .get_mut(skill_name)
+    .ok_or_else(|| format!("Skill '{}' is not in skills-lock.json", skill_name))?;
+  entry.computed_hash = FolderHelper::compute_skill_folder_hash(skill_dir)?;
+  entry.file_hashes = FolderHelper::compute_skill_file_hashes(skill_dir)?;
+  write_project_skill_lock_internal(project_root, &lock)
+}
+
 pub fn remove_skill_from_project_lock(
   project_root: &Path,
   skill_name: String,
@@ -109,8 +157,10 @@ pub fn project_lock_source_type(
   let Some(entry) = project_lock.skills.get(skill_name) else {
     return SourceType::Unknown;
   };
+  if let Some(kind) = entry.provider() {
+    return kind.into();
+  }
   match entry.source_type.as_str() {
-    "github" => SourceType::Github,
     "native" => SourceType::Native,
     _ => SourceType::Unknown,
   }
@@ -121,9 +171,7 @@ pub fn project_lock_source(
   project_lock: &ProjectSkillLockFile,
 ) -> Option<String> {
   let entry = project_lock.skills.get(skill_name)?;
-  if entry.source_type != "github" {
-    return None;
-  }
+  entry.provider()?;
   let source = entry.source.trim();
   if source.is_empty() {
     return None;
@@ -132,9 +180,11 @@ pub fn project_lock_source(
 }
 
 fn source_type_to_lock_value(source_type: &SourceType) -> &'static str {
+  if let Some(kind) = source_type.provider() {
+    return kind.as_str();
+  }
   match source_type {
-    SourceType::Github => "github",
     SourceType::Native => "native",
-    SourceType::Unknown => "unknown",
+    _ => "unknown",
   }
 }
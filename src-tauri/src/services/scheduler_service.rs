@@ -0,0 +1,342 @@
+use crate::config::{config_path, load_config, AppConfig, BackupSchedule};
+use crate::models::{
+  InstallScope, RemoteSkill, ScheduleState, ScheduledJob, ScheduledJobRun, SkillUpdateCheckItem,
+};
+use crate::services::{backup_service, remote_service, skill_service};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Event sent to the UI after every scheduled run.
+pub const SCHEDULED_JOB_EVENT: &str = "scheduled-job-finished";
+
+const SCHEDULE_STATE_FILE: &str = "schedule-state.json";
+/// Schedules are in days and hours; checking once a minute is plenty.
+const TICK: Duration = Duration::from_secs(60);
+const STOP_POLL: Duration = Duration::from_secs(1);
+/// A failed run is retried after this long instead of waiting a full interval.
+const RETRY_AFTER_HOURS: i64 = 1;
+
+/// Background thread running scheduled backups and update checks. Stops when dropped.
+pub struct Scheduler {
+  stop: Arc<AtomicBool>,
+  thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for Scheduler {
+  fn drop(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+/// Start the scheduler and call `on_run` after each job it runs. Jobs overdue from
+/// before the last shutdown run on the first tick.
+pub fn start_scheduler<F>(on_run: F) -> Result<Scheduler, String>
+where
+  F: Fn(ScheduledJobRun) + Send + 'static,
+{
+  let runtime = tokio::runtime::Builder::new_current_thread()
+    .enable_all()
+    .build()
+    .map_err(|e| format!("Failed to start scheduler runtime: {}", e))?;
+  let stop = Arc::new(AtomicBool::new(false));
+  let stop_flag = stop.clone();
+
+  let thread = std::thread::Builder::new()
+    .name("scheduler".to_string())
+    .spawn(move || {
+      let mut next_tick = Instant::now();
+      while !stop_flag.load(Ordering::Relaxed) {
+        if Instant::now() < next_tick {
+          std::thread::sleep(STOP_POLL);
+          continue;
+        }
+        next_tick = Instant::now() + TICK;
+        for run in runtime.block_on(run_due_jobs()) {
+          on_run(run);
+        }
+      }
+    })
+    .map_err(|e| format!("Failed to start scheduler thread: {}", e))?;
+
+  Ok(Scheduler {
+    stop,
+    thread: Some(thread),
+  })
+}
+
+/// Last recorded run of each scheduled job.
+pub fn read_schedule_state() -> Result<ScheduleState, String> {
+  let path = schedule_state_path()?;
+  if !path.exists() {
+    return Ok(ScheduleState::default());
+  }
+  let content =
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read schedule state: {}", e))?;
+  serde_json::from_str(&content).map_err(|e| format!("Failed to parse schedule state: {}", e))
+}
+
+fn save_schedule_state(state: &ScheduleState) -> Result<(), String> {
+  let path = schedule_state_path()?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create config folder: {}", e))?;
+  }
+  let content = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+  fs::write(&path, content).map_err(|e| format!("Failed to write schedule state: {}", e))
+}
+
+fn schedule_state_path() -> Result<PathBuf, String> {
+  let config_path = config_path()?;
+  let dir = config_path
+    .parent()
+    .ok_or("Config path has no parent folder")?;
+  Ok(dir.join(SCHEDULE_STATE_FILE))
+}
+
+/// Compare tracked global skills against the registry, returning the ones with a
+/// newer version. Mirrors the check the UI runs on the local skills list.
+pub async fn check_skill_updates_now() -> Result<Vec<RemoteSkill>, String> {
+  let names: Vec<String> = skill_service::list_skills(InstallScope::Global, None)?
+    .into_iter()
+    .map(|skill| skill.name)
+    .collect();
+  if names.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  outdated_skills(remote_service::fetch_skills_by_names(names).await?)
+}
+
+/// Narrow registry entries down to the skills whose installed version differs. Only
+/// reads the skill lock, so it is cheap enough to re-run after every local change.
+pub fn outdated_skills(remote: Vec<RemoteSkill>) -> Result<Vec<RemoteSkill>, String> {
+  let checks: Vec<SkillUpdateCheckItem> = remote
+    .iter()
+    .filter_map(|skill| {
+      Some(SkillUpdateCheckItem {
+        name: skill.name.clone(),
+        source: skill.source.clone(),
+        remote_sha: skill.skill_path_sha.clone()?,
+        branch: skill.branch.clone(),
+      })
+    })
+    .collect();
+  let updated: HashSet<String> = skill_service::check_skills_updates(checks)?
+    .into_iter()
+    .collect();
+  Ok(
+    remote
+      .into_iter()
+      .filter(|skill| updated.contains(&skill.name))
+      .collect(),
+  )
+}
+
+/// Run `job` right away, outside its schedule, and record it like a scheduled run.
+pub async fn run_job_now(job: ScheduledJob) -> ScheduledJobRun {
+  let run = match job {
+    ScheduledJob::Backup => match load_config() {
+      Ok(config) => run_backup(&config).await,
+      Err(err) => finished(ScheduledJob::Backup, false, Some(err)),
+    },
+    ScheduledJob::UpdateCheck => run_update_check().await,
+  };
+  let mut state = read_schedule_state().unwrap_or_default();
+  match job {
+    ScheduledJob::Backup => state.backup = Some(run.clone()),
+    ScheduledJob::UpdateCheck => state.update_check = Some(run.clone()),
+  }
+  if let Err(err) = save_schedule_state(&state) {
+    tracing::warn!("Failed to save schedule state: {}", err);
+  }
+  run
+}
+
+async fn run_due_jobs() -> Vec<ScheduledJobRun> {
+  let config = match load_config() {
+    Ok(config) => config,
+    Err(err) => {
+      tracing::warn!("Scheduler could not load config: {}", err);
+      return Vec::new();
+    },
+  };
+  let mut state = read_schedule_state().unwrap_or_else(|err| {
+    tracing::warn!("Starting with empty schedule state: {}", err);
+    ScheduleState::default()
+  });
+  let now = Utc::now();
+  let mut runs = Vec::new();
+
+  if let Some(interval) = backup_interval(config.backup_schedule) {
+    let last_manual = config
+      .last_backup_time
+      .as_deref()
+      .and_then(parse_backup_time);
+    if is_due(state.backup.as_ref(), last_manual, interval, now) {
+      let run = run_backup(&config).await;
+      state.backup = Some(run.clone());
+      runs.push(run);
+    }
+  }
+
+  if config.update_check_hours > 0 {
+    let interval = chrono::Duration::hours(i64::from(config.update_check_hours));
+    if is_due(state.update_check.as_ref(), None, interval, now) {
+      let run = run_update_check().await;
+      state.update_check = Some(run.clone());
+      runs.push(run);
+    }
+  }
+
+  if !runs.is_empty() {
+    if let Err(err) = save_schedule_state(&state) {
+      tracing::warn!("Failed to save schedule state: {}", err);
+    }
+  }
+  runs
+}
+
+async fn run_backup(config: &AppConfig) -> ScheduledJobRun {
+  let Some(folder) = config
+    .backup_folder
+    .clone()
+    .filter(|folder| !folder.trim().is_empty())
+  else {
+    return finished(
+      ScheduledJob::Backup,
+      false,
+      Some("No backup folder configured".to_string()),
+    );
+  };
+  match backup_service::backup_skills(folder).await {
+    Ok(result) => ScheduledJobRun {
+      backup_path: result.backup_path,
+      ..finished(ScheduledJob::Backup, result.success, Some(result.message))
+    },
+    Err(err) => finished(ScheduledJob::Backup, false, Some(err)),
+  }
+}
+
+async fn run_update_check() -> ScheduledJobRun {
+  match check_skill_updates_now().await {
+    Ok(updates) => ScheduledJobRun {
+      updates,
+      ..finished(ScheduledJob::UpdateCheck, true, None)
+    },
+    Err(err) => finished(ScheduledJob::UpdateCheck, false, Some(err)),
+  }
+}
+
+fn finished(job: ScheduledJob, success: bool, message: Option<String>) -> ScheduledJobRun {
+  ScheduledJobRun {
+    job,
+    finished_at: Utc::now().to_rfc3339(),
+    success,
+    message,
+    backup_path: None,
+    updates: Vec::new(),
+  }
+}
+
+fn backup_interval(schedule: BackupSchedule) -> Option<chrono::Duration> {
+  match schedule {
+    BackupSchedule::Off => None,
+    BackupSchedule::Daily => Some(chrono::Duration::days(1)),
+    BackupSchedule::Weekly => Some(chrono::Duration::weeks(1)),
+  }
+}
+
+/// `last_backup_time` is stored in local time for display.
+fn parse_backup_time(value: &str) -> Option<DateTime<Utc>> {
+  let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").ok()?;
+  Local
+    .from_local_datetime(&naive)
+    .single()
+    .map(|time| time.with_timezone(&Utc))
+}
+
+/// A job is due once `interval` has passed since its last successful run, or since
+/// `also_ran` (e.g. a manual backup). Failures back off for `RETRY_AFTER_HOURS`.
+fn is_due(
+  last: Option<&ScheduledJobRun>,
+  also_ran: Option<DateTime<Utc>>,
+  interval: chrono::Duration,
+  now: DateTime<Utc>,
+) -> bool {
+  let last_run = last.and_then(|run| {
+    DateTime::parse_from_rfc3339(&run.finished_at)
+      .ok()
+      .map(|time| (time.with_timezone(&Utc), run.success))
+  });
+  let mut next = match last_run {
+    Some((time, true)) => Some(time + interval),
+    Some((time, false)) => Some(time + interval.min(chrono::Duration::hours(RETRY_AFTER_HOURS))),
+    None => None,
+  };
+  if let Some(time) = also_ran {
+    next = next.max(Some(time + interval));
+  }
+  next.is_none_or(|next| now >= next)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn run_at(time: DateTime<Utc>, success: bool) -> ScheduledJobRun {
+    ScheduledJobRun {
+      finished_at: time.to_rfc3339(),
+      ..finished(ScheduledJob::Backup, success, None)
+    }
+  }
+
+  #[test]
+  fn due_after_interval_with_retry_backoff_and_manual_runs() {
+    let now = Utc::now();
+    let day = chrono::Duration::days(1);
+
+    assert!(is_due(None, None, day, now));
+    assert!(!is_due(
+      Some(&run_at(now - chrono::Duration::hours(2), true)),
+      None,
+      day,
+      now
+    ));
+    assert!(is_due(
+      Some(&run_at(now - chrono::Duration::hours(25), true)),
+      None,
+      day,
+      now
+    ));
+    // Failed runs retry within the hour rather than a day later
+    assert!(is_due(
+      Some(&run_at(now - chrono::Duration::hours(2), false)),
+      None,
+      day,
+      now
+    ));
+    assert!(!is_due(
+      Some(&run_at(now - chrono::Duration::minutes(5), false)),
+      None,
+      day,
+      now
+    ));
+    // A recent manual backup pushes the schedule out
+    let manual = Some(now - chrono::Duration::hours(3));
+    assert!(!is_due(None, manual, day, now));
+    assert!(!is_due(
+      Some(&run_at(now - chrono::Duration::hours(30), true)),
+      manual,
+      day,
+      now
+    ));
+  }
+}
@@ -0,0 +1,581 @@
+use crate::models::{InstallScope, LintSeverity, SkillDiagnostic, SkillLintReport};
+use crate::services::{skill_service, user_projects_service};
+use crate::utils::file::FileHelper;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+const SKILL_MD_FILE_NAME: &str = "SKILL.md";
+const MAX_NAME_LENGTH: usize = 64;
+const MAX_DESCRIPTION_LENGTH: usize = 1024;
+/// Shorter descriptions rarely tell the agent when to use the skill.
+const MIN_DESCRIPTION_LENGTH: usize = 20;
+const MAX_COMPATIBILITY_LENGTH: usize = 500;
+/// The spec recommends moving detail into referenced files past this point.
+const MAX_SKILL_MD_LINES: usize = 500;
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Lint a skill folder, or the folder holding the given SKILL.md.
+pub fn lint_skill(path: String) -> Result<SkillLintReport, String> {
+  let path = PathBuf::from(path.trim());
+  let skill_dir = if path.is_file() {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+  } else {
+    path
+  };
+  if !skill_dir.is_dir() {
+    return Err(format!(
+      "Skill directory does not exist: {}",
+      skill_dir.to_string_lossy()
+    ));
+  }
+  Ok(lint_skill_dir(&skill_dir))
+}
+
+/// Lint every installed skill, globally and in each saved project.
+pub fn lint_installed_skills() -> Result<Vec<SkillLintReport>, String> {
+  let mut targets = vec![(InstallScope::Global, None)];
+  for project in user_projects_service::list_user_projects()? {
+    targets.push((InstallScope::Project, Some(project.path)));
+  }
+
+  let mut reports = Vec::new();
+  for (scope, project_path) in targets {
+    let skills = match skill_service::list_skills(scope.clone(), project_path.clone()) {
+      Ok(skills) => skills,
+      Err(err) => {
+        tracing::warn!("Skipping lint for {:?} {:?}: {}", scope, project_path, err);
+        continue;
+      },
+    };
+    for skill in skills {
+      let skill_dir = skill.root_folder.or_else(|| {
+        skill
+          .installed_agent_apps
+          .first()
+          .map(|app| app.skill_folder.clone())
+      });
+      let Some(skill_dir) = skill_dir else {
+        continue;
+      };
+      let mut report = lint_skill_dir(Path::new(&skill_dir));
+      report.name = report.name.or(Some(skill.name));
+      report.scope = Some(scope.clone());
+      report.project_path = project_path.clone();
+      reports.push(report);
+    }
+  }
+  Ok(reports)
+}
+
+pub fn lint_skill_dir(skill_dir: &Path) -> SkillLintReport {
+  let mut diagnostics = Vec::new();
+  let skill_md = skill_dir.join(SKILL_MD_FILE_NAME);
+
+  let name = if !skill_md.is_file() {
+    diagnostics.push(diagnostic(
+      LintSeverity::Error,
+      "skill-md-missing",
+      "SKILL.md not found in the skill folder".to_string(),
+      None,
+      None,
+    ));
+    None
+  } else {
+    match read_markdown(&skill_md, SKILL_MD_FILE_NAME, &mut diagnostics) {
+      Some(content) => lint_skill_md(skill_dir, &content, &mut diagnostics),
+      None => None,
+    }
+  };
+
+  lint_other_files(skill_dir, &mut diagnostics);
+  diagnostics.sort_by(|a, b| (a.severity, &a.file, a.line).cmp(&(b.severity, &b.file, b.line)));
+
+  SkillLintReport {
+    name,
+    path: skill_dir.to_string_lossy().to_string(),
+    scope: None,
+    project_path: None,
+    diagnostics,
+  }
+}
+
+/// Check the frontmatter and body of SKILL.md and return the skill name, if valid YAML
+/// declares one.
+fn lint_skill_md(
+  skill_dir: &Path,
+  content: &str,
+  diagnostics: &mut Vec<SkillDiagnostic>,
+) -> Option<String> {
+  let lines: Vec<&str> = content.lines().collect();
+  lint_links(skill_dir, SKILL_MD_FILE_NAME, content, diagnostics);
+  if lines.len() > MAX_SKILL_MD_LINES {
+    diagnostics.push(skill_md_diagnostic(
+      LintSeverity::Warning,
+      "skill-md-too-long",
+      format!(
+        "SKILL.md has {} lines; consider moving detail into referenced files (recommended: under {})",
+        lines.len(),
+        MAX_SKILL_MD_LINES
+      ),
+      None,
+    ));
+  }
+
+  if lines.first().map(|line| line.trim()) != Some("---") {
+    diagnostics.push(skill_md_diagnostic(
+      LintSeverity::Error,
+      "frontmatter-missing",
+      "SKILL.md must start with a YAML frontmatter block delimited by ---".to_string(),
+      Some(1),
+    ));
+    return None;
+  }
+  let Some(close) = lines.iter().skip(1).position(|line| line.trim() == "---") else {
+    diagnostics.push(skill_md_diagnostic(
+      LintSeverity::Error,
+      "frontmatter-unclosed",
+      "Frontmatter is not closed with ---".to_string(),
+      Some(1),
+    ));
+    return None;
+  };
+  let yaml_lines = &lines[1..=close];
+
+  // Frontmatter starts on the second line of the file.
+  let yaml = yaml_lines.join("\n");
+  if let Err(err) = serde_yaml::from_str::<serde_yaml::Value>(&yaml) {
+    diagnostics.push(skill_md_diagnostic(
+      LintSeverity::Error,
+      "frontmatter-invalid",
+      format!("Invalid YAML in frontmatter: {}", err),
+      err.location().map(|location| location.line() + 1),
+    ));
+    return None;
+  }
+  let frontmatter = match FileHelper::parse_skill_frontmatter(content) {
+    Ok(frontmatter) => frontmatter,
+    Err(err) => {
+      diagnostics.push(skill_md_diagnostic(
+        LintSeverity::Error,
+        "frontmatter-invalid",
+        err,
+        Some(1),
+      ));
+      return None;
+    },
+  };
+  let key_line = |key: &str| {
+    yaml_lines
+      .iter()
+      .position(|line| {
+        line
+          .strip_prefix(key)
+          .is_some_and(|rest| rest.trim_start().starts_with(':'))
+      })
+      .map(|index| index + 2)
+  };
+
+  match &frontmatter.name {
+    None => diagnostics.push(skill_md_diagnostic(
+      LintSeverity::Error,
+      "name-missing",
+      "Frontmatter is missing 'name'".to_string(),
+      key_line("name").or(Some(1)),
+    )),
+    Some(name) => {
+      if let Some(problem) = name_format_problem(name) {
+        diagnostics.push(skill_md_diagnostic(
+          LintSeverity::Error,
+          "name-format",
+          format!("Invalid name '{}': {}", name, problem),
+          key_line("name"),
+        ));
+      }
+      let folder_name = skill_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+      if !folder_name.is_empty() && &folder_name != name {
+        diagnostics.push(skill_md_diagnostic(
+          LintSeverity::Error,
+          "name-folder-mismatch",
+          format!(
+            "Name '{}' does not match the folder name '{}'",
+            name, folder_name
+          ),
+          key_line("name"),
+        ));
+      }
+    },
+  }
+
+  match &frontmatter.description {
+    None => diagnostics.push(skill_md_diagnostic(
+      LintSeverity::Error,
+      "description-missing",
+      "Frontmatter is missing 'description'; agents use it to decide when to load the skill"
+        .to_string(),
+      key_line("description").or(Some(1)),
+    )),
+    Some(description) => {
+      let length = description.chars().count();
+      if length > MAX_DESCRIPTION_LENGTH {
+        diagnostics.push(skill_md_diagnostic(
+          LintSeverity::Error,
+          "description-too-long",
+          format!(
+            "Description is {} characters (max {})",
+            length, MAX_DESCRIPTION_LENGTH
+          ),
+          key_line("description"),
+        ));
+      } else if length < MIN_DESCRIPTION_LENGTH {
+        diagnostics.push(skill_md_diagnostic(
+          LintSeverity::Warning,
+          "description-too-short",
+          "Description is very short; say what the skill does and when to use it".to_string(),
+          key_line("description"),
+        ));
+      }
+    },
+  }
+
+  if let Some(compatibility) = &frontmatter.compatibility {
+    if compatibility.chars().count() > MAX_COMPATIBILITY_LENGTH {
+      diagnostics.push(skill_md_diagnostic(
+        LintSeverity::Warning,
+        "compatibility-too-long",
+        format!(
+          "Compatibility is longer than {} characters",
+          MAX_COMPATIBILITY_LENGTH
+        ),
+        key_line("compatibility"),
+      ));
+    }
+  }
+
+  frontmatter.name
+}
+
+/// Size limits for every file, plus encoding and link checks for Markdown other than
+/// SKILL.md.
+fn lint_other_files(skill_dir: &Path, diagnostics: &mut Vec<SkillDiagnostic>) {
+  for entry in WalkDir::new(skill_dir)
+    .into_iter()
+    .filter_entry(|entry| entry.file_name() != ".git")
+    .filter_map(Result::ok)
+  {
+    if !entry.file_type().is_file() {
+      continue;
+    }
+    let path = entry.path();
+    let relative = relative_path(skill_dir, path);
+
+    if let Ok(metadata) = entry.metadata() {
+      if metadata.len() > MAX_FILE_SIZE {
+        diagnostics.push(diagnostic(
+          LintSeverity::Warning,
+          "file-too-large",
+          format!(
+            "File is {:.1} MB (recommended: under {} MB)",
+            metadata.len() as f64 / (1024.0 * 1024.0),
+            MAX_FILE_SIZE / (1024 * 1024)
+          ),
+          Some(relative.clone()),
+          None,
+        ));
+        continue;
+      }
+    }
+
+    let is_markdown = path
+      .extension()
+      .is_some_and(|ext| ext.eq_ignore_ascii_case("md"));
+    if !is_markdown || relative == SKILL_MD_FILE_NAME {
+      continue;
+    }
+    if let Some(content) = read_markdown(path, &relative, diagnostics) {
+      lint_links(skill_dir, &relative, &content, diagnostics);
+    }
+  }
+}
+
+fn read_markdown(
+  path: &Path,
+  relative: &str,
+  diagnostics: &mut Vec<SkillDiagnostic>,
+) -> Option<String> {
+  let bytes = match fs::read(path) {
+    Ok(bytes) => bytes,
+    Err(err) => {
+      diagnostics.push(diagnostic(
+        LintSeverity::Error,
+        "read-failed",
+        format!("Failed to read file: {}", err),
+        Some(relative.to_string()),
+        None,
+      ));
+      return None;
+    },
+  };
+  match String::from_utf8(bytes) {
+    Ok(content) => Some(content),
+    Err(err) => {
+      let valid = &err.as_bytes()[..err.utf8_error().valid_up_to()];
+      let line = valid.iter().filter(|b| **b == b'\n').count() + 1;
+      diagnostics.push(diagnostic(
+        LintSeverity::Error,
+        "non-utf8",
+        "Markdown file is not valid UTF-8".to_string(),
+        Some(relative.to_string()),
+        Some(line),
+      ));
+      None
+    },
+  }
+}
+
+/// Report relative Markdown links whose target is missing or lies outside the skill
+/// folder, which would not be installed with the skill.
+fn lint_links(
+  skill_dir: &Path,
+  relative_file: &str,
+  content: &str,
+  diagnostics: &mut Vec<SkillDiagnostic>,
+) {
+  let file_dir = Path::new(relative_file)
+    .parent()
+    .map(Path::to_path_buf)
+    .unwrap_or_default();
+  let mut in_code_block = false;
+  for (index, line) in content.lines().enumerate() {
+    if line.trim_start().starts_with("```") {
+      in_code_block = !in_code_block;
+      continue;
+    }
+    if in_code_block {
+      continue;
+    }
+    for target in markdown_link_targets(line) {
+      let Some(target) = local_link_path(&target) else {
+        continue;
+      };
+      let Some(resolved) = normalize_within(&file_dir.join(&target)) else {
+        diagnostics.push(diagnostic(
+          LintSeverity::Error,
+          "link-outside-skill",
+          format!(
+            "Link '{}' points outside the skill folder and will not be installed with it",
+            target
+          ),
+          Some(relative_file.to_string()),
+          Some(index + 1),
+        ));
+        continue;
+      };
+      if !skill_dir.join(&resolved).exists() {
+        diagnostics.push(diagnostic(
+          LintSeverity::Error,
+          "link-missing",
+          format!("Linked file '{}' does not exist", target),
+          Some(relative_file.to_string()),
+          Some(index + 1),
+        ));
+      }
+    }
+  }
+}
+
+/// Destinations of inline links and images on one line: `[text](target "title")`.
+fn markdown_link_targets(line: &str) -> Vec<String> {
+  let mut targets = Vec::new();
+  let mut rest = line;
+  while let Some(start) = rest.find("](") {
+    let after = &rest[start + 2..];
+    let Some(end) = after.find(')') else {
+      break;
+    };
+    let raw = after[..end].trim();
+    let target = match raw.strip_prefix('<') {
+      Some(bracketed) => bracketed.split('>').next().unwrap_or_default(),
+      None => raw.split_whitespace().next().unwrap_or_default(),
+    };
+    if !target.is_empty() {
+      targets.push(target.to_string());
+    }
+    rest = &after[end..];
+  }
+  targets
+}
+
+/// The file path a link refers to, or `None` for URLs, anchors and absolute paths.
+fn local_link_path(target: &str) -> Option<String> {
+  if target.starts_with('#') || target.starts_with('/') || target.contains(':') {
+    return None;
+  }
+  let path = target.split(['#', '?']).next().unwrap_or_default();
+  if path.is_empty() {
+    return None;
+  }
+  Some(percent_decode(path))
+}
+
+fn percent_decode(value: &str) -> String {
+  let bytes = value.as_bytes();
+  let mut out = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    if bytes[i] == b'%' && i + 2 < bytes.len() {
+      let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+      if let Ok(byte) = u8::from_str_radix(hex, 16) {
+        out.push(byte);
+        i += 3;
+        continue;
+      }
+    }
+    out.push(bytes[i]);
+    i += 1;
+  }
+  String::from_utf8_lossy(&out).to_string()
+}
+
+/// Resolve `.` and `..` lexically, returning `None` if the path climbs above its root.
+fn normalize_within(path: &Path) -> Option<PathBuf> {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::Normal(part) => normalized.push(part),
+      Component::CurDir => {},
+      Component::ParentDir => {
+        if !normalized.pop() {
+          return None;
+        }
+      },
+      Component::RootDir | Component::Prefix(_) => return None,
+    }
+  }
+  Some(normalized)
+}
+
+/// Problems with a name under the Agent Skills rules: 1-64 lowercase letters, digits
+/// and hyphens, without leading, trailing or consecutive hyphens.
+fn name_format_problem(name: &str) -> Option<String> {
+  if name.chars().count() > MAX_NAME_LENGTH {
+    return Some(format!("must be at most {} characters", MAX_NAME_LENGTH));
+  }
+  if !name
+    .chars()
+    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+  {
+    return Some("use only lowercase letters, digits and hyphens".to_string());
+  }
+  if name.starts_with('-') || name.ends_with('-') {
+    return Some("must not start or end with a hyphen".to_string());
+  }
+  if name.contains("--") {
+    return Some("must not contain consecutive hyphens".to_string());
+  }
+  None
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+  path
+    .strip_prefix(root)
+    .unwrap_or(path)
+    .to_string_lossy()
+    .replace('\\', "/")
+}
+
+fn skill_md_diagnostic(
+  severity: LintSeverity,
+  code: &str,
+  message: String,
+  line: Option<usize>,
+) -> SkillDiagnostic {
+  diagnostic(
+    severity,
+    code,
+    message,
+    Some(SKILL_MD_FILE_NAME.to_string()),
+    line,
+  )
+}
+
+fn diagnostic(
+  severity: LintSeverity,
+  code: &str,
+  message: String,
+  file: Option<String>,
+  line: Option<usize>,
+) -> SkillDiagnostic {
+  SkillDiagnostic {
+    severity,
+    code: code.to_string(),
+    message,
+    file,
+    line,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn codes(report: &SkillLintReport) -> Vec<&str> {
+    report.diagnostics.iter().map(|d| d.code.as_str()).collect()
+  }
+
+  #[test]
+  fn reports_frontmatter_and_link_problems_with_lines() {
+    let root = std::env::temp_dir().join(format!(
+      "youskill-lint-test-{}",
+      crate::utils::time::now_millis()
+    ));
+    let skill_dir = root.join("pdf-tools");
+    fs::create_dir_all(&skill_dir).unwrap();
+    fs::write(
+      skill_dir.join(SKILL_MD_FILE_NAME),
+      "---\nname: PDF_Tools\ndescription: short\n---\n\nSee [forms](forms.md) and [spec](../spec.md).\n\n```\n[ignored](nope.md)\n```\n",
+    )
+    .unwrap();
+
+    let report = lint_skill_dir(&skill_dir);
+    let _ = fs::remove_dir_all(&root);
+
+    assert_eq!(report.name.as_deref(), Some("PDF_Tools"));
+    assert_eq!(
+      codes(&report),
+      vec![
+        "name-format",
+        "name-folder-mismatch",
+        "link-missing",
+        "link-outside-skill",
+        "description-too-short"
+      ]
+    );
+    assert_eq!(report.diagnostics[0].line, Some(2));
+    assert_eq!(report.diagnostics[2].line, Some(6));
+  }
+
+  #[test]
+  fn invalid_yaml_points_at_the_file_line() {
+    let root = std::env::temp_dir().join(format!(
+      "youskill-lint-yaml-{}",
+      crate::utils::time::now_millis()
+    ));
+    let skill_dir = root.join("broken");
+    fs::create_dir_all(&skill_dir).unwrap();
+    fs::write(
+      skill_dir.join(SKILL_MD_FILE_NAME),
+      "---\nname: broken\ndescription: [unclosed\n---\n",
+    )
+    .unwrap();
+
+    let report = lint_skill_dir(&skill_dir);
+    let _ = fs::remove_dir_all(&root);
+
+    assert_eq!(codes(&report), vec!["frontmatter-invalid"]);
+    assert!(report.diagnostics[0].line.is_some_and(|line| line >= 3));
+  }
+}
@@ -1,6 +1,6 @@
-use crate::models::SourceType;
+use crate::models::{GitRefKind, ProviderKind, SourceRef, SourceType};
 use crate::services::native_skill_lock_service::NativeSkillLockFile;
-use crate::utils::github::GithubHelper;
+use crate::utils::source_provider::RemoteSource;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -16,10 +16,31 @@ pub struct SkillLockEntry {
   pub source_url: String,
   pub skill_path: Option<String>,
   pub skill_folder_hash: Option<String>,
+  /// Branch, tag or commit requested at install time; `None` follows the default branch
+  #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+  pub git_ref: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub ref_kind: Option<GitRefKind>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub resolved_commit: Option<String>,
   pub installed_at: String,
   pub updated_at: String,
 }
 
+impl SkillLockEntry {
+  pub fn provider(&self) -> Option<ProviderKind> {
+    ProviderKind::from_lock_value(&self.source_type)
+  }
+
+  pub fn source_ref(&self) -> SourceRef {
+    SourceRef {
+      git_ref: self.git_ref.clone(),
+      ref_kind: self.ref_kind,
+      resolved_commit: self.resolved_commit.clone(),
+    }
+  }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SkillLockFile {
   #[serde(default = "default_version")]
@@ -47,7 +68,7 @@ fn default_version() -> i64 {
   3
 }
 
-fn skill_lock_path() -> Result<PathBuf, String> {
+pub fn skill_lock_path() -> Result<PathBuf, String> {
   let home_dir = dirs_next::home_dir().ok_or("Unable to get user home directory")?;
   Ok(home_dir.join(AGENTS_DIR).join(LOCK_FILE))
 }
@@ -83,15 +104,11 @@ pub fn add_skill_to_lock(skill_name: String, entry: SkillLockEntry) -> Result<()
   lock.skills.insert(
     skill_name.clone(),
     SkillLockEntry {
-      source: entry.source.clone(),
-      source_type: entry.source_type.clone(),
-      source_url: entry.source_url.clone(),
-      skill_path: entry.skill_path.clone(),
-      skill_folder_hash: entry.skill_folder_hash,
       installed_at: existing_entry
         .map(|e| e.installed_at.clone())
         .unwrap_or_else(|| now.clone()),
       updated_at: now,
+      ..entry
     },
   );
   write_skill_lock_internal(&lock)
@@ -112,8 +129,9 @@ pub fn lock_source_type(
   github_lock: &SkillLockFile,
   native_lock: &NativeSkillLockFile,
 ) -> SourceType {
-  if github_lock.skills.contains_key(skill_name) {
-    SourceType::Github
+  if let Some(entry) = github_lock.skills.get(skill_name) {
+    // Entries written by other tools may omit the provider; they are GitHub installs.
+    entry.provider().unwrap_or_default().into()
   } else if native_lock.skills.contains_key(skill_name) {
     SourceType::Native
   } else {
@@ -126,7 +144,7 @@ pub fn lock_source(
   source_type: &SourceType,
   github_lock: &SkillLockFile,
 ) -> Option<String> {
-  if *source_type != SourceType::Github {
+  if !source_type.is_remote() {
     return None;
   }
 
@@ -137,7 +155,7 @@ pub fn lock_source(
     return Some(source.to_string());
   }
 
-  GithubHelper::parse_github_url(&entry.source_url)
-    .map(|(owner, repo)| format!("{}/{}", owner, repo))
+  RemoteSource::parse_as(&entry.source_url, entry.provider())
+    .map(|source| source.source_id())
     .ok()
 }
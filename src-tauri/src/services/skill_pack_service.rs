@@ -0,0 +1,219 @@
+use crate::models::{
+  ExportSkillPackRequest, InstallTarget, SkillPackAgentLink, SkillPackExportResult,
+  SkillPackOrigin, SourceType,
+};
+use crate::services::project_skill_lock_service::read_project_skill_lock_internal;
+use crate::services::skill_lock_service::{read_skill_lock_internal, SkillLockEntry};
+use crate::services::{skill_service, workspace_service};
+use crate::utils::folder::FolderHelper;
+use crate::utils::source_provider::RemoteSource;
+use crate::utils::zip::ZipHelper;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const SKILL_PACK_MANIFEST_FILE: &str = "skill-pack.json";
+const SKILL_PACK_VERSION: i64 = 1;
+/// Skill folders sit under this folder in the archive.
+const SKILL_PACK_SKILLS_DIR: &str = "skills";
+
+/// Stored at the root of a skill pack ZIP. Its presence is what marks a ZIP as a pack.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct SkillPackManifest {
+  pub version: i64,
+  pub created_at: String,
+  #[serde(default)]
+  pub skills: Vec<SkillPackRecord>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct SkillPackRecord {
+  pub name: String,
+  /// Folder of the skill inside the pack
+  pub path: String,
+  /// Skill folder hash at export time
+  pub hash: String,
+  /// Where the skill was installed from; `None` for local skills
+  #[serde(default)]
+  pub lock: Option<SkillLockEntry>,
+  #[serde(default)]
+  pub agent_apps: Vec<SkillPackAgentLink>,
+}
+
+impl SkillPackRecord {
+  pub fn origin(&self, skill_dir: &Path) -> SkillPackOrigin {
+    SkillPackOrigin {
+      source_type: match &self.lock {
+        Some(entry) => entry.provider().unwrap_or_default().into(),
+        None => SourceType::Native,
+      },
+      source: self.lock.as_ref().map(|entry| entry.source.clone()),
+      agent_apps: self.agent_apps.clone(),
+      modified: !self.matches_export(skill_dir),
+    }
+  }
+
+  /// Whether `skill_dir` still holds the content that was exported. An edited skill
+  /// no longer is what its lock entry describes.
+  pub fn matches_export(&self, skill_dir: &Path) -> bool {
+    FolderHelper::compute_skill_folder_hash(skill_dir).is_ok_and(|hash| hash == self.hash)
+  }
+}
+
+/// Write the selected skills of a scope, with their lock provenance and agent links,
+/// into a single ZIP that `detect_zip` recognises.
+pub fn export_skill_pack(request: ExportSkillPackRequest) -> Result<SkillPackExportResult, String> {
+  if request.names.is_empty() {
+    return Err("Select at least one skill to export".to_string());
+  }
+  let install_target = InstallTarget::from_scope(&request.scope, &request.project_path)?;
+  let installed = skill_service::list_skills(request.scope.clone(), request.project_path.clone())?;
+
+  let mut sources: Vec<(PathBuf, SkillPackRecord)> = Vec::new();
+  for name in &request.names {
+    let skill = installed
+      .iter()
+      .find(|skill| skill.name == *name)
+      .ok_or_else(|| format!("Skill '{}' is not installed", name))?;
+    let dir = skill
+      .root_folder
+      .clone()
+      .or_else(|| {
+        skill
+          .installed_agent_apps
+          .first()
+          .map(|app| app.skill_folder.clone())
+      })
+      .map(PathBuf::from)
+      .ok_or_else(|| format!("Skill '{}' has no folder", name))?;
+    sources.push((
+      dir.clone(),
+      SkillPackRecord {
+        name: name.clone(),
+        path: format!("{}/{}", SKILL_PACK_SKILLS_DIR, name),
+        hash: FolderHelper::compute_skill_folder_hash(&dir)?,
+        lock: lock_entry(name, &install_target)?,
+        agent_apps: skill
+          .installed_agent_apps
+          .iter()
+          .map(|app| SkillPackAgentLink {
+            id: app.id.clone(),
+            method: app.method.clone(),
+          })
+          .collect(),
+      },
+    ));
+  }
+
+  let output = PathBuf::from(&request.output_path);
+  if let Some(parent) = output
+    .parent()
+    .filter(|parent| !parent.as_os_str().is_empty())
+  {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create folder: {}", e))?;
+  }
+  let manifest = SkillPackManifest {
+    version: SKILL_PACK_VERSION,
+    created_at: chrono::Utc::now().to_rfc3339(),
+    skills: sources.iter().map(|(_, record)| record.clone()).collect(),
+  };
+  let manifest_content = serde_json::to_string_pretty(&manifest)
+    .map_err(|e| format!("Failed to serialize skill pack manifest: {}", e))?;
+
+  // Written next to the output first, so a failed export leaves no half-written pack.
+  let partial = output.with_extension("zip.partial");
+  let write = || -> Result<(), String> {
+    let file = fs::File::create(&partial).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+      zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (dir, record) in &sources {
+      zip
+        .add_directory(record.path.as_str(), options)
+        .map_err(|e| format!("Failed to add directory to ZIP: {}", e))?;
+      ZipHelper::add_dir(&mut zip, dir, &record.path, options)?;
+    }
+    zip
+      .start_file(SKILL_PACK_MANIFEST_FILE, options)
+      .map_err(|e| format!("Failed to add file to ZIP: {}", e))?;
+    zip
+      .write_all(manifest_content.as_bytes())
+      .map_err(|e| format!("Failed to write file to ZIP: {}", e))?;
+    zip
+      .finish()
+      .map_err(|e| format!("Failed to finish ZIP: {}", e))?;
+    fs::rename(&partial, &output).map_err(|e| format!("Failed to write skill pack: {}", e))
+  };
+  if let Err(err) = write() {
+    let _ = fs::remove_file(&partial);
+    return Err(err);
+  }
+
+  Ok(SkillPackExportResult {
+    path: output.to_string_lossy().to_string(),
+    skills: request.names,
+  })
+}
+
+/// Manifest of an extracted skill pack, or `None` for an ordinary ZIP.
+pub fn read_pack_manifest(root: &Path) -> Option<SkillPackManifest> {
+  let content = fs::read_to_string(root.join(SKILL_PACK_MANIFEST_FILE)).ok()?;
+  match serde_json::from_str(&content) {
+    Ok(manifest) => Some(manifest),
+    Err(err) => {
+      tracing::warn!("Ignoring unreadable skill pack manifest: {}", err);
+      None
+    },
+  }
+}
+
+/// Record of the skill whose SKILL.md is at `skill_path` in a detect session, if the
+/// session holds a skill pack.
+pub fn pack_record(session_id: &str, skill_path: &str) -> Result<Option<SkillPackRecord>, String> {
+  let root = workspace_service::session_path(session_id)?;
+  Ok(read_pack_manifest(&root).and_then(|manifest| record_for(&manifest, skill_path)))
+}
+
+pub fn record_for(manifest: &SkillPackManifest, skill_path: &str) -> Option<SkillPackRecord> {
+  let dir = Path::new(skill_path).parent()?;
+  manifest
+    .skills
+    .iter()
+    .find(|record| Path::new(&record.path) == dir)
+    .cloned()
+}
+
+/// The lock entry describing where a skill came from. Project locks are converted, so a
+/// pack exported from a project can restore provenance globally and the other way round.
+fn lock_entry(
+  name: &str,
+  install_target: &InstallTarget,
+) -> Result<Option<SkillLockEntry>, String> {
+  match install_target {
+    InstallTarget::Global => Ok(read_skill_lock_internal()?.skills.remove(name)),
+    InstallTarget::Project(project_root) => {
+      let lock = read_project_skill_lock_internal(project_root)?;
+      let Some(entry) = lock.skills.get(name) else {
+        return Ok(None);
+      };
+      let Some(provider) = entry.provider() else {
+        return Ok(None);
+      };
+      let source_url = RemoteSource::parse_as(&entry.source, Some(provider))
+        .map(|source| source.web_url())
+        .unwrap_or_else(|_| entry.source.clone());
+      Ok(Some(SkillLockEntry {
+        source: entry.source.clone(),
+        source_type: entry.source_type.clone(),
+        source_url,
+        skill_path: None,
+        skill_folder_hash: None,
+        git_ref: entry.git_ref.clone(),
+        ref_kind: entry.ref_kind,
+        resolved_commit: entry.resolved_commit.clone(),
+        installed_at: String::new(),
+        updated_at: String::new(),
+      }))
+    },
+  }
+}
@@ -1,31 +1,38 @@
 use crate::models::{
   DetectedSkill, InstallGithubRequest, InstallMethod, InstallNativeRequest, InstallResult,
   InstallScope, InstallTarget, InstallUnknownRequest, InstalledAgentApp, LocalSkill,
-  ManageSkillAgentAppsRequest, SelectedAgentPath, SkillDirectoryEntry, SourceCheckResult,
-  SourceType,
+  ManageSkillAgentAppsRequest, ProjectLockSyncResult, ProjectLockSyncStatus, ProviderKind,
+  SelectedAgentPath, SkillAdapterKind, SkillDirectoryEntry, SkillFrontmatter, SkillUpdateResult,
+  SourceCheckResult, SourceRef, SourceType, SyncProjectLockRequest,
 };
 use crate::services::agent_apps_service::{
-  local_agent_apps, resolve_all_available_apps_paths, resolve_selected_apps_paths,
-  root_folder_from_install_target,
+  agent_skill_roots, local_agent_apps, resolve_all_agent_skill_folders,
+  resolve_all_available_apps_paths, resolve_selected_apps_paths,
 };
 use crate::services::native_skill_lock_service::NativeSkillLockFile;
 use crate::services::native_skill_lock_service::{
   add_skill_to_native_lock, read_native_skill_lock_internal, remove_skill_from_native_lock,
 };
 use crate::services::project_skill_lock_service::{
-  add_skill_to_project_lock, project_lock_source, project_lock_source_type,
-  read_project_skill_lock_internal, remove_skill_from_project_lock, ProjectSkillLockFile,
+  add_skill_to_project_lock, project_lock_path, project_lock_source, project_lock_source_type,
+  read_project_skill_lock_internal, remove_skill_from_project_lock, ProjectSkillLockEntry,
+  ProjectSkillLockFile,
 };
+use crate::services::skill_lint_service;
 use crate::services::skill_lock_service::{
   add_skill_to_lock, lock_source, lock_source_type, read_skill_lock_internal,
   remove_skill_from_global_lock, SkillLockEntry, SkillLockFile,
 };
+use crate::services::skill_pack_service::{self, SkillPackRecord};
+use crate::services::workspace_service::{self, WorkspaceSession};
 use crate::utils::file::FileHelper;
 use crate::utils::folder::{copy_dir_all_sync, FolderHelper};
 use crate::utils::github::GithubHelper;
 use crate::utils::path::{canonical_skill_folder_by_name, remove_path_any};
+use crate::utils::skill_adapter;
+use crate::utils::source_provider::RemoteSource;
 use crate::utils::str::normalize_optional_string;
-use crate::utils::time::now_millis;
+use crate::utils::transaction::FsTransaction;
 use crate::utils::zip::ZipHelper;
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -39,84 +46,67 @@ pub fn detect_folder(folder_path: String) -> Result<Vec<DetectedSkill>, String>
     return Err(format!("Folder does not exist: {}", folder_path));
   }
 
-  if folder.join(SKILL_MD_FILE_NAME).exists() {
-    return Ok(vec![detect_folder_exact(folder)?]);
-  }
-
-  let skill_dirs = FolderHelper::find_dirs_containing_file(folder, SKILL_MD_FILE_NAME)?;
-  if skill_dirs.is_empty() {
-    return Err(format!("SKILL.md not found in folder: {}", folder_path));
-  }
-
-  let mut result = Vec::new();
+  let skill_dirs = find_skill_dirs(folder)?;
+  // Only the skill folders are staged, at the same relative paths as in the source.
+  let session = workspace_service::create_session("detect-folder")?;
+  let mut staged_dirs = Vec::new();
   for dir in skill_dirs {
-    if let Ok(mut detected) = detect_folder_exact(&dir) {
-      let relative_dir = dir
-        .strip_prefix(folder)
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_default();
-      detected.skill_path = skill_path_from_relative_dir(&relative_dir);
-      result.push(detected);
-    }
-  }
-
-  if result.is_empty() {
-    Err(format!(
-      "No valid SKILL.md found after parsing in folder: {}",
-      folder_path
-    ))
-  } else {
-    Ok(result)
+    let relative_dir = dir.strip_prefix(folder).map_err(|e| e.to_string())?;
+    let staged_dir = session.path().join(relative_dir);
+    copy_dir_all_sync(&dir, &staged_dir)?;
+    staged_dirs.push(staged_dir);
   }
+  detect_staged_skills(session, staged_dirs, None)
 }
 
 pub fn detect_zip(zip_path: String) -> Result<Vec<DetectedSkill>, String> {
-  let temp_extract_dir = create_temp_dir("detect-zip")?;
-  ZipHelper::extract_to_dir(&zip_path, &temp_extract_dir)?;
-  detect_folder(temp_extract_dir.to_string_lossy().to_string())
+  let session = workspace_service::create_session("detect-zip")?;
+  ZipHelper::extract_to_dir(&zip_path, session.path())?;
+  let skill_dirs = find_skill_dirs(session.path())?;
+  let manifest = skill_pack_service::read_pack_manifest(session.path());
+  let mut detected = detect_staged_skills(session, skill_dirs, None)?;
+  if let Some(manifest) = manifest {
+    for skill in &mut detected {
+      let skill_dir = workspace_service::session_skill_dir(&skill.session_id, &skill.skill_path)?;
+      skill.pack_origin = skill_pack_service::record_for(&manifest, &skill.skill_path)
+        .map(|record| record.origin(&skill_dir));
+    }
+  }
+  Ok(detected)
 }
 
-pub async fn detect_github_manual(github_path: String) -> Result<Vec<DetectedSkill>, String> {
-  let (owner, repo) = GithubHelper::parse_github_url(&github_path)?;
-  let clone_dir = create_temp_dir(&format!("detect-github-manual-{}-{}", owner, repo))?;
-  GithubHelper::clone_repo_to(&owner, &repo, &clone_dir).await?;
-
-  let skill_dirs = FolderHelper::find_dirs_containing_file(&clone_dir, SKILL_MD_FILE_NAME)?;
+pub async fn detect_github_manual(
+  github_path: String,
+  git_ref: Option<String>,
+) -> Result<Vec<DetectedSkill>, String> {
+  let source = RemoteSource::parse(&github_path)?;
+  let session = workspace_service::create_session(&format!("detect-{}", source.repo.slug()))?;
+  let git_ref = normalize_optional_string(git_ref);
+  let source_ref = source
+    .download_to(git_ref.as_deref(), session.path())
+    .await?;
+
+  let skill_dirs = FolderHelper::find_dirs_containing_file(session.path(), SKILL_MD_FILE_NAME)?;
   if skill_dirs.is_empty() {
     return Err("No SKILL.md found in repository".to_string());
   }
-
-  let mut result = Vec::new();
-  for dir in skill_dirs {
-    match detect_folder_exact(&dir) {
-      Ok(mut detected) => {
-        let relative_dir = dir
-          .strip_prefix(&clone_dir)
-          .map(|p| p.to_string_lossy().to_string())
-          .unwrap_or_default();
-        detected.skill_path = skill_path_from_relative_dir(&relative_dir);
-        result.push(detected);
-      },
-      Err(_) => continue,
-    }
-  }
-
-  if result.is_empty() {
-    return Err("No valid skills found after parsing SKILL.md".to_string());
-  }
-
-  Ok(result)
+  detect_staged_skills(session, skill_dirs, Some(source_ref))
+    .map_err(|_| "No valid skills found after parsing SKILL.md".to_string())
 }
 
 pub async fn detect_github_auto(
   github_path: String,
   skill_name: String,
+  git_ref: Option<String>,
 ) -> Result<DetectedSkill, String> {
-  let (owner, repo) = GithubHelper::parse_github_url(&github_path)?;
-  let clone_dir = create_temp_dir(&format!("detect-github-auto-{}-{}", owner, repo))?;
-  GithubHelper::clone_repo_to(&owner, &repo, &clone_dir).await?;
-
-  let skill_dirs = FolderHelper::find_dirs_containing_file(&clone_dir, SKILL_MD_FILE_NAME)?;
+  let source = RemoteSource::parse(&github_path)?;
+  let session = workspace_service::create_session(&format!("detect-{}", source.repo.slug()))?;
+  let git_ref = normalize_optional_string(git_ref);
+  let source_ref = source
+    .download_to(git_ref.as_deref(), session.path())
+    .await?;
+
+  let skill_dirs = FolderHelper::find_dirs_containing_file(session.path(), SKILL_MD_FILE_NAME)?;
   if skill_dirs.is_empty() {
     return Err("No SKILL.md found in repository".to_string());
   }
@@ -135,16 +125,69 @@ pub async fn detect_github_auto(
 
     let mut detected = detect_folder_exact(&dir)?;
     let relative_dir = dir
-      .strip_prefix(&clone_dir)
+      .strip_prefix(session.path())
       .map(|p| p.to_string_lossy().to_string())
       .unwrap_or_default();
     detected.skill_path = skill_path_from_relative_dir(&relative_dir);
+    detected.source_ref = Some(source_ref);
+    detected.session_id = session.keep();
     return Ok(detected);
   }
 
   Err(format!("No skill matched '{}'", skill_name))
 }
 
+/// Skill folders under `root`: `root` itself when it holds a SKILL.md, otherwise every
+/// folder below it that does.
+fn find_skill_dirs(root: &Path) -> Result<Vec<PathBuf>, String> {
+  if root.join(SKILL_MD_FILE_NAME).exists() {
+    return Ok(vec![root.to_path_buf()]);
+  }
+  let skill_dirs = FolderHelper::find_dirs_containing_file(root, SKILL_MD_FILE_NAME)?;
+  if skill_dirs.is_empty() {
+    return Err(format!(
+      "SKILL.md not found in folder: {}",
+      root.to_string_lossy()
+    ));
+  }
+  Ok(skill_dirs)
+}
+
+/// Parse skill folders staged in `session` and keep the session for the install that
+/// follows. A single folder reports its own parse error; otherwise invalid ones are
+/// skipped.
+fn detect_staged_skills(
+  session: WorkspaceSession,
+  skill_dirs: Vec<PathBuf>,
+  source_ref: Option<SourceRef>,
+) -> Result<Vec<DetectedSkill>, String> {
+  let single = skill_dirs.len() == 1 && skill_dirs[0] == session.path();
+  let mut result = Vec::new();
+  for dir in skill_dirs {
+    let mut detected = match detect_folder_exact(&dir) {
+      Ok(detected) => detected,
+      Err(err) if single => return Err(err),
+      Err(_) => continue,
+    };
+    let relative_dir = dir
+      .strip_prefix(session.path())
+      .map(|p| p.to_string_lossy().to_string())
+      .unwrap_or_default();
+    detected.skill_path = skill_path_from_relative_dir(&relative_dir);
+    detected.source_ref = source_ref.clone();
+    result.push(detected);
+  }
+
+  if result.is_empty() {
+    return Err("No valid SKILL.md found after parsing".to_string());
+  }
+  let session_id = session.keep();
+  for detected in &mut result {
+    detected.session_id = session_id.clone();
+  }
+  Ok(result)
+}
+
 fn detect_folder_exact(folder: &Path) -> Result<DetectedSkill, String> {
   let skill_md = folder.join(SKILL_MD_FILE_NAME);
   if !skill_md.exists() {
@@ -154,17 +197,20 @@ fn detect_folder_exact(folder: &Path) -> Result<DetectedSkill, String> {
     ));
   }
 
-  let name = FileHelper::read_skill_frontmatter(&skill_md)?
+  let frontmatter = FileHelper::read_skill_frontmatter(&skill_md)?;
+  let name = frontmatter
     .name
+    .clone()
     .ok_or("SKILL.md frontmatter missing valid 'name'".to_string())?;
-  let tmp_dir = create_temp_dir("detect-folder")?;
-  let tmp_path = tmp_dir.join(&name);
-  copy_dir_all_sync(folder, &tmp_path)?;
 
   Ok(DetectedSkill {
     name,
-    tmp_path: tmp_path.to_string_lossy().to_string(),
+    session_id: String::new(),
     skill_path: SKILL_MD_FILE_NAME.to_string(),
+    source_ref: None,
+    frontmatter: Some(frontmatter),
+    diagnostics: skill_lint_service::lint_skill_dir(folder).diagnostics,
+    pack_origin: None,
   })
 }
 
@@ -202,7 +248,7 @@ fn list_skills_by_target(install_target: &InstallTarget) -> Result<Vec<LocalSkil
       if !is_skill_folder(&skill_dir) {
         continue;
       }
-      let Some(skill_name) = read_valid_skill_name(&skill_dir) else {
+      let Some((skill_name, frontmatter)) = read_valid_skill_frontmatter(&skill_dir) else {
         continue;
       };
       if skill_index.contains_key(&skill_name) {
@@ -223,16 +269,19 @@ fn list_skills_by_target(install_target: &InstallTarget) -> Result<Vec<LocalSkil
         root_folder: Some(skill_dir.to_string_lossy().to_string()),
         installed_agent_apps: Vec::new(),
         source_type,
+        frontmatter: Some(frontmatter),
       });
       skill_index.insert(skill_name, index);
     }
   }
 
-  for app in local_agent_apps() {
-    let app_root = match root_folder_from_install_target(&app, install_target) {
-      Ok(path) => path,
-      Err(_) => continue,
-    };
+  // Every folder an agent reads, so skills outside its preferred folder show up too
+  let agent_roots = local_agent_apps().into_iter().flat_map(|app| {
+    agent_skill_roots(&app, install_target)
+      .into_iter()
+      .map(move |root| (app.clone(), root))
+  });
+  for (app, app_root) in agent_roots {
     if !app_root.exists() || !app_root.is_dir() {
       continue;
     }
@@ -249,7 +298,7 @@ fn list_skills_by_target(install_target: &InstallTarget) -> Result<Vec<LocalSkil
       if !is_skill_folder(&skill_dir) {
         continue;
       }
-      let Some(skill_name) = read_valid_skill_name(&skill_dir) else {
+      let Some((skill_name, frontmatter)) = read_valid_skill_frontmatter(&skill_dir) else {
         continue;
       };
 
@@ -283,6 +332,7 @@ fn list_skills_by_target(install_target: &InstallTarget) -> Result<Vec<LocalSkil
         root_folder: None,
         installed_agent_apps: vec![installed_app],
         source_type,
+        frontmatter: Some(frontmatter),
       });
       skill_index.insert(skill_name, index);
     }
@@ -317,76 +367,190 @@ fn resolve_skill_source(
 
 pub fn install_from_native(request: InstallNativeRequest) -> Result<InstallResult, String> {
   validate_native_install_request(&request)?;
-  let install_target = InstallTarget::from_scope(&request.scope, &request.project_path)?;
-  let selected_apps = resolve_selected_apps_paths(&request.agent_apps, &install_target)?;
-  remove_existing_associations(&request.name, &install_target)?;
-  install_skill_to_apps(
-    Path::new(&request.tmp_path),
+  let skill_dir = workspace_service::session_skill_dir(&request.session_id, &request.skill_path)?;
+  if request.restore_pack_origin {
+    if let Some(record) = skill_pack_service::pack_record(&request.session_id, &request.skill_path)?
+    {
+      return install_pack_skill(&skill_dir, &request, &record);
+    }
+  }
+  install_native_skill(
+    &skill_dir,
     &request.name,
     &request.method,
-    &selected_apps,
-    &install_target,
-  )?;
+    &request.agent_apps,
+    &request.scope,
+    &request.project_path,
+  )
+}
+
+/// Install a skill from a skill pack with the lock entry it was exported with, linking
+/// the recorded agents that exist here as well as the requested ones. A skill edited
+/// since the export is recorded as a local skill instead.
+fn install_pack_skill(
+  source: &Path,
+  request: &InstallNativeRequest,
+  record: &SkillPackRecord,
+) -> Result<InstallResult, String> {
+  let install_target = InstallTarget::from_scope(&request.scope, &request.project_path)?;
+  let mut links: Vec<(SelectedAgentPath, InstallMethod)> =
+    resolve_selected_apps_paths(&request.agent_apps, &install_target)?
+      .into_iter()
+      .map(|app| (app, request.method.clone()))
+      .collect();
+  for link in &record.agent_apps {
+    if request.agent_apps.contains(&link.id) {
+      continue;
+    }
+    // Agents missing on this machine are skipped rather than failing the install.
+    if let Ok(apps) = resolve_selected_apps_paths(std::slice::from_ref(&link.id), &install_target) {
+      links.extend(apps.into_iter().map(|app| (app, link.method.clone())));
+    }
+  }
+  let applied =
+    plan_skill_install_links(source, &request.name, &links, &install_target)?.apply()?;
+
+  let unchanged = record.matches_export(source);
+  let lock = record.lock.as_ref().filter(|_| unchanged);
+  match (&install_target, lock) {
+    (InstallTarget::Global, Some(entry)) => {
+      sync_lock_for_github_install(request.name.clone(), entry.clone())?
+    },
+    (InstallTarget::Global, None) => sync_lock_for_native_install(&request.name)?,
+    (InstallTarget::Project(_), Some(entry)) => sync_project_lock_for_install(
+      &request.name,
+      &entry.source,
+      entry.provider().unwrap_or_default().into(),
+      Some(&entry.source_ref()),
+      &install_target,
+    )?,
+    (InstallTarget::Project(_), None) => sync_project_lock_for_install(
+      &request.name,
+      "native",
+      SourceType::Native,
+      None,
+      &install_target,
+    )?,
+  }
+  applied.commit();
+
+  let stderr = match &record.lock {
+    Some(entry) if !unchanged => format!(
+      "Skill '{}' differs from the copy exported from {}; installed as a local skill",
+      request.name, entry.source
+    ),
+    _ => String::new(),
+  };
+  Ok(InstallResult {
+    success: true,
+    stdout: format!("Skill '{}' installed from skill pack", request.name),
+    stderr,
+    message: "安装成功".to_string(),
+    diagnostics: skill_lint_service::lint_skill_dir(source).diagnostics,
+  })
+}
+
+fn install_native_skill(
+  source: &Path,
+  name: &str,
+  method: &InstallMethod,
+  agent_apps: &[String],
+  scope: &InstallScope,
+  project_path: &Option<String>,
+) -> Result<InstallResult, String> {
+  let install_target = InstallTarget::from_scope(scope, project_path)?;
+  let selected_apps = resolve_selected_apps_paths(agent_apps, &install_target)?;
+  let applied =
+    plan_skill_install(source, name, method, &selected_apps, &install_target)?.apply()?;
 
   match &install_target {
-    InstallTarget::Global => sync_lock_for_native_install(&request.name)?,
+    InstallTarget::Global => sync_lock_for_native_install(name)?,
     InstallTarget::Project(_) => {
-      sync_project_lock_for_install(&request.name, "native", SourceType::Native, &install_target)?
+      sync_project_lock_for_install(name, "native", SourceType::Native, None, &install_target)?
     },
   }
+  applied.commit();
 
   Ok(InstallResult {
     success: true,
-    stdout: format!("Skill '{}' installed from native source", request.name),
+    stdout: format!("Skill '{}' installed from native source", name),
     stderr: String::new(),
     message: "安装成功".to_string(),
+    diagnostics: skill_lint_service::lint_skill_dir(source).diagnostics,
   })
 }
 
 pub async fn install_from_github(request: InstallGithubRequest) -> Result<InstallResult, String> {
   validate_github_install_request(&request)?;
+  let skill_dir = workspace_service::session_skill_dir(&request.session_id, &request.skill_path)?;
   let install_target = InstallTarget::from_scope(&request.scope, &request.project_path)?;
   let selected_apps = resolve_selected_apps_paths(&request.agent_apps, &install_target)?;
-  remove_existing_associations(&request.name, &install_target)?;
-  install_skill_to_apps(
-    Path::new(&request.tmp_path),
+  let transaction = plan_skill_install(
+    &skill_dir,
     &request.name,
     &request.method,
     &selected_apps,
     &install_target,
   )?;
 
+  let remote = RemoteSource::parse(&request.source_url)?;
+  let mut source_ref = request.source_ref.clone().unwrap_or_default();
+  source_ref.git_ref = normalize_optional_string(source_ref.git_ref);
+  if source_ref.resolved_commit.is_none() {
+    match remote.resolve_ref(source_ref.git_ref.as_deref()).await {
+      Ok(resolved) => source_ref = resolved,
+      Err(err) => tracing::warn!("Failed to resolve ref for {}: {}", request.source_url, err),
+    }
+  }
+
   let skill_folder_hash = match normalize_optional_string(request.skill_folder_hash.clone()) {
     Some(hash) => hash,
-    None => GithubHelper::get_skill_folder_hash(&request.source_url, &request.skill_path).await?,
+    None => {
+      remote
+        .skill_folder_hash(&request.skill_path, source_ref.git_ref.as_deref())
+        .await?
+    },
   };
 
-  let source = GithubHelper::parse_github_url(&request.source_url)
-    .map(|(owner, repo)| format!("{}/{}", owner, repo))
-    .unwrap_or_else(|_| request.source_url.clone());
+  let source = remote.source_id();
 
   let lock_entry = SkillLockEntry {
     source: source.clone(),
-    source_type: "github".to_string(),
+    source_type: remote.kind().as_str().to_string(),
     source_url: request.source_url.clone(),
     skill_path: Some(request.skill_path.clone()),
     skill_folder_hash: Some(skill_folder_hash),
+    git_ref: source_ref.git_ref.clone(),
+    ref_kind: source_ref.ref_kind,
+    resolved_commit: source_ref.resolved_commit.clone(),
     installed_at: String::new(),
     updated_at: String::new(),
   };
 
+  // Nothing on disk changes until the source has been resolved.
+  let applied = transaction.apply()?;
   match &install_target {
     InstallTarget::Global => sync_lock_for_github_install(request.name.clone(), lock_entry)?,
-    InstallTarget::Project(_) => {
-      sync_project_lock_for_install(&request.name, &source, SourceType::Github, &install_target)?
-    },
+    InstallTarget::Project(_) => sync_project_lock_for_install(
+      &request.name,
+      &source,
+      remote.source_type(),
+      Some(&source_ref),
+      &install_target,
+    )?,
   }
+  applied.commit();
 
   Ok(InstallResult {
     success: true,
-    stdout: format!("Skill '{}' installed from github source", request.name),
+    stdout: format!(
+      "Skill '{}' installed from {} source",
+      request.name,
+      remote.kind().as_str()
+    ),
     stderr: String::new(),
     message: "安装成功".to_string(),
+    diagnostics: skill_lint_service::lint_skill_dir(&skill_dir).diagnostics,
   })
 }
 
@@ -410,24 +574,17 @@ pub fn check_skill_version(
 
 pub fn install_from_unknown(request: InstallUnknownRequest) -> Result<InstallResult, String> {
   validate_install_from_unknown_request(&request)?;
-  let install_target = InstallTarget::from_scope(&request.scope, &request.project_path)?;
-
-  let source = Path::new(&request.source_path);
-  let source_path = if matches!(install_target, InstallTarget::Global) {
-    prepare_canonical_skill_dir(source, &request.name, &install_target)?
-  } else {
-    source.to_path_buf()
-  };
 
-  install_from_native(InstallNativeRequest {
-    name: request.name,
-    tmp_path: source_path.to_string_lossy().to_string(),
-    skill_path: SKILL_MD_FILE_NAME.to_string(),
-    agent_apps: request.agent_apps,
-    method: request.method,
-    scope: request.scope,
-    project_path: request.project_path,
-  })
+  // The source is copied into the canonical folder before its agent folder is
+  // replaced, so it may be one of the folders being managed.
+  install_native_skill(
+    Path::new(&request.source_path),
+    &request.name,
+    &request.method,
+    &request.agent_apps,
+    &request.scope,
+    &request.project_path,
+  )
 }
 
 pub fn manage_skill_agent_apps(
@@ -456,28 +613,23 @@ pub fn manage_skill_agent_apps(
     return Err("source_path does not exist".to_string());
   }
 
-  let canonical_root = install_target.skill_folder_path()?;
-  let source = if source_path.starts_with(&canonical_root) {
-    source_path
-  } else {
-    prepare_canonical_skill_dir(&source_path, &request.name, &install_target)?
-  };
-
   let selected_apps = resolve_selected_apps_paths(&request.agent_apps, &install_target)?;
-  remove_existing_associations(&request.name, &install_target)?;
-  install_skill_to_apps(
-    &source,
+  plan_skill_install(
+    &source_path,
     &request.name,
     &request.method,
     &selected_apps,
     &install_target,
-  )?;
+  )?
+  .apply()?
+  .commit();
 
   Ok(InstallResult {
     success: true,
     stdout: format!("Skill '{}' agent apps updated", request.name),
     stderr: String::new(),
     message: "安装成功".to_string(),
+    diagnostics: Vec::new(),
   })
 }
 
@@ -523,6 +675,17 @@ pub fn check_skills_updates(
     if entry.source.trim() != check.source.trim() {
       continue;
     }
+    // Pinned skills never update, and a registry SHA from another branch says
+    // nothing about the branch the skill tracks.
+    let source_ref = entry.source_ref();
+    if source_ref.is_pinned() {
+      continue;
+    }
+    if let (Some(git_ref), Some(branch)) = (&source_ref.git_ref, &check.branch) {
+      if git_ref != branch {
+        continue;
+      }
+    }
     let Some(local_sha) = &entry.skill_folder_hash else {
       continue;
     };
@@ -533,6 +696,536 @@ pub fn check_skills_updates(
   Ok(updated)
 }
 
+pub async fn update_skill(
+  name: String,
+  scope: InstallScope,
+  project_path: Option<String>,
+) -> Result<SkillUpdateResult, String> {
+  if name.trim().is_empty() {
+    return Err("Skill name is required".to_string());
+  }
+  let install_target = InstallTarget::from_scope(&scope, &project_path)?;
+  Ok(update_skill_by_target(&name, &install_target).await)
+}
+
+pub async fn update_all_skills(
+  scope: InstallScope,
+  project_path: Option<String>,
+) -> Result<Vec<SkillUpdateResult>, String> {
+  let install_target = InstallTarget::from_scope(&scope, &project_path)?;
+  let mut names: Vec<String> = match &install_target {
+    InstallTarget::Global => read_skill_lock_internal()?
+      .skills
+      .into_iter()
+      .filter(|(_, entry)| entry.provider().is_some())
+      .map(|(name, _)| name)
+      .collect(),
+    InstallTarget::Project(project_root) => read_project_skill_lock_internal(project_root)?
+      .skills
+      .into_iter()
+      .filter(|(_, entry)| entry.provider().is_some())
+      .map(|(name, _)| name)
+      .collect(),
+  };
+  names.sort();
+
+  let mut results = Vec::new();
+  for name in names {
+    results.push(update_skill_by_target(&name, &install_target).await);
+  }
+  Ok(results)
+}
+
+/// Where a remote skill was installed from, as recorded in its lock file.
+struct RemoteSkillOrigin {
+  kind: ProviderKind,
+  source: String,
+  source_url: String,
+  skill_path: Option<String>,
+  hash: Option<String>,
+  source_ref: SourceRef,
+}
+
+async fn update_skill_by_target(name: &str, install_target: &InstallTarget) -> SkillUpdateResult {
+  match try_update_skill(name, install_target).await {
+    Ok(result) => result,
+    Err(err) => SkillUpdateResult {
+      name: name.to_string(),
+      success: false,
+      updated: false,
+      previous_hash: None,
+      current_hash: None,
+      agent_apps: Vec::new(),
+      message: err,
+    },
+  }
+}
+
+async fn try_update_skill(
+  name: &str,
+  install_target: &InstallTarget,
+) -> Result<SkillUpdateResult, String> {
+  let origin = read_remote_skill_origin(name, install_target)?;
+  let canonical_skill_dir = canonical_skill_folder_by_name(name, install_target)?;
+  if !canonical_skill_dir.exists() {
+    return Err(format!("Skill '{}' is not installed", name));
+  }
+  if origin.source_ref.is_pinned() {
+    let mut result = up_to_date_result(name, origin.hash);
+    result.message = format!(
+      "Skill '{}' is pinned to {}",
+      name,
+      origin.source_ref.git_ref.as_deref().unwrap_or_default()
+    );
+    return Ok(result);
+  }
+
+  let remote = RemoteSource::parse_as(&origin.source_url, Some(origin.kind))?;
+
+  // The global lock stores the host's folder hash, so an unchanged skill can be
+  // skipped without downloading the repository.
+  let remote_hash = match (install_target, &origin.skill_path) {
+    (InstallTarget::Global, Some(skill_path)) => remote
+      .skill_folder_hash(skill_path, origin.source_ref.git_ref.as_deref())
+      .await
+      .ok(),
+    _ => None,
+  };
+  if remote_hash.is_some() && remote_hash == origin.hash {
+    return Ok(up_to_date_result(name, origin.hash));
+  }
+
+  let session = workspace_service::create_session(&format!("update-{}", remote.repo.slug()))?;
+  apply_skill_update(
+    name,
+    install_target,
+    &remote,
+    &origin,
+    remote_hash,
+    &canonical_skill_dir,
+    session.path(),
+  )
+  .await
+}
+
+async fn apply_skill_update(
+  name: &str,
+  install_target: &InstallTarget,
+  remote: &RemoteSource,
+  origin: &RemoteSkillOrigin,
+  remote_hash: Option<String>,
+  canonical_skill_dir: &Path,
+  clone_dir: &Path,
+) -> Result<SkillUpdateResult, String> {
+  let source_ref = remote
+    .download_to(origin.source_ref.git_ref.as_deref(), clone_dir)
+    .await?;
+  let (skill_dir, skill_path) =
+    locate_skill_in_repo(clone_dir, name, origin.skill_path.as_deref())?;
+
+  // Project locks store a content hash instead of a tree SHA.
+  if matches!(install_target, InstallTarget::Project(_)) {
+    let content_hash = FolderHelper::compute_skill_folder_hash(&skill_dir)?;
+    if origin.hash.as_deref() == Some(content_hash.as_str()) {
+      return Ok(up_to_date_result(name, origin.hash.clone()));
+    }
+  }
+
+  let remote_hash = match (install_target, remote_hash) {
+    (InstallTarget::Global, None) => remote
+      .skill_folder_hash(&skill_path, source_ref.git_ref.as_deref())
+      .await
+      .ok(),
+    (_, remote_hash) => remote_hash,
+  };
+
+  let associations = existing_associations(name, install_target)?;
+  let applied = plan_skill_refresh(&skill_dir, name, install_target)?.apply()?;
+
+  let current_hash = match install_target {
+    InstallTarget::Global => {
+      add_skill_to_lock(
+        name.to_string(),
+        SkillLockEntry {
+          source: origin.source.clone(),
+          source_type: origin.kind.as_str().to_string(),
+          source_url: origin.source_url.clone(),
+          skill_path: Some(skill_path),
+          skill_folder_hash: remote_hash.clone(),
+          git_ref: source_ref.git_ref.clone(),
+          ref_kind: source_ref.ref_kind,
+          resolved_commit: source_ref.resolved_commit.clone(),
+          installed_at: String::new(),
+          updated_at: String::new(),
+        },
+      )?;
+      remote_hash
+    },
+    InstallTarget::Project(_) => {
+      sync_project_lock_for_install(
+        name,
+        &origin.source,
+        origin.kind.into(),
+        Some(&source_ref),
+        install_target,
+      )?;
+      Some(FolderHelper::compute_skill_folder_hash(
+        canonical_skill_dir,
+      )?)
+    },
+  };
+  applied.commit();
+
+  Ok(SkillUpdateResult {
+    name: name.to_string(),
+    success: true,
+    updated: true,
+    previous_hash: origin.hash.clone(),
+    current_hash,
+    agent_apps: associations
+      .into_iter()
+      .map(|(app, _)| app.display_name)
+      .collect(),
+    message: format!("Skill '{}' updated", name),
+  })
+}
+
+fn up_to_date_result(name: &str, hash: Option<String>) -> SkillUpdateResult {
+  SkillUpdateResult {
+    name: name.to_string(),
+    success: true,
+    updated: false,
+    previous_hash: hash.clone(),
+    current_hash: hash,
+    agent_apps: Vec::new(),
+    message: format!("Skill '{}' is already up to date", name),
+  }
+}
+
+/// Install every skill listed in a project's skills-lock.json, the way `npm ci` installs
+/// packages: remote entries are fetched at their locked commit and must reproduce the
+/// locked hash. The lock file itself is left untouched.
+pub async fn sync_project_from_lock(
+  request: SyncProjectLockRequest,
+) -> Result<Vec<ProjectLockSyncResult>, String> {
+  let install_target =
+    InstallTarget::from_scope(&InstallScope::Project, &Some(request.project_path.clone()))?;
+  let project_root = install_target.root_path()?;
+  if !project_lock_path(&project_root).exists() {
+    return Err(format!(
+      "No skills-lock.json found in {}",
+      project_root.to_string_lossy()
+    ));
+  }
+  let lock = read_project_skill_lock_internal(&project_root)?;
+
+  let selected_apps = if request.agent_apps.is_empty() {
+    let existing: Vec<SelectedAgentPath> = resolve_all_available_apps_paths(&install_target)?
+      .into_iter()
+      .filter(|app| app.install_root.is_dir())
+      .collect();
+    if existing.is_empty() {
+      return Err(
+        "No agent skills folder exists in this project yet; choose the agent apps to link into"
+          .to_string(),
+      );
+    }
+    existing
+  } else {
+    resolve_selected_apps_paths(&request.agent_apps, &install_target)?
+  };
+
+  // Skills locked to the same repository and commit share one download.
+  let mut downloads: HashMap<String, (WorkspaceSession, Result<(), String>)> = HashMap::new();
+  let mut results = Vec::new();
+  for (name, entry) in &lock.skills {
+    let result = try_sync_lock_entry(
+      name,
+      entry,
+      &selected_apps,
+      &request.method,
+      &install_target,
+      &mut downloads,
+    )
+    .await
+    .unwrap_or_else(|err| lock_sync_result(name, entry, ProjectLockSyncStatus::Failed, None, err));
+    results.push(result);
+  }
+  Ok(results)
+}
+
+/// Put a locked project skill back to the content recorded in skills-lock.json,
+/// discarding local edits. Agent links are kept; copied installs are refreshed.
+pub async fn restore_project_skill(
+  project_path: String,
+  name: String,
+) -> Result<ProjectLockSyncResult, String> {
+  let install_target = InstallTarget::from_scope(&InstallScope::Project, &Some(project_path))?;
+  let project_root = install_target.root_path()?;
+  let lock = read_project_skill_lock_internal(&project_root)?;
+  let entry = lock
+    .skills
+    .get(&name)
+    .ok_or_else(|| format!("Skill '{}' is not in skills-lock.json", name))?;
+
+  let mut downloads = HashMap::new();
+  let fetched = fetch_locked_skill(&name, entry, &mut downloads).await;
+  match fetched {
+    Ok(Ok((skill_dir, fetched_hash))) => {
+      restore_locked_skill_dir(&name, &skill_dir, &install_target).map(|_| {
+        lock_sync_result(
+          &name,
+          entry,
+          ProjectLockSyncStatus::Installed,
+          Some(fetched_hash),
+          format!("Skill '{}' restored from {}", name, entry.source),
+        )
+      })
+    },
+    Ok(Err(rejected)) => Ok(rejected),
+    Err(err) => Err(err),
+  }
+}
+
+fn restore_locked_skill_dir(
+  name: &str,
+  skill_dir: &Path,
+  install_target: &InstallTarget,
+) -> Result<(), String> {
+  plan_skill_refresh(skill_dir, name, install_target)?
+    .apply()?
+    .commit();
+  Ok(())
+}
+
+async fn try_sync_lock_entry(
+  name: &str,
+  entry: &ProjectSkillLockEntry,
+  selected_apps: &[SelectedAgentPath],
+  method: &InstallMethod,
+  install_target: &InstallTarget,
+  downloads: &mut HashMap<String, (WorkspaceSession, Result<(), String>)>,
+) -> Result<ProjectLockSyncResult, String> {
+  // A copy that already matches the lock only needs its links.
+  let canonical_skill_dir = canonical_skill_folder_by_name(name, install_target)?;
+  if canonical_skill_dir.is_dir() {
+    let current_hash = FolderHelper::compute_skill_folder_hash(&canonical_skill_dir)?;
+    if current_hash == entry.computed_hash {
+      plan_skill_install(
+        &canonical_skill_dir,
+        name,
+        method,
+        selected_apps,
+        install_target,
+      )?
+      .apply()?
+      .commit();
+      return Ok(lock_sync_result(
+        name,
+        entry,
+        ProjectLockSyncStatus::UpToDate,
+        Some(current_hash),
+        format!("Skill '{}' already matches the lock", name),
+      ));
+    }
+  }
+
+  let (skill_dir, fetched_hash) = match fetch_locked_skill(name, entry, downloads).await? {
+    Ok(fetched) => fetched,
+    Err(rejected) => return Ok(rejected),
+  };
+
+  plan_skill_install(&skill_dir, name, method, selected_apps, install_target)?
+    .apply()?
+    .commit();
+  Ok(lock_sync_result(
+    name,
+    entry,
+    ProjectLockSyncStatus::Installed,
+    Some(fetched_hash),
+    format!("Skill '{}' installed from {}", name, entry.source),
+  ))
+}
+
+/// Download a locked skill at its locked commit. The inner `Err` carries the result to
+/// report when the entry has no repository or the content does not match the lock.
+async fn fetch_locked_skill(
+  name: &str,
+  entry: &ProjectSkillLockEntry,
+  downloads: &mut HashMap<String, (WorkspaceSession, Result<(), String>)>,
+) -> Result<Result<(PathBuf, String), ProjectLockSyncResult>, String> {
+  let kind = match entry.provider() {
+    Some(kind) if !entry.source.trim().is_empty() => kind,
+    _ => {
+      return Ok(Err(lock_sync_result(
+        name,
+        entry,
+        ProjectLockSyncStatus::Unsatisfiable,
+        None,
+        format!(
+          "Skill '{}' has no repository to fetch it from (source type '{}')",
+          name, entry.source_type
+        ),
+      )))
+    },
+  };
+  let remote = RemoteSource::parse_as(&entry.source, Some(kind))?;
+
+  // The locked commit reproduces the recorded content even after the ref has moved.
+  let archive_ref = normalize_optional_string(entry.resolved_commit.clone())
+    .or_else(|| normalize_optional_string(entry.git_ref.clone()));
+  let key = format!(
+    "{}@{}",
+    remote.web_url(),
+    archive_ref.as_deref().unwrap_or_default()
+  );
+  if !downloads.contains_key(&key) {
+    let session = workspace_service::create_session(&format!("sync-{}", remote.repo.slug()))?;
+    let downloaded = remote
+      .download_to(archive_ref.as_deref(), session.path())
+      .await
+      .map(|_| ());
+    downloads.insert(key.clone(), (session, downloaded));
+  }
+  let (session, downloaded) = &downloads[&key];
+  downloaded.clone()?;
+
+  let (skill_dir, _) = locate_skill_in_repo(session.path(), name, None)?;
+  let fetched_hash = FolderHelper::compute_skill_folder_hash(&skill_dir)?;
+  if fetched_hash != entry.computed_hash {
+    return Ok(Err(lock_sync_result(
+      name,
+      entry,
+      ProjectLockSyncStatus::HashMismatch,
+      Some(fetched_hash),
+      format!(
+        "Fetched content of '{}' does not match the hash in skills-lock.json",
+        name
+      ),
+    )));
+  }
+
+  Ok(Ok((skill_dir, fetched_hash)))
+}
+
+fn lock_sync_result(
+  name: &str,
+  entry: &ProjectSkillLockEntry,
+  status: ProjectLockSyncStatus,
+  actual_hash: Option<String>,
+  message: String,
+) -> ProjectLockSyncResult {
+  ProjectLockSyncResult {
+    name: name.to_string(),
+    status,
+    source: entry.source.clone(),
+    expected_hash: entry.computed_hash.clone(),
+    actual_hash,
+    message,
+  }
+}
+
+fn read_remote_skill_origin(
+  name: &str,
+  install_target: &InstallTarget,
+) -> Result<RemoteSkillOrigin, String> {
+  let not_remote = || format!("Skill '{}' was not installed from a git repository", name);
+  match install_target {
+    InstallTarget::Global => {
+      let lock = read_skill_lock_internal()?;
+      let entry = lock.skills.get(name).ok_or_else(not_remote)?;
+      let kind = entry.provider().ok_or_else(not_remote)?;
+      if entry.source_url.trim().is_empty() {
+        return Err(not_remote());
+      }
+      Ok(RemoteSkillOrigin {
+        kind,
+        source: entry.source.clone(),
+        source_url: entry.source_url.clone(),
+        skill_path: normalize_optional_string(entry.skill_path.clone()),
+        hash: normalize_optional_string(entry.skill_folder_hash.clone()),
+        source_ref: entry.source_ref(),
+      })
+    },
+    InstallTarget::Project(project_root) => {
+      let lock = read_project_skill_lock_internal(project_root)?;
+      let entry = lock.skills.get(name).ok_or_else(not_remote)?;
+      let kind = entry.provider().ok_or_else(not_remote)?;
+      if entry.source.trim().is_empty() {
+        return Err(not_remote());
+      }
+      Ok(RemoteSkillOrigin {
+        kind,
+        source: entry.source.clone(),
+        source_url: entry.source.clone(),
+        skill_path: None,
+        hash: normalize_optional_string(Some(entry.computed_hash.clone())),
+        source_ref: entry.source_ref(),
+      })
+    },
+  }
+}
+
+/// Find the skill folder inside a downloaded repository, preferring the recorded
+/// `skill_path` and falling back to a frontmatter name match.
+fn locate_skill_in_repo(
+  repo_dir: &Path,
+  name: &str,
+  skill_path: Option<&str>,
+) -> Result<(PathBuf, String), String> {
+  if let Some(skill_path) = skill_path {
+    let relative_dir = skill_path
+      .trim_start_matches("./")
+      .trim_end_matches(SKILL_MD_FILE_NAME)
+      .trim_end_matches('/');
+    let dir = if relative_dir.is_empty() {
+      repo_dir.to_path_buf()
+    } else {
+      repo_dir.join(relative_dir)
+    };
+    if read_valid_skill_name(&dir).as_deref() == Some(name) {
+      return Ok((dir, skill_path_from_relative_dir(relative_dir)));
+    }
+  }
+
+  for dir in FolderHelper::find_dirs_containing_file(repo_dir, SKILL_MD_FILE_NAME)? {
+    if read_valid_skill_name(&dir).as_deref() != Some(name) {
+      continue;
+    }
+    let relative_dir = dir
+      .strip_prefix(repo_dir)
+      .map(|p| p.to_string_lossy().replace('\\', "/"))
+      .unwrap_or_default();
+    return Ok((dir, skill_path_from_relative_dir(&relative_dir)));
+  }
+
+  Err(format!(
+    "Skill '{}' no longer exists in the repository",
+    name
+  ))
+}
+
+/// Agent folders that currently hold the skill, with the method used to install it.
+fn existing_associations(
+  skill_name: &str,
+  install_target: &InstallTarget,
+) -> Result<Vec<(SelectedAgentPath, InstallMethod)>, String> {
+  let canonical_skill_dir = install_target.skill_folder_path()?.join(skill_name);
+  let mut seen: HashSet<PathBuf> = HashSet::new();
+  let mut associations = Vec::new();
+  for app in resolve_all_agent_skill_folders(install_target) {
+    let target = app.install_root.join(skill_name);
+    if target == canonical_skill_dir || !seen.insert(target.clone()) {
+      continue;
+    }
+    if target.exists() || target.is_symlink() {
+      let method = detect_install_method(&target);
+      associations.push((app, method));
+    }
+  }
+  Ok(associations)
+}
+
 pub fn delete_skill(
   name: String,
   scope: InstallScope,
@@ -550,10 +1243,54 @@ pub fn delete_skill(
   Ok(())
 }
 
+/// Link an installed canonical skill into the given agent apps, replacing whatever each
+/// agent folder currently holds under that name.
+pub fn link_skill_to_apps(
+  name: &str,
+  links: &[(String, InstallMethod)],
+  install_target: &InstallTarget,
+) -> Result<(), String> {
+  let canonical_skill_dir = canonical_skill_folder_by_name(name, install_target)?;
+  let mut errors = Vec::new();
+  let mut transaction = FsTransaction::new();
+  for (app_id, method) in links {
+    let selected = match resolve_selected_apps_paths(std::slice::from_ref(app_id), install_target) {
+      Ok(selected) => selected,
+      Err(err) => {
+        errors.push(err);
+        continue;
+      },
+    };
+    for app in selected {
+      let target = app.install_root.join(name);
+      if target != canonical_skill_dir {
+        transaction.link(&canonical_skill_dir, target, method.clone());
+      }
+      if let Some(kind) = app.adapter {
+        if let Err(err) = plan_adapter_output(
+          &mut transaction,
+          &canonical_skill_dir,
+          name,
+          kind,
+          install_target,
+        ) {
+          errors.push(format!("{}: {}", app.display_name, err));
+        }
+      }
+    }
+  }
+  // Agents that are no longer available are reported, but do not stop the others.
+  transaction.apply()?.commit();
+  if !errors.is_empty() {
+    return Err(errors.join("\n"));
+  }
+  Ok(())
+}
+
 fn validate_native_install_request(request: &InstallNativeRequest) -> Result<(), String> {
   validate_common_install_request(
     &request.name,
-    &request.tmp_path,
+    &request.session_id,
     &request.skill_path,
     &request.agent_apps,
     &request.scope,
@@ -564,13 +1301,13 @@ fn validate_native_install_request(request: &InstallNativeRequest) -> Result<(),
 fn validate_github_install_request(request: &InstallGithubRequest) -> Result<(), String> {
   validate_common_install_request(
     &request.name,
-    &request.tmp_path,
+    &request.session_id,
     &request.skill_path,
     &request.agent_apps,
     &request.scope,
     &request.project_path,
   )?;
-  GithubHelper::parse_github_url(&request.source_url)?;
+  RemoteSource::parse(&request.source_url)?;
   Ok(())
 }
 
@@ -609,7 +1346,7 @@ fn validate_install_from_unknown_request(request: &InstallUnknownRequest) -> Res
 
 fn validate_common_install_request(
   name: &str,
-  tmp_path: &str,
+  session_id: &str,
   skill_path: &str,
   agent_apps: &[String],
   scope: &InstallScope,
@@ -618,8 +1355,8 @@ fn validate_common_install_request(
   if name.trim().is_empty() {
     return Err("Skill name is required".to_string());
   }
-  if tmp_path.trim().is_empty() {
-    return Err("tmp_path is required".to_string());
+  if session_id.trim().is_empty() {
+    return Err("session_id is required".to_string());
   }
   if skill_path.trim().is_empty() {
     return Err("skill_path is required".to_string());
@@ -627,23 +1364,14 @@ fn validate_common_install_request(
   if agent_apps.is_empty() {
     return Err("agent_apps is required".to_string());
   }
-  InstallTarget::validate_install_scope(scope, project_path)?;
-
-  let source = Path::new(tmp_path);
-  if !source.exists() || !source.is_dir() {
-    return Err(format!(
-      "Detected skill tmp path does not exist: {}",
-      source.to_string_lossy()
-    ));
-  }
-  Ok(())
+  InstallTarget::validate_install_scope(scope, project_path)
 }
 
 fn remove_existing_associations(
   skill_name: &str,
   install_target: &InstallTarget,
 ) -> Result<(), String> {
-  let all_apps = resolve_all_available_apps_paths(install_target)?;
+  let all_apps = resolve_all_agent_skill_folders(install_target);
   let canonical_skill_dir = install_target.skill_folder_path()?.join(skill_name);
 
   let mut errors = Vec::new();
@@ -658,6 +1386,11 @@ fn remove_existing_associations(
       }
     }
   }
+  for (_, output) in skill_adapter::generated_outputs(skill_name, install_target) {
+    if let Err(err) = remove_path_any(&output) {
+      errors.push(format!("{}: {}", output.to_string_lossy(), err));
+    }
+  }
   if !errors.is_empty() {
     return Err(errors.join("\n"));
   }
@@ -781,56 +1514,128 @@ fn normalized_existing_skill_paths(
   Ok(out)
 }
 
-fn install_skill_to_apps(
+/// Plan putting `source` in place as the canonical copy of a skill and linking it into
+/// `selected_apps`. The skill is removed from every other agent folder.
+fn plan_skill_install(
   source: &Path,
   name: &str,
   method: &InstallMethod,
   selected_apps: &[SelectedAgentPath],
   install_target: &InstallTarget,
-) -> Result<(), String> {
-  let mut errors = Vec::new();
-  let canonical_skill_dir = prepare_canonical_skill_dir(source, name, install_target)?;
+) -> Result<FsTransaction, String> {
+  let links: Vec<(SelectedAgentPath, InstallMethod)> = selected_apps
+    .iter()
+    .map(|app| (app.clone(), method.clone()))
+    .collect();
+  plan_skill_install_links(source, name, &links, install_target)
+}
+
+/// [`plan_skill_install`] with a link method per agent.
+fn plan_skill_install_links(
+  source: &Path,
+  name: &str,
+  links: &[(SelectedAgentPath, InstallMethod)],
+  install_target: &InstallTarget,
+) -> Result<FsTransaction, String> {
+  let canonical_skill_dir = canonical_skill_folder_by_name(name, install_target)?;
+  let mut transaction = FsTransaction::new();
+  if source != canonical_skill_dir {
+    transaction.copy_dir(source, &canonical_skill_dir);
+  }
 
-  for app in selected_apps {
-    if let Err(err) = fs::create_dir_all(&app.install_root) {
-      errors.push(format!("{}: {}", app.display_name, err));
+  let selected_targets: HashSet<PathBuf> = links
+    .iter()
+    .map(|(app, _)| app.install_root.join(name))
+    .collect();
+  for app in resolve_all_agent_skill_folders(install_target) {
+    let target = app.install_root.join(name);
+    if target == canonical_skill_dir || selected_targets.contains(&target) {
       continue;
     }
+    if target.exists() || target.is_symlink() {
+      transaction.remove(target);
+    }
+  }
+
+  let mut seen = HashSet::new();
+  for (app, method) in links {
     let target = app.install_root.join(name);
-    if let Err(err) = install_to_app(&canonical_skill_dir, &target, method) {
-      errors.push(format!("{}: {}", app.display_name, err));
+    if target == canonical_skill_dir || !seen.insert(target.clone()) {
+      continue;
     }
+    transaction.link(&canonical_skill_dir, target, method.clone());
   }
 
-  if !errors.is_empty() {
-    return Err(errors.join("\n"));
+  // Agents that only read their own formats get the skill rendered for them
+  let mut adapters: Vec<SkillAdapterKind> = Vec::new();
+  for kind in links.iter().filter_map(|(app, _)| app.adapter) {
+    if !adapters.contains(&kind) {
+      adapters.push(kind);
+    }
   }
-  Ok(())
+  for (kind, output) in skill_adapter::generated_outputs(name, install_target) {
+    if !adapters.contains(&kind) {
+      transaction.remove(output);
+    }
+  }
+  for kind in adapters {
+    plan_adapter_output(&mut transaction, source, name, kind, install_target)?;
+  }
+  Ok(transaction)
 }
 
-fn prepare_canonical_skill_dir(
+/// Plan replacing the content of an installed skill with `source`, keeping its agent
+/// links. Symlinks follow the canonical copy; copied installs and files rendered by
+/// skill adapters are replaced too.
+fn plan_skill_refresh(
   source: &Path,
   name: &str,
-  target: &InstallTarget,
-) -> Result<PathBuf, String> {
-  let canonical_root = target.skill_folder_path()?;
-  fs::create_dir_all(&canonical_root).map_err(|e| e.to_string())?;
-
-  let canonical_skill_dir = canonical_root.join(name);
-  if canonical_skill_dir == source {
-    if !canonical_skill_dir.exists() {
-      return Err(format!(
-        "Canonical skill path does not exist: {}",
-        canonical_skill_dir.to_string_lossy()
-      ));
+  install_target: &InstallTarget,
+) -> Result<FsTransaction, String> {
+  let mut transaction = FsTransaction::new();
+  transaction.copy_dir(
+    source,
+    canonical_skill_folder_by_name(name, install_target)?,
+  );
+  for (app, method) in existing_associations(name, install_target)? {
+    if let InstallMethod::Copy = method {
+      transaction.copy_dir(source, app.install_root.join(name));
     }
-    return Ok(canonical_skill_dir);
   }
-  if canonical_skill_dir.exists() {
-    remove_path_any(&canonical_skill_dir)?;
+  for (kind, output) in skill_adapter::generated_outputs(name, install_target) {
+    transaction.write_file(
+      output,
+      skill_adapter::render_skill(kind, source, name, install_target)?,
+    );
   }
-  copy_dir_all_sync(source, &canonical_skill_dir)?;
-  Ok(canonical_skill_dir)
+  Ok(transaction)
+}
+
+/// Plan writing the skill in `source` in the format of `kind`. A file at that path that
+/// was not rendered by YouSkill is left alone.
+fn plan_adapter_output(
+  transaction: &mut FsTransaction,
+  source: &Path,
+  name: &str,
+  kind: SkillAdapterKind,
+  install_target: &InstallTarget,
+) -> Result<(), String> {
+  let Some(output) = skill_adapter::output_path(kind, name, install_target) else {
+    return Ok(());
+  };
+  if output.exists() && !skill_adapter::is_generated_for(&output, name) {
+    tracing::warn!(
+      "Keeping hand-written {} instead of rendering skill '{}'",
+      output.to_string_lossy(),
+      name
+    );
+    return Ok(());
+  }
+  transaction.write_file(
+    output,
+    skill_adapter::render_skill(kind, source, name, install_target)?,
+  );
+  Ok(())
 }
 
 fn skill_path_from_relative_dir(relative_dir: &str) -> String {
@@ -868,60 +1673,39 @@ fn sync_project_lock_for_install(
   skill_name: &str,
   source: &str,
   source_type: SourceType,
+  source_ref: Option<&SourceRef>,
   install_target: &InstallTarget,
 ) -> Result<(), String> {
   let skill_name = skill_name.to_string();
   let source = source.to_string();
   let project_root = install_target.root_path()?;
   let skill_dir = install_target.skill_folder_path()?.join(&skill_name);
-  add_skill_to_project_lock(&project_root, skill_name, source, source_type, &skill_dir)?;
+  add_skill_to_project_lock(
+    &project_root,
+    skill_name,
+    source,
+    source_type,
+    source_ref,
+    &skill_dir,
+  )?;
   Ok(())
 }
 
-fn create_temp_dir(prefix: &str) -> Result<PathBuf, String> {
-  let dir = std::env::temp_dir().join(format!(
-    "skill-kit-{}-{}-{}",
-    prefix,
-    std::process::id(),
-    now_millis()
-  ));
-  fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
-  Ok(dir)
-}
-
-fn install_to_app(source: &Path, target: &Path, method: &InstallMethod) -> Result<(), String> {
-  if source == target {
-    return Ok(());
-  }
-  match method {
-    InstallMethod::Symlink => {
-      #[cfg(unix)]
-      {
-        std::os::unix::fs::symlink(source, target).map_err(|e| e.to_string())
-      }
-      #[cfg(windows)]
-      {
-        match std::os::windows::fs::symlink_dir(source, target) {
-          Ok(_) => Ok(()),
-          Err(_) => copy_dir_all_sync(source, target),
-        }
-      }
-    },
-    InstallMethod::Copy => copy_dir_all_sync(source, target),
-  }
-}
-
 fn is_skill_folder(path: &Path) -> bool {
   (path.is_dir() || path.is_symlink()) && path.join(SKILL_MD_FILE_NAME).exists()
 }
 
 fn read_valid_skill_name(skill_dir: &Path) -> Option<String> {
+  read_valid_skill_frontmatter(skill_dir).map(|(name, _)| name)
+}
+
+fn read_valid_skill_frontmatter(skill_dir: &Path) -> Option<(String, SkillFrontmatter)> {
   let skill_md = skill_dir.join(SKILL_MD_FILE_NAME);
   if !skill_md.exists() {
     return None;
   }
   let frontmatter = FileHelper::read_skill_frontmatter(&skill_md).ok()?;
-  frontmatter.name
+  Some((frontmatter.name.clone()?, frontmatter))
 }
 
 fn detect_install_method(skill_dir: &Path) -> InstallMethod {
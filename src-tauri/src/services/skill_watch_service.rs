@@ -0,0 +1,382 @@
+use crate::models::{
+  AgentApp, AgentChange, InstallScope, InstallTarget, SkillChange, SkillWatchEvent,
+};
+use crate::services::{agent_apps_service, skill_service, user_projects_service};
+use crate::utils::folder::FolderHelper;
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Editors and installers touch several files per save; one batch per burst is enough.
+const DEBOUNCE: Duration = Duration::from_millis(750);
+const STOP_POLL: Duration = Duration::from_secs(1);
+/// Installing an agent binary touches no watched folder, so agents are re-detected on
+/// this interval as well.
+const AGENT_RECHECK: Duration = Duration::from_secs(5 * 60);
+
+/// Background watcher over the global skills folder, every agent's global folder and
+/// the skill folders of registered projects. Stops when dropped.
+pub struct SkillWatcher {
+  stop: Arc<AtomicBool>,
+  thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for SkillWatcher {
+  fn drop(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+/// Start watching and call `on_events` with each debounced batch of changes.
+pub fn start_skill_watcher<F>(on_events: F) -> Result<SkillWatcher, String>
+where
+  F: Fn(Vec<SkillWatchEvent>) + Send + 'static,
+{
+  let (tx, rx) = mpsc::channel::<DebounceEventResult>();
+  let debouncer =
+    new_debouncer(DEBOUNCE, tx).map_err(|e| format!("Failed to start file watcher: {}", e))?;
+  let stop = Arc::new(AtomicBool::new(false));
+  let stop_flag = stop.clone();
+
+  let thread = std::thread::Builder::new()
+    .name("skill-watcher".to_string())
+    .spawn(move || {
+      let mut state = WatchState::capture();
+      let mut watches = WatchSet::new(debouncer);
+      watches.sync(&state.targets());
+
+      while !stop_flag.load(Ordering::Relaxed) {
+        let paths: Vec<PathBuf> = match rx.recv_timeout(STOP_POLL) {
+          Ok(Ok(events)) => events.into_iter().map(|event| event.path).collect(),
+          Ok(Err(err)) => {
+            tracing::warn!("File watcher error: {}", err);
+            continue;
+          },
+          Err(RecvTimeoutError::Timeout) if state.agents_checked_at.elapsed() >= AGENT_RECHECK => {
+            Vec::new()
+          },
+          Err(RecvTimeoutError::Timeout) => continue,
+          Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let events = state.refresh(&paths);
+        watches.sync(&state.targets());
+        if !events.is_empty() {
+          on_events(events);
+        }
+      }
+    })
+    .map_err(|e| format!("Failed to start file watcher thread: {}", e))?;
+
+  Ok(SkillWatcher {
+    stop,
+    thread: Some(thread),
+  })
+}
+
+/// A folder whose contents matter, and the scope it belongs to. `None` is global.
+#[derive(Debug, Clone)]
+struct WatchTarget {
+  path: PathBuf,
+  project: Option<String>,
+}
+
+/// Per skill: content hash plus the agents linking it, so relinking counts as a change.
+type ScopeSnapshot = BTreeMap<String, (Option<String>, Vec<String>)>;
+
+struct WatchState {
+  agents: Vec<AgentApp>,
+  agents_checked_at: Instant,
+  projects: Vec<String>,
+  scopes: HashMap<Option<String>, ScopeSnapshot>,
+}
+
+impl WatchState {
+  fn capture() -> Self {
+    let mut state = WatchState {
+      agents: agent_apps_service::local_agent_apps(),
+      agents_checked_at: Instant::now(),
+      projects: registered_projects(),
+      scopes: HashMap::new(),
+    };
+    for scope in state.scope_keys() {
+      let snapshot = scan_scope(&scope);
+      state.scopes.insert(scope, snapshot);
+    }
+    state
+  }
+
+  fn scope_keys(&self) -> Vec<Option<String>> {
+    std::iter::once(None)
+      .chain(self.projects.iter().cloned().map(Some))
+      .collect()
+  }
+
+  fn targets(&self) -> Vec<WatchTarget> {
+    let mut targets = Vec::new();
+    if let Ok(root) = InstallTarget::Global.skill_folder_path() {
+      targets.push(WatchTarget {
+        path: root,
+        project: None,
+      });
+    }
+    // Every known agent, so a newly installed one is noticed.
+    for app in agent_apps_service::all_agent_apps() {
+      for path in agent_apps_service::agent_skill_roots(&app, &InstallTarget::Global) {
+        targets.push(WatchTarget {
+          path,
+          project: None,
+        });
+      }
+    }
+    // Registered projects and custom agents; only these files, not the rest of the
+    // config folder, which the scheduler and doctor write to.
+    for path in [
+      user_projects_service::user_projects_path(),
+      agent_apps_service::user_agent_apps_path(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+      targets.push(WatchTarget {
+        path,
+        project: None,
+      });
+    }
+    for project in &self.projects {
+      let root = PathBuf::from(project);
+      targets.push(WatchTarget {
+        path: root.join(".agents").join("skills"),
+        project: Some(project.clone()),
+      });
+      let target = InstallTarget::Project(root);
+      for app in &self.agents {
+        for path in agent_apps_service::agent_skill_roots(app, &target) {
+          targets.push(WatchTarget {
+            path,
+            project: Some(project.clone()),
+          });
+        }
+      }
+    }
+    targets
+  }
+
+  /// Re-read whatever the changed paths could affect and describe the differences.
+  fn refresh(&mut self, paths: &[PathBuf]) -> Vec<SkillWatchEvent> {
+    let mut events = Vec::new();
+
+    // Detection runs every agent's version command, so it is only redone on the
+    // recheck interval or when the changes could add or remove an agent.
+    let mut agents_changed = false;
+    if self.agents_checked_at.elapsed() >= AGENT_RECHECK || agents_may_have_changed(paths) {
+      agent_apps_service::refresh_local_agent_apps();
+      let agents = agent_apps_service::local_agent_apps();
+      agents_changed = diff_agents(&self.agents, &agents, &mut events);
+      self.agents = agents;
+      self.agents_checked_at = Instant::now();
+    }
+
+    // Projects registered or removed since the last batch start with a silent snapshot.
+    self.projects = registered_projects();
+    let keys = self.scope_keys();
+    self.scopes.retain(|scope, _| keys.contains(scope));
+
+    let targets = self.targets();
+    for scope in keys {
+      let Some(previous) = self.scopes.get(&scope) else {
+        let snapshot = scan_scope(&scope);
+        self.scopes.insert(scope, snapshot);
+        continue;
+      };
+      let affected = agents_changed
+        || targets
+          .iter()
+          .filter(|target| target.project == scope)
+          .any(|target| paths.iter().any(|path| is_related(path, &target.path)));
+      if !affected {
+        continue;
+      }
+      let current = scan_scope(&scope);
+      diff_scope(&scope, previous, &current, &mut events);
+      self.scopes.insert(scope, current);
+    }
+    events
+  }
+}
+
+/// Whether a change at one of `paths` could change which agents are detected: an edit of
+/// the custom agents file, or the creation or removal of an agent's global skills folder.
+/// Edits inside a skills folder leave detection as it was.
+fn agents_may_have_changed(paths: &[PathBuf]) -> bool {
+  let user_apps = agent_apps_service::user_agent_apps_path().ok();
+  let roots: Vec<PathBuf> = agent_apps_service::all_agent_apps()
+    .iter()
+    .flat_map(|app| agent_apps_service::agent_skill_roots(app, &InstallTarget::Global))
+    .collect();
+  paths.iter().any(|path| {
+    user_apps.as_deref() == Some(path.as_path()) || roots.iter().any(|root| root.starts_with(path))
+  })
+}
+
+/// A change at `path` matters if it is inside `target` or could create or remove it.
+fn is_related(path: &Path, target: &Path) -> bool {
+  path.starts_with(target) || target.starts_with(path)
+}
+
+fn registered_projects() -> Vec<String> {
+  user_projects_service::list_user_projects()
+    .map(|projects| projects.into_iter().map(|project| project.path).collect())
+    .unwrap_or_default()
+}
+
+fn scan_scope(project: &Option<String>) -> ScopeSnapshot {
+  let scope = if project.is_some() {
+    InstallScope::Project
+  } else {
+    InstallScope::Global
+  };
+  let skills = match skill_service::list_skills(scope, project.clone()) {
+    Ok(skills) => skills,
+    Err(err) => {
+      tracing::warn!("Failed to scan skills for {:?}: {}", project, err);
+      return ScopeSnapshot::new();
+    },
+  };
+  skills
+    .into_iter()
+    .map(|skill| {
+      let dir = skill.root_folder.clone().or_else(|| {
+        skill
+          .installed_agent_apps
+          .first()
+          .map(|app| app.skill_folder.clone())
+      });
+      let hash = dir.and_then(|dir| FolderHelper::compute_skill_folder_hash(Path::new(&dir)).ok());
+      let mut agents: Vec<String> = skill
+        .installed_agent_apps
+        .into_iter()
+        .map(|app| app.id)
+        .collect();
+      agents.sort();
+      (skill.name, (hash, agents))
+    })
+    .collect()
+}
+
+fn diff_scope(
+  project: &Option<String>,
+  previous: &ScopeSnapshot,
+  current: &ScopeSnapshot,
+  events: &mut Vec<SkillWatchEvent>,
+) {
+  let change = |name: &str| SkillChange {
+    name: name.to_string(),
+    scope: if project.is_some() {
+      InstallScope::Project
+    } else {
+      InstallScope::Global
+    },
+    project_path: project.clone(),
+  };
+  for (name, state) in current {
+    match previous.get(name) {
+      None => events.push(SkillWatchEvent::SkillAdded(change(name))),
+      Some(previous_state) if previous_state != state => {
+        events.push(SkillWatchEvent::SkillModified(change(name)))
+      },
+      Some(_) => {},
+    }
+  }
+  for name in previous.keys() {
+    if !current.contains_key(name) {
+      events.push(SkillWatchEvent::SkillRemoved(change(name)));
+    }
+  }
+}
+
+/// Returns whether the set of agents changed.
+fn diff_agents(
+  previous: &[AgentApp],
+  current: &[AgentApp],
+  events: &mut Vec<SkillWatchEvent>,
+) -> bool {
+  let change = |app: &AgentApp| AgentChange {
+    id: app.id.clone(),
+    display_name: app.display_name.clone(),
+  };
+  let before = events.len();
+  for app in current {
+    if !previous.iter().any(|item| item.id == app.id) {
+      events.push(SkillWatchEvent::AgentDetected(change(app)));
+    }
+  }
+  for app in previous {
+    if !current.iter().any(|item| item.id == app.id) {
+      events.push(SkillWatchEvent::AgentRemoved(change(app)));
+    }
+  }
+  events.len() != before
+}
+
+/// Keeps the OS watches in line with the targets. Targets that do not exist yet are
+/// covered by watching their nearest existing parent, so their creation is seen.
+struct WatchSet {
+  debouncer: Debouncer<notify_debouncer_mini::notify::RecommendedWatcher>,
+  watched: HashMap<PathBuf, RecursiveMode>,
+}
+
+impl WatchSet {
+  fn new(debouncer: Debouncer<notify_debouncer_mini::notify::RecommendedWatcher>) -> Self {
+    Self {
+      debouncer,
+      watched: HashMap::new(),
+    }
+  }
+
+  fn sync(&mut self, targets: &[WatchTarget]) {
+    let mut desired: HashMap<PathBuf, RecursiveMode> = HashMap::new();
+    for target in targets {
+      let (path, mode) = if target.path.is_dir() {
+        (target.path.clone(), RecursiveMode::Recursive)
+      } else {
+        let Some(parent) = target.path.ancestors().skip(1).find(|p| p.is_dir()) else {
+          continue;
+        };
+        (parent.to_path_buf(), RecursiveMode::NonRecursive)
+      };
+      let entry = desired.entry(path).or_insert(mode);
+      if mode == RecursiveMode::Recursive {
+        *entry = RecursiveMode::Recursive;
+      }
+    }
+
+    let watcher = self.debouncer.watcher();
+    self.watched.retain(|path, mode| {
+      if desired.get(path) == Some(mode) {
+        return true;
+      }
+      let _ = watcher.unwatch(path);
+      false
+    });
+    for (path, mode) in desired {
+      if self.watched.contains_key(&path) {
+        continue;
+      }
+      match watcher.watch(&path, mode) {
+        Ok(()) => {
+          self.watched.insert(path, mode);
+        },
+        Err(err) => tracing::warn!("Failed to watch {}: {}", path.to_string_lossy(), err),
+      }
+    }
+  }
+}
@@ -0,0 +1,14 @@
+use crate::config::SourceHostConfig;
+use crate::models::SourceConnectionStatus;
+use crate::utils::source_provider;
+
+/// Check that a git host is reachable with the given settings and report what its
+/// token can do. The settings are tested as given, so they can be checked before saving.
+pub async fn test_source_connection(
+  host: SourceHostConfig,
+) -> Result<SourceConnectionStatus, String> {
+  if host.base_url.trim().is_empty() {
+    return Err("base_url is required".to_string());
+  }
+  source_provider::test_connection(&host).await
+}
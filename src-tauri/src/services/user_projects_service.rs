@@ -58,7 +58,7 @@ pub fn remove_user_project(name: String) -> Result<(), String> {
   Ok(())
 }
 
-fn user_projects_path() -> Result<PathBuf, String> {
+pub fn user_projects_path() -> Result<PathBuf, String> {
   let config_dir = dirs_next::config_dir().ok_or("Unable to get config directory")?;
   Ok(config_dir.join("youskill").join("user_projects.json"))
 }
@@ -0,0 +1,266 @@
+use crate::config::load_config;
+use crate::utils::time::now_millis;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+const WORKSPACE_DIR_NAME: &str = "youskill-workspace";
+/// Prefix of the per-call temp folders used before sessions existed.
+const LEGACY_TEMP_PREFIX: &str = "skill-kit-";
+/// Sessions untouched for this long belong to a detect that was never installed.
+const SESSION_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+static SESSION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A staging folder for downloaded, extracted or copied skills.
+///
+/// The folder is removed when the session is dropped, unless it is kept with
+/// [`WorkspaceSession::keep`] so a later install can refer to it by ID.
+#[derive(Debug)]
+pub struct WorkspaceSession {
+  id: String,
+  path: PathBuf,
+  kept: bool,
+}
+
+impl WorkspaceSession {
+  pub fn id(&self) -> &str {
+    &self.id
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Leave the folder in place until [`release_session`] or the next sweep.
+  pub fn keep(mut self) -> String {
+    self.kept = true;
+    self.id.clone()
+  }
+}
+
+impl Drop for WorkspaceSession {
+  fn drop(&mut self) {
+    if self.kept {
+      return;
+    }
+    if let Err(err) = fs::remove_dir_all(&self.path) {
+      if err.kind() != std::io::ErrorKind::NotFound {
+        tracing::warn!(
+          "Failed to remove workspace session {}: {}",
+          self.path.to_string_lossy(),
+          err
+        );
+      }
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct WorkspaceSweepResult {
+  pub removed_sessions: usize,
+  pub freed_bytes: u64,
+  pub remaining_bytes: u64,
+}
+
+pub fn workspace_root() -> PathBuf {
+  std::env::temp_dir().join(WORKSPACE_DIR_NAME)
+}
+
+/// Create an empty session folder. `kind` says what it is for, e.g. `detect-zip`.
+pub fn create_session(kind: &str) -> Result<WorkspaceSession, String> {
+  let kind: String = kind
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() {
+        c.to_ascii_lowercase()
+      } else {
+        '-'
+      }
+    })
+    .collect();
+  let id = format!(
+    "{}-{}-{}-{}",
+    kind.trim_matches('-'),
+    std::process::id(),
+    now_millis(),
+    SESSION_COUNTER.fetch_add(1, Ordering::Relaxed)
+  );
+  let path = workspace_root().join(&id);
+  fs::create_dir_all(&path).map_err(|e| format!("Failed to create workspace session: {}", e))?;
+  Ok(WorkspaceSession {
+    id,
+    path,
+    kept: false,
+  })
+}
+
+/// Folder of a kept session.
+pub fn session_path(session_id: &str) -> Result<PathBuf, String> {
+  validate_session_id(session_id)?;
+  let path = workspace_root().join(session_id);
+  if !path.is_dir() {
+    return Err(format!(
+      "Detect session '{}' no longer exists, detect the skill again",
+      session_id
+    ));
+  }
+  Ok(path)
+}
+
+/// Folder of a skill staged in a session, given the relative path of its SKILL.md.
+pub fn session_skill_dir(session_id: &str, skill_path: &str) -> Result<PathBuf, String> {
+  let root = session_path(session_id)?;
+  let relative = Path::new(skill_path);
+  if !relative
+    .components()
+    .all(|component| matches!(component, Component::Normal(_)))
+  {
+    return Err(format!("Invalid skill path: {}", skill_path));
+  }
+  let skill_dir = match relative.parent() {
+    Some(parent) => root.join(parent),
+    None => root,
+  };
+  if !skill_dir
+    .join(relative.file_name().unwrap_or_default())
+    .is_file()
+  {
+    return Err(format!(
+      "Skill '{}' not found in detect session '{}'",
+      skill_path, session_id
+    ));
+  }
+  Ok(skill_dir)
+}
+
+/// Remove a kept session once its skills are installed or the install was cancelled.
+pub fn release_session(session_id: &str) -> Result<(), String> {
+  validate_session_id(session_id)?;
+  let path = workspace_root().join(session_id);
+  match fs::remove_dir_all(&path) {
+    Ok(()) => Ok(()),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+    Err(err) => Err(format!("Failed to remove detect session: {}", err)),
+  }
+}
+
+/// Remove sessions older than a day, then the oldest remaining ones until the workspace
+/// fits the configured size cap. Leftover temp folders from older versions go too.
+pub fn sweep_workspace() -> Result<WorkspaceSweepResult, String> {
+  let max_bytes = load_config()?.workspace_max_mb.saturating_mul(1024 * 1024);
+  let mut result = WorkspaceSweepResult::default();
+  let now = SystemTime::now();
+  let is_stale = |modified: SystemTime| {
+    now
+      .duration_since(modified)
+      .map(|age| age > SESSION_MAX_AGE)
+      .unwrap_or(false)
+  };
+
+  let mut sessions: Vec<(PathBuf, SystemTime, u64)> = Vec::new();
+  let root = workspace_root();
+  if root.is_dir() {
+    for entry in fs::read_dir(&root).map_err(|e| e.to_string())? {
+      let path = entry.map_err(|e| e.to_string())?.path();
+      let modified = modified_time(&path);
+      let size = dir_size(&path);
+      if is_stale(modified) {
+        remove_swept(&path, size, &mut result);
+      } else {
+        sessions.push((path, modified, size));
+      }
+    }
+  }
+
+  if let Ok(entries) = fs::read_dir(std::env::temp_dir()) {
+    for entry in entries.filter_map(Result::ok) {
+      let path = entry.path();
+      let is_legacy = entry
+        .file_name()
+        .to_string_lossy()
+        .starts_with(LEGACY_TEMP_PREFIX);
+      if is_legacy && path.is_dir() && is_stale(modified_time(&path)) {
+        remove_swept(&path, dir_size(&path), &mut result);
+      }
+    }
+  }
+
+  // Oldest first, so the sessions most likely still in use survive the cap.
+  sessions.sort_by_key(|(_, modified, _)| *modified);
+  let mut total: u64 = sessions.iter().map(|(_, _, size)| size).sum();
+  for (path, _, size) in &sessions {
+    if total <= max_bytes {
+      break;
+    }
+    remove_swept(path, *size, &mut result);
+    total = total.saturating_sub(*size);
+  }
+  result.remaining_bytes = total;
+  Ok(result)
+}
+
+fn validate_session_id(session_id: &str) -> Result<(), String> {
+  let valid = !session_id.is_empty()
+    && session_id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-');
+  if !valid {
+    return Err(format!("Invalid detect session id: {}", session_id));
+  }
+  Ok(())
+}
+
+fn remove_swept(path: &Path, size: u64, result: &mut WorkspaceSweepResult) {
+  match fs::remove_dir_all(path) {
+    Ok(()) => {
+      result.removed_sessions += 1;
+      result.freed_bytes += size;
+    },
+    Err(err) => tracing::warn!("Failed to remove {}: {}", path.to_string_lossy(), err),
+  }
+}
+
+fn modified_time(path: &Path) -> SystemTime {
+  fs::metadata(path)
+    .and_then(|metadata| metadata.modified())
+    .unwrap_or_else(|_| SystemTime::now())
+}
+
+fn dir_size(path: &Path) -> u64 {
+  WalkDir::new(path)
+    .follow_links(false)
+    .into_iter()
+    .filter_map(Result::ok)
+    .filter(|entry| entry.file_type().is_file())
+    .filter_map(|entry| entry.metadata().ok())
+    .map(|metadata| metadata.len())
+    .sum()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn session_resolves_staged_skills_and_cleans_up_on_drop() {
+    let session = create_session("test").unwrap();
+    let skill_dir = session.path().join("skills").join("demo");
+    fs::create_dir_all(&skill_dir).unwrap();
+    fs::write(skill_dir.join("SKILL.md"), "---\nname: demo\n---\n").unwrap();
+    let id = session.id().to_string();
+
+    let resolved = session_skill_dir(&id, "skills/demo/SKILL.md").unwrap();
+    let escaped = session_skill_dir(&id, "../demo/SKILL.md");
+    let path = session.path().to_path_buf();
+    drop(session);
+
+    assert_eq!(resolved, skill_dir);
+    assert!(escaped.is_err());
+    assert!(!path.exists());
+    assert!(session_path(&id).is_err());
+  }
+}
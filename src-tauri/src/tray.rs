@@ -1,16 +1,83 @@
 use image::ImageDecoder;
+use std::sync::Mutex;
 use tauri::{
-  menu::{Menu, MenuItem, PredefinedMenuItem},
+  menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
   tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
   AppHandle, Emitter, Manager,
 };
+use you_skill::models::{InstallScope, RemoteSkill, ScheduledJob};
+use you_skill::services::{scheduler_service, skill_service, user_projects_service};
+
+const TRAY_ID: &str = "main";
+/// Projects listed under "Recent projects", newest first
+const RECENT_PROJECTS: usize = 10;
+const UPDATE_PREFIX: &str = "update:";
+const PROJECT_PREFIX: &str = "project:";
+
+/// Global skills last reported as outdated, by the scheduler or the UI's own check.
+#[derive(Default)]
+pub struct TrayUpdates(Mutex<Vec<RemoteSkill>>);
 
 pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+  // Start from the last scheduled update check so the badge survives restarts
+  let updates = scheduler_service::read_schedule_state()
+    .ok()
+    .and_then(|state| state.update_check)
+    .filter(|run| run.success)
+    .map(|run| run.updates)
+    .unwrap_or_default();
+  app.manage(TrayUpdates(Mutex::new(updates)));
+
   // Build tray menu
   build_tray_menu(app)?;
   Ok(())
 }
 
+/// Replace the known updates and rebuild the menu.
+pub fn set_tray_updates(app: &AppHandle, updates: Vec<RemoteSkill>) {
+  if let Some(state) = app.try_state::<TrayUpdates>() {
+    if let Ok(mut current) = state.0.lock() {
+      *current = updates;
+    }
+  }
+  refresh_tray(app);
+}
+
+/// Rebuild the menu after skills, projects or updates change.
+pub fn refresh_tray(app: &AppHandle) {
+  let Some(tray) = app.tray_by_id(TRAY_ID) else {
+    return;
+  };
+  let updates = current_updates(app);
+  let result = create_menu(app, &updates).and_then(|menu| {
+    tray.set_menu(Some(menu))?;
+    let tooltip = match updates.len() {
+      0 => "YouSkill".to_string(),
+      count => format!("YouSkill - {} skill updates", count),
+    };
+    tray.set_tooltip(Some(tooltip))?;
+    // Shown next to the icon on macOS and Linux
+    tray.set_title((!updates.is_empty()).then(|| updates.len().to_string()))
+  });
+  if let Err(e) = result {
+    tracing::warn!("Failed to refresh tray menu: {}", e);
+  }
+}
+
+/// Known updates minus the skills updated since, which only needs the skill lock.
+fn current_updates(app: &AppHandle) -> Vec<RemoteSkill> {
+  let Some(state) = app.try_state::<TrayUpdates>() else {
+    return Vec::new();
+  };
+  let Ok(mut known) = state.0.lock() else {
+    return Vec::new();
+  };
+  if let Ok(outdated) = scheduler_service::outdated_skills(known.clone()) {
+    *known = outdated;
+  }
+  known.clone()
+}
+
 fn load_tray_icon() -> tauri::image::Image<'static> {
   // Load PNG from bytes and convert to RGBA
   let png_data = include_bytes!("../icons/logo-tray-32.png");
@@ -26,21 +93,89 @@ fn load_tray_icon() -> tauri::image::Image<'static> {
   tauri::image::Image::new_owned(rgba_vec, width, height)
 }
 
-fn build_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-  // Create menu items
-  let open_item = MenuItem::with_id(app, "open", "Open YouSkill", true, None::<&str>)?;
-  let install_item = MenuItem::with_id(app, "install", "Install New", true, None::<&str>)?;
-  let separator = PredefinedMenuItem::separator(app)?;
-  let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+fn create_menu(app: &AppHandle, updates: &[RemoteSkill]) -> tauri::Result<Menu<tauri::Wry>> {
+  let menu = Menu::new(app)?;
+  menu.append(&MenuItem::with_id(
+    app,
+    "open",
+    "Open YouSkill",
+    true,
+    None::<&str>,
+  )?)?;
+  menu.append(&MenuItem::with_id(
+    app,
+    "install",
+    "Install New",
+    true,
+    None::<&str>,
+  )?)?;
+  menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+  // Updates
+  if updates.is_empty() {
+    menu.append(&MenuItem::with_id(
+      app,
+      "no_updates",
+      "All skills up to date",
+      false,
+      None::<&str>,
+    )?)?;
+  } else {
+    menu.append(&MenuItem::with_id(
+      app,
+      "update_all",
+      format!("Update All ({})", updates.len()),
+      true,
+      None::<&str>,
+    )?)?;
+    let submenu = Submenu::new(app, "Skills with Updates", true)?;
+    for skill in updates {
+      submenu.append(&MenuItem::with_id(
+        app,
+        format!("{}{}", UPDATE_PREFIX, skill.name),
+        &skill.name,
+        true,
+        None::<&str>,
+      )?)?;
+    }
+    menu.append(&submenu)?;
+  }
+
+  // Recent projects
+  let projects = user_projects_service::list_user_projects().unwrap_or_default();
+  let submenu = Submenu::new(app, "Recent Projects", !projects.is_empty())?;
+  for project in projects.iter().rev().take(RECENT_PROJECTS) {
+    submenu.append(&MenuItem::with_id(
+      app,
+      format!("{}{}", PROJECT_PREFIX, project.path),
+      &project.name,
+      true,
+      None::<&str>,
+    )?)?;
+  }
+  menu.append(&submenu)?;
+
+  menu.append(&MenuItem::with_id(
+    app,
+    "backup",
+    "Backup Now",
+    true,
+    None::<&str>,
+  )?)?;
+  menu.append(&PredefinedMenuItem::separator(app)?)?;
+  menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+  Ok(menu)
+}
 
-  // Build menu
-  let menu = Menu::with_items(app, &[&open_item, &install_item, &separator, &quit_item])?;
+fn build_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+  let updates = current_updates(app);
+  let menu = create_menu(app, &updates)?;
 
   // Load tray icon
   let tray_icon = load_tray_icon();
 
-  // Create tray icon (only created once at startup)
-  let _tray = TrayIconBuilder::new()
+  // Create tray icon (only created once at startup; the menu is swapped by refresh_tray)
+  let _tray = TrayIconBuilder::with_id(TRAY_ID)
     .icon(tray_icon)
     .icon_as_template(true)
     .menu(&menu)
@@ -55,7 +190,23 @@ fn build_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
       "install" => {
         show_main_window_with_install(app);
       },
-      _ => {},
+      "update_all" => {
+        let names = current_updates(app)
+          .into_iter()
+          .map(|skill| skill.name)
+          .collect();
+        update_skills(app, names);
+      },
+      "backup" => {
+        backup_now(app);
+      },
+      id => {
+        if let Some(name) = id.strip_prefix(UPDATE_PREFIX) {
+          update_skills(app, vec![name.to_string()]);
+        } else if let Some(path) = id.strip_prefix(PROJECT_PREFIX) {
+          show_main_window_with_project(app, path);
+        }
+      },
     })
     .on_tray_icon_event(|tray, event| {
       if let TrayIconEvent::Click {
@@ -70,9 +221,37 @@ fn build_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     })
     .build(app)?;
 
+  refresh_tray(app);
   Ok(())
 }
 
+fn update_skills(app: &AppHandle, names: Vec<String>) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    for name in names {
+      match skill_service::update_skill(name.clone(), InstallScope::Global, None).await {
+        Ok(result) if !result.success => {
+          tracing::warn!("Failed to update {}: {}", name, result.message);
+        },
+        Ok(_) => {},
+        Err(e) => tracing::warn!("Failed to update {}: {}", name, e),
+      }
+    }
+    // The skill watcher tells the UI about the changed folders
+    refresh_tray(&app);
+  });
+}
+
+fn backup_now(app: &AppHandle) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let run = scheduler_service::run_job_now(ScheduledJob::Backup).await;
+    if let Err(e) = app.emit(scheduler_service::SCHEDULED_JOB_EVENT, &run) {
+      tracing::warn!("Failed to emit backup result: {}", e);
+    }
+  });
+}
+
 fn show_main_window(app: &AppHandle) {
   if let Some(window) = app.get_webview_window("main") {
     let _ = window.unminimize();
@@ -91,3 +270,14 @@ fn show_main_window_with_install(app: &AppHandle) {
     let _ = window.emit("open-install-modal", ());
   }
 }
+
+fn show_main_window_with_project(app: &AppHandle, project_path: &str) {
+  if let Some(window) = app.get_webview_window("main") {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+
+    // Emit event to frontend to switch the local list to the project
+    let _ = window.emit("open-project", project_path);
+  }
+}
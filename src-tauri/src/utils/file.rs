@@ -1,13 +1,9 @@
+use crate::models::SkillFrontmatter;
 use serde_yaml::Value;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
 
-#[derive(Debug, Clone, Default)]
-pub struct SkillFrontmatter {
-  pub name: Option<String>,
-}
-
 pub struct FileHelper;
 
 impl FileHelper {
@@ -21,6 +17,10 @@ impl FileHelper {
 
   pub fn read_skill_frontmatter(skill_md_path: &Path) -> Result<SkillFrontmatter, String> {
     let content = Self::read_to_string(skill_md_path)?;
+    Self::parse_skill_frontmatter(&content)
+  }
+
+  pub fn parse_skill_frontmatter(content: &str) -> Result<SkillFrontmatter, String> {
     let mut lines = content.lines();
 
     if lines.next().map(|s| s.trim()) != Some("---") {
@@ -44,13 +44,114 @@ impl FileHelper {
 
     let yaml: Value = serde_yaml::from_str(&frontmatter)
       .map_err(|e| format!("Failed to parse SKILL.md frontmatter: {}", e))?;
-    let name = yaml
-      .get("name")
-      .and_then(|v| v.as_str())
-      .map(|s| s.trim().to_string())
+    Ok(frontmatter_from_yaml(&yaml))
+  }
+}
+
+/// Map the YAML frontmatter onto the known fields, keeping every other key in `extra`.
+/// Fields with an unexpected type are dropped rather than failing the whole skill.
+fn frontmatter_from_yaml(yaml: &Value) -> SkillFrontmatter {
+  let mut frontmatter = SkillFrontmatter::default();
+  let Some(mapping) = yaml.as_mapping() else {
+    return frontmatter;
+  };
+
+  for (key, value) in mapping {
+    let Some(key) = key.as_str() else {
+      continue;
+    };
+    match key {
+      "name" => {
+        frontmatter.name = value
+          .as_str()
+          .map(|s| s.trim().to_string())
+          .filter(|s| !s.is_empty())
+      },
+      "description" => frontmatter.description = yaml_scalar_string(value),
+      "license" => frontmatter.license = yaml_scalar_string(value),
+      "version" => frontmatter.version = yaml_scalar_string(value),
+      "compatibility" => frontmatter.compatibility = yaml_scalar_string(value),
+      "allowed-tools" | "allowed_tools" => frontmatter.allowed_tools = yaml_tool_list(value),
+      "metadata" => {
+        frontmatter.metadata = value
+          .as_mapping()
+          .map(|metadata| {
+            metadata
+              .iter()
+              .filter_map(|(k, v)| Some((k.as_str()?.to_string(), yaml_to_json(v))))
+              .collect()
+          })
+          .unwrap_or_default()
+      },
+      _ => {
+        frontmatter
+          .extra
+          .insert(key.to_string(), yaml_to_json(value));
+      },
+    }
+  }
+
+  // The spec keeps the version under `metadata`; many skills put it at the top level.
+  if frontmatter.version.is_none() {
+    frontmatter.version = frontmatter
+      .metadata
+      .get("version")
+      .and_then(|v| match v {
+        serde_json::Value::String(s) => Some(s.trim().to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+      })
       .filter(|s| !s.is_empty());
-    Ok(SkillFrontmatter { name })
   }
+
+  frontmatter
+}
+
+fn yaml_scalar_string(value: &Value) -> Option<String> {
+  let text = match value {
+    Value::String(s) => s.trim().to_string(),
+    Value::Number(n) => n.to_string(),
+    Value::Bool(b) => b.to_string(),
+    _ => return None,
+  };
+  Some(text).filter(|s| !s.is_empty())
+}
+
+/// `allowed-tools` is a space-delimited string in the spec, but comma-separated strings
+/// and YAML lists are common too. Separators inside parentheses, as in
+/// `Bash(git add:*)`, belong to the tool pattern.
+fn yaml_tool_list(value: &Value) -> Vec<String> {
+  match value {
+    Value::Sequence(items) => items.iter().filter_map(yaml_scalar_string).collect(),
+    Value::String(s) => {
+      let mut tools = Vec::new();
+      let mut current = String::new();
+      let mut depth = 0usize;
+      for c in s.chars() {
+        match c {
+          '(' => depth += 1,
+          ')' => depth = depth.saturating_sub(1),
+          _ => {},
+        }
+        if depth == 0 && (c == ',' || c.is_whitespace()) {
+          if !current.is_empty() {
+            tools.push(std::mem::take(&mut current));
+          }
+          continue;
+        }
+        current.push(c);
+      }
+      if !current.is_empty() {
+        tools.push(current);
+      }
+      tools
+    },
+    _ => Vec::new(),
+  }
+}
+
+fn yaml_to_json(value: &Value) -> serde_json::Value {
+  serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
 }
 
 pub fn open_in_file_manager(file_path: String) -> Result<(), String> {
@@ -119,3 +220,25 @@ pub async fn read_skill_file(skill_path: String) -> Result<String, String> {
 
   Err(format!("SKILL.md not found in: {}", skill_path))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_known_fields_and_keeps_extra_keys() {
+    let content = "---\nname: pdf\ndescription: Work with PDFs\nlicense: MIT\nversion: 1.2\n\
+      allowed-tools: Read Bash(git add:*), Grep\nmetadata:\n  author: acme\ntags: [docs]\n---\n# PDF\n";
+    let frontmatter = FileHelper::parse_skill_frontmatter(content).unwrap();
+    assert_eq!(frontmatter.name.as_deref(), Some("pdf"));
+    assert_eq!(frontmatter.description.as_deref(), Some("Work with PDFs"));
+    assert_eq!(frontmatter.license.as_deref(), Some("MIT"));
+    assert_eq!(frontmatter.version.as_deref(), Some("1.2"));
+    assert_eq!(
+      frontmatter.allowed_tools,
+      vec!["Read", "Bash(git add:*)", "Grep"]
+    );
+    assert_eq!(frontmatter.metadata["author"], "acme");
+    assert_eq!(frontmatter.extra["tags"], serde_json::json!(["docs"]));
+  }
+}
@@ -1,5 +1,8 @@
 use crate::utils::file::FileHelper;
+use crate::utils::path::remove_path_any;
+use crate::utils::time::now_millis;
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
 use std::fs::File;
 use std::io::{BufReader, Read};
@@ -100,7 +103,9 @@ impl FolderHelper {
     Ok((entry_count, total_size, latest_mtime_secs))
   }
 
-  pub fn compute_skill_folder_hash(skill_dir: &Path) -> Result<String, String> {
+  /// Files that make up a skill, sorted by relative path. `.git` and `node_modules`
+  /// are not part of the skill.
+  fn skill_folder_files(skill_dir: &Path) -> Result<Vec<(String, Vec<u8>)>, String> {
     if !skill_dir.exists() || !skill_dir.is_dir() {
       return Err(format!(
         "Skill directory does not exist: {}",
@@ -136,6 +141,11 @@ impl FolderHelper {
     }
 
     files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+  }
+
+  pub fn compute_skill_folder_hash(skill_dir: &Path) -> Result<String, String> {
+    let files = Self::skill_folder_files(skill_dir)?;
 
     let mut hasher = Sha256::new();
     for (relative_path, content) in files {
@@ -145,15 +155,31 @@ impl FolderHelper {
 
     Ok(format!("{:x}", hasher.finalize()))
   }
+
+  /// SHA-256 of each file in a skill, keyed by relative path, so later changes can be
+  /// traced to individual files.
+  pub fn compute_skill_file_hashes(skill_dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    Ok(
+      Self::skill_folder_files(skill_dir)?
+        .into_iter()
+        .map(|(relative_path, content)| (relative_path, format!("{:x}", Sha256::digest(&content))))
+        .collect(),
+    )
+  }
 }
 
+/// Copy the contents of `src` into `dst`. Symlinks inside `src` are copied as links,
+/// so what they point at is never pulled into `dst`.
 pub fn copy_dir_all_sync(src: &Path, dst: &Path) -> Result<(), String> {
   fs::create_dir_all(dst).map_err(|e| e.to_string())?;
   for entry in fs::read_dir(src).map_err(|e| e.to_string())? {
     let entry = entry.map_err(|e| e.to_string())?;
     let src_path = entry.path();
     let dst_path = dst.join(entry.file_name());
-    if src_path.is_dir() {
+    let file_type = entry.file_type().map_err(|e| e.to_string())?;
+    if file_type.is_symlink() {
+      copy_symlink(&src_path, &dst_path)?;
+    } else if file_type.is_dir() {
       copy_dir_all_sync(&src_path, &dst_path)?;
     } else {
       fs::copy(&src_path, &dst_path).map_err(|e| e.to_string())?;
@@ -161,3 +187,125 @@ pub fn copy_dir_all_sync(src: &Path, dst: &Path) -> Result<(), String> {
   }
   Ok(())
 }
+
+fn copy_symlink(src: &Path, dst: &Path) -> Result<(), String> {
+  let target = fs::read_link(src).map_err(|e| e.to_string())?;
+  #[cfg(unix)]
+  {
+    std::os::unix::fs::symlink(&target, dst).map_err(|e| e.to_string())
+  }
+  #[cfg(not(unix))]
+  {
+    // Creating symlinks needs extra privileges on Windows; skills work without them.
+    tracing::warn!(
+      "Skipping symlink {} -> {}",
+      src.to_string_lossy(),
+      target.to_string_lossy()
+    );
+    let _ = dst;
+    Ok(())
+  }
+}
+
+/// Replace `target` with a copy of `source`.
+///
+/// The new content is staged next to `target` and swapped in with renames, so `target`
+/// is never left half-written. If the swap fails the previous content is put back.
+pub fn replace_dir_sync(source: &Path, target: &Path) -> Result<(), String> {
+  let parent = target
+    .parent()
+    .ok_or(format!("Invalid target path: {}", target.to_string_lossy()))?;
+  let name = target
+    .file_name()
+    .ok_or(format!("Invalid target path: {}", target.to_string_lossy()))?
+    .to_string_lossy()
+    .to_string();
+  let stamp = now_millis();
+  let staging = parent.join(format!(".{}.staging-{}", name, stamp));
+  let backup = parent.join(format!(".{}.backup-{}", name, stamp));
+
+  if let Err(err) = copy_dir_all_sync(source, &staging) {
+    let _ = fs::remove_dir_all(&staging);
+    return Err(err);
+  }
+
+  let had_previous = target.exists() || target.is_symlink();
+  if had_previous {
+    if let Err(err) = fs::rename(target, &backup) {
+      let _ = fs::remove_dir_all(&staging);
+      return Err(format!("Failed to move previous content aside: {}", err));
+    }
+  }
+
+  if let Err(err) = fs::rename(&staging, target) {
+    if had_previous {
+      let _ = fs::rename(&backup, target);
+    }
+    let _ = fs::remove_dir_all(&staging);
+    return Err(format!("Failed to swap in new content: {}", err));
+  }
+
+  if had_previous {
+    let _ = remove_path_any(&backup);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{copy_dir_all_sync, replace_dir_sync};
+  use std::fs;
+
+  #[test]
+  fn replace_dir_swaps_content_and_leaves_no_staging_dirs() {
+    let root = std::env::temp_dir().join(format!("skill-kit-test-replace-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let source = root.join("source");
+    let target = root.join("target");
+    fs::create_dir_all(&source).unwrap();
+    fs::create_dir_all(&target).unwrap();
+    fs::write(source.join("new.md"), "new").unwrap();
+    fs::write(target.join("old.md"), "old").unwrap();
+
+    replace_dir_sync(&source, &target).unwrap();
+
+    assert!(target.join("new.md").exists());
+    assert!(!target.join("old.md").exists());
+    let leftovers: Vec<_> = fs::read_dir(&root)
+      .unwrap()
+      .filter_map(Result::ok)
+      .filter(|entry| entry.file_name().to_string_lossy().starts_with('.'))
+      .collect();
+    assert!(leftovers.is_empty());
+    let _ = fs::remove_dir_all(&root);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn copies_symlinks_as_links_without_their_targets() {
+    let root = std::env::temp_dir().join(format!("skill-kit-test-links-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let source = root.join("source");
+    let outside = root.join("outside");
+    fs::create_dir_all(&source).unwrap();
+    fs::create_dir_all(&outside).unwrap();
+    fs::write(outside.join("secret.txt"), "secret").unwrap();
+    fs::write(source.join("SKILL.md"), "skill").unwrap();
+    std::os::unix::fs::symlink(&outside, source.join("dir-link")).unwrap();
+    std::os::unix::fs::symlink(outside.join("secret.txt"), source.join("file-link")).unwrap();
+
+    let copy = root.join("copy");
+    copy_dir_all_sync(&source, &copy).unwrap();
+
+    for link in ["dir-link", "file-link"] {
+      let metadata = fs::symlink_metadata(copy.join(link)).unwrap();
+      assert!(metadata.file_type().is_symlink(), "{}", link);
+    }
+    assert_eq!(
+      fs::read_link(copy.join("dir-link")).unwrap(),
+      outside.clone()
+    );
+    assert_eq!(fs::read_to_string(copy.join("SKILL.md")).unwrap(), "skill");
+    let _ = fs::remove_dir_all(&root);
+  }
+}
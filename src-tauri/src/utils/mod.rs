@@ -2,6 +2,9 @@ pub mod file;
 pub mod folder;
 pub mod github;
 pub mod path;
+pub mod skill_adapter;
+pub mod source_provider;
 pub mod str;
 pub mod time;
+pub mod transaction;
 pub mod zip;
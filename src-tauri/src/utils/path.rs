@@ -26,6 +26,72 @@ pub fn expand_home(path: &str) -> PathBuf {
   PathBuf::from(path)
 }
 
+/// Expand `${VAR}` and `${VAR:-default}` references, then a leading `~/`. Fails when a
+/// reference cannot be parsed or names an unset variable without a default, rather than
+/// turning `${AGENT_HOME}/skills` into `/skills`.
+pub fn expand_path(path: &str) -> Result<PathBuf, String> {
+  Ok(expand_home(&expand_env_vars(path)?))
+}
+
+/// Replace `${VAR}` and `${VAR:-default}` with the variable's value. `:-` also covers a
+/// variable set to an empty value, and defaults can hold references of their own. An
+/// unset variable without a default is an error.
+pub fn expand_env_vars(value: &str) -> Result<String, String> {
+  expand_references(value, true)
+}
+
+/// Check that every `${...}` reference in `value` is well-formed, whatever variables
+/// happen to be set here.
+pub fn check_env_vars(value: &str) -> Result<(), String> {
+  expand_references(value, false).map(|_| ())
+}
+
+fn expand_references(value: &str, require_set: bool) -> Result<String, String> {
+  let mut result = String::new();
+  let mut rest = value;
+  while let Some(start) = rest.find("${") {
+    result.push_str(&rest[..start]);
+    let reference = &rest[start + 2..];
+    let end = closing_brace(reference)
+      .ok_or_else(|| format!("Unterminated variable reference in '{}'", value))?;
+    let (name, default) = match reference[..end].split_once(":-") {
+      Some((name, default)) => (name, Some(default)),
+      None => (&reference[..end], None),
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+      return Err(format!("Invalid variable name '{}' in '{}'", name, value));
+    }
+    match (std::env::var(name).ok().filter(|v| !v.is_empty()), default) {
+      (Some(variable), _) => result.push_str(&variable),
+      (None, Some(default)) => result.push_str(&expand_references(default, require_set)?),
+      (None, None) if require_set => {
+        return Err(format!(
+          "Variable '{}' in '{}' is not set and has no default",
+          name, value
+        ));
+      },
+      (None, None) => {},
+    }
+    rest = &reference[end + 1..];
+  }
+  result.push_str(rest);
+  Ok(result)
+}
+
+/// Index of the `}` closing a reference whose `${` has already been consumed.
+fn closing_brace(reference: &str) -> Option<usize> {
+  let mut depth = 0;
+  for (index, c) in reference.char_indices() {
+    match c {
+      '{' => depth += 1,
+      '}' if depth == 0 => return Some(index),
+      '}' => depth -= 1,
+      _ => {},
+    }
+  }
+  None
+}
+
 pub fn remove_path_any(path: &Path) -> Result<(), String> {
   if path.is_dir() && !path.is_symlink() {
     fs::remove_dir_all(path).map_err(|e| e.to_string())
@@ -33,3 +99,41 @@ pub fn remove_path_any(path: &Path) -> Result<(), String> {
     fs::remove_file(path).map_err(|e| e.to_string())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn expands_variables_with_defaults() {
+    std::env::set_var("SKILL_KIT_TEST_SET", "/opt/agent");
+    std::env::set_var("SKILL_KIT_TEST_EMPTY", "");
+    std::env::remove_var("SKILL_KIT_TEST_UNSET");
+
+    let expand = |value: &str| expand_env_vars(value).unwrap();
+    assert_eq!(expand("${SKILL_KIT_TEST_SET}/skills"), "/opt/agent/skills");
+    assert_eq!(
+      expand("${SKILL_KIT_TEST_SET:-~/.x}/skills"),
+      "/opt/agent/skills"
+    );
+    assert_eq!(
+      expand("${SKILL_KIT_TEST_EMPTY:-~/.x}/skills"),
+      "~/.x/skills"
+    );
+    assert_eq!(
+      expand("${SKILL_KIT_TEST_UNSET:-${SKILL_KIT_TEST_SET}/cfg}/skills"),
+      "/opt/agent/cfg/skills"
+    );
+    assert!(expand_env_vars("${SKILL_KIT_TEST_UNSET}/skills").is_err());
+    assert!(expand_path("${SKILL_KIT_TEST_UNSET}/skills").is_err());
+    assert!(check_env_vars("${SKILL_KIT_TEST_UNSET}/skills").is_ok());
+    assert!(check_env_vars("${SKILL_KIT_TEST_UNSET:-${}}/skills").is_err());
+    assert_eq!(expand("~/.claude/skills"), "~/.claude/skills");
+    assert!(expand_env_vars("${SKILL_KIT_TEST_SET/skills").is_err());
+    assert!(expand_env_vars("${}/skills").is_err());
+    assert_eq!(
+      expand_path("${SKILL_KIT_TEST_UNSET:-~/.x}/skills"),
+      Ok(expand_home("~/.x/skills"))
+    );
+  }
+}
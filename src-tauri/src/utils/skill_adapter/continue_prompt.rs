@@ -0,0 +1,27 @@
+use super::{quoted, SkillAdapter, SkillDocument};
+
+/// Continue prompt files, invoked as a slash command named after the skill.
+pub struct ContinuePromptAdapter;
+
+impl SkillAdapter for ContinuePromptAdapter {
+  fn project_dir(&self) -> &'static str {
+    ".continue/prompts"
+  }
+
+  fn global_dir(&self) -> Option<&'static str> {
+    Some(".continue/prompts")
+  }
+
+  fn file_name(&self, skill_name: &str) -> String {
+    format!("{}.prompt", skill_name)
+  }
+
+  fn render(&self, skill: &SkillDocument) -> String {
+    format!(
+      "---\nname: {}\ndescription: {}\ninvokable: true\n---\n{}",
+      quoted(&skill.name),
+      quoted(&skill.description),
+      skill.body
+    )
+  }
+}
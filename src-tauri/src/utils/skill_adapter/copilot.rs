@@ -0,0 +1,33 @@
+use super::{quoted, SkillAdapter, SkillDocument};
+
+/// GitHub Copilot instruction files, applied to files matching the skill's globs.
+pub struct CopilotInstructionsAdapter;
+
+impl SkillAdapter for CopilotInstructionsAdapter {
+  fn project_dir(&self) -> &'static str {
+    ".github/instructions"
+  }
+
+  // User instructions live in the editor's profile folder
+  fn global_dir(&self) -> Option<&'static str> {
+    None
+  }
+
+  fn file_name(&self, skill_name: &str) -> String {
+    format!("{}.instructions.md", skill_name)
+  }
+
+  fn render(&self, skill: &SkillDocument) -> String {
+    let apply_to = if skill.globs.is_empty() {
+      String::new()
+    } else {
+      format!("applyTo: {}\n", quoted(&skill.globs.join(",")))
+    };
+    format!(
+      "---\ndescription: {}\n{}---\n{}",
+      quoted(&skill.description),
+      apply_to,
+      skill.body
+    )
+  }
+}
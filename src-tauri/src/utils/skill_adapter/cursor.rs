@@ -0,0 +1,29 @@
+use super::{quoted, SkillAdapter, SkillDocument};
+
+/// Cursor project rules. Rules with globs are attached when matching files are in
+/// context; the rest are picked by the agent from their description.
+pub struct CursorRulesAdapter;
+
+impl SkillAdapter for CursorRulesAdapter {
+  fn project_dir(&self) -> &'static str {
+    ".cursor/rules"
+  }
+
+  // User rules are kept in Cursor's settings, not in files
+  fn global_dir(&self) -> Option<&'static str> {
+    None
+  }
+
+  fn file_name(&self, skill_name: &str) -> String {
+    format!("{}.mdc", skill_name)
+  }
+
+  fn render(&self, skill: &SkillDocument) -> String {
+    format!(
+      "---\ndescription: {}\nglobs: {}\nalwaysApply: false\n---\n{}",
+      quoted(&skill.description),
+      skill.globs.join(","),
+      skill.body
+    )
+  }
+}
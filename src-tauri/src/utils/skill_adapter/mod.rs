@@ -0,0 +1,255 @@
+//! Agent-native renderings of a skill.
+//!
+//! Most agents load a skill straight from its linked `SKILL.md` folder. Agents that only
+//! read their own rule or prompt files get a [`SkillAdapter`] instead, which renders the
+//! skill into a single file in that agent's format. Every rendered file carries a marker
+//! naming its skill; updates and deletes find the files again by that marker, and a
+//! hand-written file with the same name is never overwritten.
+
+mod continue_prompt;
+mod copilot;
+mod cursor;
+mod roo;
+mod windsurf;
+
+use crate::models::{InstallTarget, SkillAdapterKind};
+use crate::utils::file::FileHelper;
+use continue_prompt::ContinuePromptAdapter;
+use copilot::CopilotInstructionsAdapter;
+use cursor::CursorRulesAdapter;
+use roo::RooRulesAdapter;
+use std::fs;
+use std::path::{Path, PathBuf};
+use windsurf::WindsurfRulesAdapter;
+
+const SKILL_MD_FILE_NAME: &str = "SKILL.md";
+
+pub const ADAPTER_KINDS: [SkillAdapterKind; 5] = [
+  SkillAdapterKind::CursorRules,
+  SkillAdapterKind::WindsurfRules,
+  SkillAdapterKind::CopilotInstructions,
+  SkillAdapterKind::ContinuePrompt,
+  SkillAdapterKind::RooRules,
+];
+
+/// The parts of a `SKILL.md` that adapters render.
+pub struct SkillDocument {
+  pub name: String,
+  pub description: String,
+  /// File patterns from `globs` or `metadata.globs`, for agents that scope rules to files
+  pub globs: Vec<String>,
+  /// Markdown after the frontmatter, led by the generated marker
+  pub body: String,
+}
+
+pub trait SkillAdapter: Sync {
+  /// Folder the agent reads these files from, relative to the project root
+  fn project_dir(&self) -> &'static str;
+
+  /// Folder relative to the home folder, or `None` when the agent keeps its user-wide
+  /// rules somewhere other than one file per rule.
+  fn global_dir(&self) -> Option<&'static str>;
+
+  fn file_name(&self, skill_name: &str) -> String;
+
+  fn render(&self, skill: &SkillDocument) -> String;
+}
+
+pub fn adapter(kind: SkillAdapterKind) -> &'static dyn SkillAdapter {
+  match kind {
+    SkillAdapterKind::CursorRules => &CursorRulesAdapter,
+    SkillAdapterKind::WindsurfRules => &WindsurfRulesAdapter,
+    SkillAdapterKind::CopilotInstructions => &CopilotInstructionsAdapter,
+    SkillAdapterKind::ContinuePrompt => &ContinuePromptAdapter,
+    SkillAdapterKind::RooRules => &RooRulesAdapter,
+  }
+}
+
+/// Where `kind` writes `skill_name` for `install_target`, if it writes anything there.
+pub fn output_path(
+  kind: SkillAdapterKind,
+  skill_name: &str,
+  install_target: &InstallTarget,
+) -> Option<PathBuf> {
+  let adapter = adapter(kind);
+  let dir = match install_target {
+    InstallTarget::Global => adapter.global_dir()?,
+    InstallTarget::Project(_) => adapter.project_dir(),
+  };
+  let root = install_target.root_path().ok()?;
+  Some(root.join(dir).join(adapter.file_name(skill_name)))
+}
+
+/// Render the skill in `skill_dir` in the format of `kind`.
+pub fn render_skill(
+  kind: SkillAdapterKind,
+  skill_dir: &Path,
+  skill_name: &str,
+  install_target: &InstallTarget,
+) -> Result<String, String> {
+  let content = FileHelper::read_to_string(&skill_dir.join(SKILL_MD_FILE_NAME))?;
+  let frontmatter = FileHelper::parse_skill_frontmatter(&content)?;
+  let globs = frontmatter
+    .extra
+    .get("globs")
+    .or_else(|| frontmatter.metadata.get("globs"))
+    .map(glob_list)
+    .unwrap_or_default();
+
+  let mut body = generated_marker(skill_name);
+  body.push('\n');
+  if has_supporting_files(skill_dir) {
+    // Relative links in the skill point into its folder, not next to this file
+    body.push_str(&format!(
+      "\nSupporting files for this skill are in `{}`.\n",
+      installed_skill_dir(skill_name, install_target)
+    ));
+  }
+  body.push('\n');
+  body.push_str(strip_frontmatter(&content).trim_start());
+
+  Ok(adapter(kind).render(&SkillDocument {
+    name: skill_name.to_string(),
+    description: frontmatter.description.unwrap_or_default(),
+    globs,
+    body,
+  }))
+}
+
+/// Whether `path` was rendered from `skill_name`, as opposed to written by hand.
+pub fn is_generated_for(path: &Path, skill_name: &str) -> bool {
+  fs::read_to_string(path)
+    .map(|content| content.contains(&generated_marker(skill_name)))
+    .unwrap_or(false)
+}
+
+/// Rendered files of `skill_name` that exist for `install_target`, whichever agents
+/// they were written for.
+pub fn generated_outputs(
+  skill_name: &str,
+  install_target: &InstallTarget,
+) -> Vec<(SkillAdapterKind, PathBuf)> {
+  ADAPTER_KINDS
+    .iter()
+    .filter_map(|kind| Some((*kind, output_path(*kind, skill_name, install_target)?)))
+    .filter(|(_, path)| is_generated_for(path, skill_name))
+    .collect()
+}
+
+/// A YAML double-quoted scalar.
+fn quoted(value: &str) -> String {
+  serde_json::Value::from(value).to_string()
+}
+
+fn generated_marker(skill_name: &str) -> String {
+  format!(
+    "<!-- Generated by YouSkill from the \"{}\" skill. Edit the skill instead; this file is rewritten on update. -->",
+    skill_name
+  )
+}
+
+/// Globs given as a list or as one comma-separated string.
+fn glob_list(value: &serde_json::Value) -> Vec<String> {
+  let items: Vec<&str> = match value {
+    serde_json::Value::String(globs) => globs.split(',').collect(),
+    serde_json::Value::Array(globs) => globs.iter().filter_map(|glob| glob.as_str()).collect(),
+    _ => Vec::new(),
+  };
+  items
+    .into_iter()
+    .map(str::trim)
+    .filter(|glob| !glob.is_empty())
+    .map(str::to_string)
+    .collect()
+}
+
+fn has_supporting_files(skill_dir: &Path) -> bool {
+  fs::read_dir(skill_dir)
+    .map(|entries| {
+      entries
+        .flatten()
+        .any(|entry| entry.file_name() != SKILL_MD_FILE_NAME)
+    })
+    .unwrap_or(false)
+}
+
+/// The installed skill folder, relative to the project so rendered project files can
+/// be committed.
+fn installed_skill_dir(skill_name: &str, install_target: &InstallTarget) -> String {
+  let Ok(skill_dir) = install_target
+    .skill_folder_path()
+    .map(|dir| dir.join(skill_name))
+  else {
+    return skill_name.to_string();
+  };
+  let shown = match (install_target, install_target.root_path()) {
+    (InstallTarget::Project(_), Ok(root)) => skill_dir
+      .strip_prefix(&root)
+      .map(Path::to_path_buf)
+      .unwrap_or(skill_dir),
+    _ => skill_dir,
+  };
+  shown.to_string_lossy().replace('\\', "/")
+}
+
+fn strip_frontmatter(content: &str) -> &str {
+  let mut offset = 0;
+  for (index, line) in content.split_inclusive('\n').enumerate() {
+    offset += line.len();
+    match (index, line.trim() == "---") {
+      (0, false) => return content,
+      (0, true) => {},
+      (_, true) => return &content[offset..],
+      _ => {},
+    }
+  }
+  content
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn renders_skill_for_each_agent_format() {
+    let root = std::env::temp_dir().join(format!("skill-kit-test-adapter-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let skill_dir = root.join(".agents").join("skills").join("lint-fix");
+    fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+    fs::write(
+      skill_dir.join(SKILL_MD_FILE_NAME),
+      "---\nname: lint-fix\ndescription: Fix \"lint\" errors\nglobs: src/**/*.ts, lib/*.js\n---\n\n# Lint fix\n\nRun scripts/fix.sh.\n",
+    )
+    .unwrap();
+    let target = InstallTarget::Project(root.clone());
+    let render = |kind| render_skill(kind, &skill_dir, "lint-fix", &target).unwrap();
+
+    let cursor = render(SkillAdapterKind::CursorRules);
+    let windsurf = render(SkillAdapterKind::WindsurfRules);
+    let copilot = render(SkillAdapterKind::CopilotInstructions);
+    let continue_prompt = render(SkillAdapterKind::ContinuePrompt);
+    let cursor_path = output_path(SkillAdapterKind::CursorRules, "lint-fix", &target).unwrap();
+    fs::create_dir_all(cursor_path.parent().unwrap()).unwrap();
+    fs::write(&cursor_path, &cursor).unwrap();
+    let outputs = generated_outputs("lint-fix", &target);
+    let _ = fs::remove_dir_all(&root);
+
+    assert!(cursor.starts_with(
+      "---\ndescription: \"Fix \\\"lint\\\" errors\"\nglobs: src/**/*.ts,lib/*.js\nalwaysApply: false\n---\n"
+    ));
+    assert!(cursor.contains("are in `.agents/skills/lint-fix`"));
+    assert!(cursor.ends_with("# Lint fix\n\nRun scripts/fix.sh.\n"));
+    assert!(windsurf.contains("trigger: glob\n"));
+    assert!(copilot.contains("applyTo: \"src/**/*.ts,lib/*.js\"\n"));
+    assert!(continue_prompt.contains("invokable: true\n"));
+    assert_eq!(outputs, vec![(SkillAdapterKind::CursorRules, cursor_path)]);
+    assert_eq!(
+      output_path(
+        SkillAdapterKind::CursorRules,
+        "lint-fix",
+        &InstallTarget::Global
+      ),
+      None
+    );
+  }
+}
@@ -0,0 +1,26 @@
+use super::{SkillAdapter, SkillDocument};
+
+/// Roo Code rules. They have no frontmatter and are always loaded, so the description
+/// leads the file to tell the model when the skill applies.
+pub struct RooRulesAdapter;
+
+impl SkillAdapter for RooRulesAdapter {
+  fn project_dir(&self) -> &'static str {
+    ".roo/rules"
+  }
+
+  fn global_dir(&self) -> Option<&'static str> {
+    Some(".roo/rules")
+  }
+
+  fn file_name(&self, skill_name: &str) -> String {
+    format!("{}.md", skill_name)
+  }
+
+  fn render(&self, skill: &SkillDocument) -> String {
+    format!(
+      "# Skill: {}\n\nUse when: {}\n\n{}",
+      skill.name, skill.description, skill.body
+    )
+  }
+}
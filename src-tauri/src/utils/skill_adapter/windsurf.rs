@@ -0,0 +1,34 @@
+use super::{quoted, SkillAdapter, SkillDocument};
+
+/// Windsurf workspace rules, triggered by globs when the skill has them and by the
+/// model otherwise.
+pub struct WindsurfRulesAdapter;
+
+impl SkillAdapter for WindsurfRulesAdapter {
+  fn project_dir(&self) -> &'static str {
+    ".windsurf/rules"
+  }
+
+  // Global rules share a single global_rules.md
+  fn global_dir(&self) -> Option<&'static str> {
+    None
+  }
+
+  fn file_name(&self, skill_name: &str) -> String {
+    format!("{}.md", skill_name)
+  }
+
+  fn render(&self, skill: &SkillDocument) -> String {
+    let trigger = if skill.globs.is_empty() {
+      "trigger: model_decision\n".to_string()
+    } else {
+      format!("trigger: glob\nglobs: {}\n", skill.globs.join(","))
+    };
+    format!(
+      "---\n{}description: {}\n---\n{}",
+      trigger,
+      quoted(&skill.description),
+      skill.body
+    )
+  }
+}
@@ -0,0 +1,190 @@
+use super::{
+  api_exists, api_get, api_get_json, connection_status, encode_component, owner_repo_from_path,
+  ProviderFuture, RepoLocator, SourceProvider,
+};
+use crate::models::{ProviderKind, SourceConnectionStatus};
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+
+/// Bitbucket Cloud. Workspaces take the place of owners.
+pub struct BitbucketProvider {
+  base_url: String,
+  api_url: String,
+  token: Option<String>,
+}
+
+impl BitbucketProvider {
+  pub fn new(base_url: String, api_url: Option<String>, token: Option<String>) -> Self {
+    let api_url = api_url.unwrap_or_else(|| {
+      if base_url.ends_with("://bitbucket.org") {
+        "https://api.bitbucket.org/2.0".to_string()
+      } else {
+        format!("{}/api/2.0", base_url)
+      }
+    });
+    Self {
+      base_url,
+      api_url,
+      token,
+    }
+  }
+
+  fn repo_api(&self, repo: &RepoLocator) -> String {
+    format!("{}/repositories/{}/{}", self.api_url, repo.owner, repo.repo)
+  }
+}
+
+impl SourceProvider for BitbucketProvider {
+  fn kind(&self) -> ProviderKind {
+    ProviderKind::Bitbucket
+  }
+
+  fn base_url(&self) -> &str {
+    &self.base_url
+  }
+
+  fn has_token(&self) -> bool {
+    self.token.is_some()
+  }
+
+  /// App passwords use basic auth with the account name; access tokens are bearer tokens.
+  fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+    let Some(token) = &self.token else {
+      return request;
+    };
+    match token.split_once(':') {
+      Some((username, password)) => request.basic_auth(username, Some(password)),
+      None => request.bearer_auth(token),
+    }
+  }
+
+  fn parse_repo_path(&self, path: &str) -> Result<RepoLocator, String> {
+    owner_repo_from_path(path, "Bitbucket")
+  }
+
+  fn archive_url(&self, repo: &RepoLocator, archive_ref: &str) -> String {
+    format!(
+      "{}/{}/{}/get/{}.zip",
+      self.base_url, repo.owner, repo.repo, archive_ref
+    )
+  }
+
+  fn default_branch<'a>(&'a self, repo: &'a RepoLocator) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      let info: RepoInfo = api_get_json(self, &self.repo_api(repo)).await?;
+      Ok(info.mainbranch.name)
+    })
+  }
+
+  fn resolve_commit<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+  ) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      let url = format!(
+        "{}/commit/{}",
+        self.repo_api(repo),
+        encode_component(git_ref)
+      );
+      let commit: CommitInfo = api_get_json(self, &url).await?;
+      Ok(commit.hash)
+    })
+  }
+
+  fn branch_exists<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    branch: &'a str,
+  ) -> ProviderFuture<'a, bool> {
+    Box::pin(async move {
+      let url = format!(
+        "{}/refs/branches/{}",
+        self.repo_api(repo),
+        encode_component(branch)
+      );
+      api_exists(self, &url, "application/json").await
+    })
+  }
+
+  fn folder_hash<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+    folder: &'a str,
+  ) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      if folder.is_empty() {
+        return self.resolve_commit(repo, git_ref).await;
+      }
+
+      // Bitbucket does not expose tree object ids, so the newest commit touching the
+      // folder serves as its hash.
+      let url = format!(
+        "{}/commits/{}?path={}&pagelen=1",
+        self.repo_api(repo),
+        encode_component(git_ref),
+        encode_component(folder)
+      );
+      let page: CommitPage = api_get_json(self, &url).await?;
+      page
+        .values
+        .into_iter()
+        .next()
+        .map(|commit| commit.hash)
+        .ok_or_else(|| format!("Skill folder not found in Bitbucket repository: {}", folder))
+    })
+  }
+
+  fn check_connection(&self) -> ProviderFuture<'_, SourceConnectionStatus> {
+    Box::pin(async move {
+      if self.token.is_none() {
+        let url = format!("{}/repositories?pagelen=1", self.api_url);
+        let resp = api_get(self, &url, "application/json").await?;
+        return Ok(connection_status(self, resp.headers(), None, None));
+      }
+      // Repository and workspace access tokens are not tied to an account, so
+      // `/user` rejects them; their scopes still come back in the headers.
+      let resp = api_get(self, &format!("{}/user", self.api_url), "application/json").await;
+      let resp = match resp {
+        Ok(resp) => resp,
+        Err(_) => {
+          let url = format!("{}/repositories?role=member&pagelen=1", self.api_url);
+          let resp = api_get(self, &url, "application/json").await?;
+          return Ok(connection_status(self, resp.headers(), None, None));
+        },
+      };
+      let headers = resp.headers().clone();
+      let user: UserInfo = resp
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Bitbucket user response: {}", e))?;
+      Ok(connection_status(self, &headers, user.username, None))
+    })
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoInfo {
+  mainbranch: BranchInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchInfo {
+  name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitInfo {
+  hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitPage {
+  values: Vec<CommitInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfo {
+  username: Option<String>,
+}
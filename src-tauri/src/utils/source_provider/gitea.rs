@@ -0,0 +1,165 @@
+use super::{
+  api_exists, api_get, api_get_json, connection_status, encode_component, owner_repo_from_path,
+  ProviderFuture, RepoLocator, SourceProvider,
+};
+use crate::models::{ProviderKind, SourceConnectionStatus};
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+
+/// A Gitea or Forgejo instance, such as codeberg.org.
+pub struct GiteaProvider {
+  base_url: String,
+  api_url: String,
+  token: Option<String>,
+}
+
+impl GiteaProvider {
+  pub fn new(base_url: String, api_url: Option<String>, token: Option<String>) -> Self {
+    let api_url = api_url.unwrap_or_else(|| format!("{}/api/v1", base_url));
+    Self {
+      base_url,
+      api_url,
+      token,
+    }
+  }
+
+  fn repo_api(&self, repo: &RepoLocator) -> String {
+    format!("{}/repos/{}/{}", self.api_url, repo.owner, repo.repo)
+  }
+}
+
+impl SourceProvider for GiteaProvider {
+  fn kind(&self) -> ProviderKind {
+    ProviderKind::Gitea
+  }
+
+  fn base_url(&self) -> &str {
+    &self.base_url
+  }
+
+  fn has_token(&self) -> bool {
+    self.token.is_some()
+  }
+
+  fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+    match &self.token {
+      Some(token) => request.header("Authorization", format!("token {}", token)),
+      None => request,
+    }
+  }
+
+  fn parse_repo_path(&self, path: &str) -> Result<RepoLocator, String> {
+    owner_repo_from_path(path, "Gitea")
+  }
+
+  fn archive_url(&self, repo: &RepoLocator, archive_ref: &str) -> String {
+    format!("{}/archive/{}.zip", self.repo_api(repo), archive_ref)
+  }
+
+  fn default_branch<'a>(&'a self, repo: &'a RepoLocator) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      let info: RepoInfo = api_get_json(self, &self.repo_api(repo)).await?;
+      Ok(info.default_branch)
+    })
+  }
+
+  fn resolve_commit<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+  ) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      let url = format!(
+        "{}/commits?sha={}&limit=1&stat=false&verification=false&files=false",
+        self.repo_api(repo),
+        encode_component(git_ref)
+      );
+      let commits: Vec<CommitInfo> = api_get_json(self, &url).await?;
+      commits
+        .into_iter()
+        .next()
+        .map(|commit| commit.sha)
+        .ok_or_else(|| format!("No commits found for '{}'", git_ref))
+    })
+  }
+
+  fn branch_exists<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    branch: &'a str,
+  ) -> ProviderFuture<'a, bool> {
+    Box::pin(async move {
+      let url = format!("{}/branches/{}", self.repo_api(repo), branch);
+      api_exists(self, &url, "application/json").await
+    })
+  }
+
+  fn folder_hash<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+    folder: &'a str,
+  ) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      // Directory listings carry each child's tree SHA but not their own, so the
+      // root falls back to the commit SHA.
+      if folder.is_empty() {
+        return self.resolve_commit(repo, git_ref).await;
+      }
+
+      let parent = folder.rsplit_once('/').map(|(p, _)| p).unwrap_or("");
+      let url = format!(
+        "{}/contents/{}?ref={}",
+        self.repo_api(repo),
+        parent,
+        encode_component(git_ref)
+      );
+      let entries: Vec<ContentEntry> = api_get_json(self, &url).await?;
+      entries
+        .into_iter()
+        .find(|entry| entry.kind == "dir" && entry.path == folder)
+        .map(|entry| entry.sha)
+        .ok_or_else(|| format!("Skill folder not found in Gitea repository: {}", folder))
+    })
+  }
+
+  fn check_connection(&self) -> ProviderFuture<'_, SourceConnectionStatus> {
+    Box::pin(async move {
+      if self.token.is_none() {
+        let url = format!("{}/version", self.api_url);
+        let resp = api_get(self, &url, "application/json").await?;
+        return Ok(connection_status(self, resp.headers(), None, None));
+      }
+      let resp = api_get(self, &format!("{}/user", self.api_url), "application/json").await?;
+      let headers = resp.headers().clone();
+      let user: UserInfo = resp
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Gitea user response: {}", e))?;
+      Ok(connection_status(self, &headers, Some(user.login), None))
+    })
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoInfo {
+  default_branch: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitInfo {
+  sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentEntry {
+  path: String,
+  sha: String,
+  #[serde(rename = "type")]
+  kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfo {
+  login: String,
+}
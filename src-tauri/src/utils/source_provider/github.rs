@@ -0,0 +1,182 @@
+use super::{
+  api_exists, api_get, connection_status, owner_repo_from_path, ProviderFuture, RepoLocator,
+  SourceProvider,
+};
+use crate::models::{ProviderKind, SourceConnectionStatus};
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+
+const GITHUB_JSON: &str = "application/vnd.github+json";
+
+/// github.com, or a GitHub Enterprise Server when `base_url` points elsewhere.
+pub struct GithubProvider {
+  base_url: String,
+  api_url: String,
+  token: Option<String>,
+}
+
+impl GithubProvider {
+  pub fn new(base_url: String, api_url: Option<String>, token: Option<String>) -> Self {
+    let api_url = api_url.unwrap_or_else(|| {
+      if base_url.ends_with("://github.com") {
+        "https://api.github.com".to_string()
+      } else {
+        format!("{}/api/v3", base_url)
+      }
+    });
+    Self {
+      base_url,
+      api_url,
+      token,
+    }
+  }
+
+  fn repo_api(&self, repo: &RepoLocator) -> String {
+    format!("{}/repos/{}/{}", self.api_url, repo.owner, repo.repo)
+  }
+}
+
+impl SourceProvider for GithubProvider {
+  fn kind(&self) -> ProviderKind {
+    ProviderKind::Github
+  }
+
+  fn base_url(&self) -> &str {
+    &self.base_url
+  }
+
+  fn has_token(&self) -> bool {
+    self.token.is_some()
+  }
+
+  fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+    match &self.token {
+      Some(token) => request.bearer_auth(token),
+      None => request,
+    }
+  }
+
+  fn parse_repo_path(&self, path: &str) -> Result<RepoLocator, String> {
+    owner_repo_from_path(path, "GitHub")
+  }
+
+  /// Web archive links do not accept tokens, so authenticated downloads go through
+  /// the API zipball endpoint instead.
+  fn archive_url(&self, repo: &RepoLocator, archive_ref: &str) -> String {
+    if self.token.is_some() {
+      return format!("{}/zipball/{}", self.repo_api(repo), archive_ref);
+    }
+    format!(
+      "{}/{}/{}/archive/{}.zip",
+      self.base_url, repo.owner, repo.repo, archive_ref
+    )
+  }
+
+  fn default_branch<'a>(&'a self, repo: &'a RepoLocator) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      let info: RepoInfo = api_get(self, &self.repo_api(repo), GITHUB_JSON)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub repository response: {}", e))?;
+      Ok(info.default_branch)
+    })
+  }
+
+  fn resolve_commit<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+  ) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      let url = format!("{}/commits/{}", self.repo_api(repo), git_ref);
+      let sha = api_get(self, &url, "application/vnd.github.sha")
+        .await?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read commit SHA: {}", e))?;
+      Ok(sha.trim().to_string())
+    })
+  }
+
+  fn branch_exists<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    branch: &'a str,
+  ) -> ProviderFuture<'a, bool> {
+    Box::pin(async move {
+      let url = format!("{}/branches/{}", self.repo_api(repo), branch);
+      api_exists(self, &url, GITHUB_JSON).await
+    })
+  }
+
+  fn folder_hash<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+    folder: &'a str,
+  ) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      let url = format!("{}/git/trees/{}?recursive=1", self.repo_api(repo), git_ref);
+      let tree: GitTreeResponse = api_get(self, &url, GITHUB_JSON)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub tree response: {}", e))?;
+
+      if folder.is_empty() {
+        return Ok(tree.sha);
+      }
+
+      let entry = tree
+        .tree
+        .into_iter()
+        .find(|item| item.kind == "tree" && item.path == folder)
+        .ok_or(format!("Skill folder not found in GitHub tree: {}", folder))?;
+
+      Ok(entry.sha)
+    })
+  }
+
+  fn check_connection(&self) -> ProviderFuture<'_, SourceConnectionStatus> {
+    Box::pin(async move {
+      if self.token.is_none() {
+        let url = format!("{}/rate_limit", self.api_url);
+        let resp = api_get(self, &url, GITHUB_JSON).await?;
+        return Ok(connection_status(self, resp.headers(), None, None));
+      }
+      let resp = api_get(self, &format!("{}/user", self.api_url), GITHUB_JSON).await?;
+      let headers = resp.headers().clone();
+      let user: UserInfo = resp
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub user response: {}", e))?;
+      // Fine-grained tokens carry no X-OAuth-Scopes header, which leaves scopes empty.
+      Ok(connection_status(self, &headers, Some(user.login), None))
+    })
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoInfo {
+  default_branch: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitTreeResponse {
+  sha: String,
+  tree: Vec<GitTreeItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitTreeItem {
+  path: String,
+  #[serde(rename = "type")]
+  kind: String,
+  sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfo {
+  login: String,
+}
@@ -0,0 +1,220 @@
+use super::{
+  api_exists, api_get, api_get_json, connection_status, encode_component, path_segments,
+  ProviderFuture, RepoLocator, SourceProvider,
+};
+use crate::models::{ProviderKind, SourceConnectionStatus};
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+
+/// Upper bound on tree pages scanned when looking for a skill folder.
+const MAX_TREE_PAGES: usize = 20;
+
+/// gitlab.com or a self-managed GitLab instance.
+pub struct GitlabProvider {
+  base_url: String,
+  api_url: String,
+  token: Option<String>,
+}
+
+impl GitlabProvider {
+  pub fn new(base_url: String, api_url: Option<String>, token: Option<String>) -> Self {
+    let api_url = api_url.unwrap_or_else(|| format!("{}/api/v4", base_url));
+    Self {
+      base_url,
+      api_url,
+      token,
+    }
+  }
+
+  /// Projects are addressed by their URL-encoded full path, which avoids an id lookup.
+  fn project_api(&self, repo: &RepoLocator) -> String {
+    format!(
+      "{}/projects/{}",
+      self.api_url,
+      encode_component(&repo.full_name())
+    )
+  }
+}
+
+impl SourceProvider for GitlabProvider {
+  fn kind(&self) -> ProviderKind {
+    ProviderKind::Gitlab
+  }
+
+  fn base_url(&self) -> &str {
+    &self.base_url
+  }
+
+  fn has_token(&self) -> bool {
+    self.token.is_some()
+  }
+
+  fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+    match &self.token {
+      Some(token) => request.header("PRIVATE-TOKEN", token),
+      None => request,
+    }
+  }
+
+  fn parse_repo_path(&self, path: &str) -> Result<RepoLocator, String> {
+    // Everything before the `/-/` route separator is the project path, which may
+    // include any number of subgroups.
+    let segments: Vec<&str> = path_segments(path)
+      .into_iter()
+      .take_while(|segment| *segment != "-")
+      .collect();
+    let Some((repo, owner)) = segments.split_last() else {
+      return Err("Invalid GitLab URL format. Use https://host/group/project".to_string());
+    };
+    if owner.is_empty() {
+      return Err("Invalid GitLab URL format. Use https://host/group/project".to_string());
+    }
+    Ok(RepoLocator {
+      owner: owner.join("/"),
+      repo: repo.to_string(),
+    })
+  }
+
+  fn archive_url(&self, repo: &RepoLocator, archive_ref: &str) -> String {
+    format!(
+      "{}/repository/archive.zip?sha={}",
+      self.project_api(repo),
+      encode_component(archive_ref)
+    )
+  }
+
+  fn default_branch<'a>(&'a self, repo: &'a RepoLocator) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      let project: ProjectInfo = api_get_json(self, &self.project_api(repo)).await?;
+      project
+        .default_branch
+        .ok_or_else(|| format!("GitLab project {} has no default branch", repo.full_name()))
+    })
+  }
+
+  fn resolve_commit<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+  ) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      let url = format!(
+        "{}/repository/commits/{}",
+        self.project_api(repo),
+        encode_component(git_ref)
+      );
+      let commit: CommitInfo = api_get_json(self, &url).await?;
+      Ok(commit.id)
+    })
+  }
+
+  fn branch_exists<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    branch: &'a str,
+  ) -> ProviderFuture<'a, bool> {
+    Box::pin(async move {
+      let url = format!(
+        "{}/repository/branches/{}",
+        self.project_api(repo),
+        encode_component(branch)
+      );
+      api_exists(self, &url, "application/json").await
+    })
+  }
+
+  fn folder_hash<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+    folder: &'a str,
+  ) -> ProviderFuture<'a, String> {
+    Box::pin(async move {
+      // The tree API lists children only, so the root has no id of its own; the
+      // commit SHA stands in for it.
+      if folder.is_empty() {
+        return self.resolve_commit(repo, git_ref).await;
+      }
+
+      let parent = folder.rsplit_once('/').map(|(p, _)| p).unwrap_or("");
+      for page in 1..=MAX_TREE_PAGES {
+        let url = format!(
+          "{}/repository/tree?ref={}&path={}&per_page=100&page={}",
+          self.project_api(repo),
+          encode_component(git_ref),
+          encode_component(parent),
+          page
+        );
+        let items: Vec<TreeItem> = api_get_json(self, &url).await?;
+        if items.is_empty() {
+          break;
+        }
+        if let Some(item) = items
+          .into_iter()
+          .find(|item| item.kind == "tree" && item.path == folder)
+        {
+          return Ok(item.id);
+        }
+      }
+
+      Err(format!("Skill folder not found in GitLab tree: {}", folder))
+    })
+  }
+
+  fn check_connection(&self) -> ProviderFuture<'_, SourceConnectionStatus> {
+    Box::pin(async move {
+      if self.token.is_none() {
+        let url = format!("{}/projects?per_page=1", self.api_url);
+        let resp = api_get(self, &url, "application/json").await?;
+        return Ok(connection_status(self, resp.headers(), None, None));
+      }
+      let resp = api_get(self, &format!("{}/user", self.api_url), "application/json").await?;
+      let headers = resp.headers().clone();
+      let user: UserInfo = resp
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitLab user response: {}", e))?;
+      // Only available for personal, group and project access tokens on GitLab 15.5+.
+      let token: Option<TokenInfo> = api_get_json(
+        self,
+        &format!("{}/personal_access_tokens/self", self.api_url),
+      )
+      .await
+      .ok();
+      Ok(connection_status(
+        self,
+        &headers,
+        Some(user.username),
+        token.map(|token| token.scopes),
+      ))
+    })
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectInfo {
+  default_branch: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitInfo {
+  id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TreeItem {
+  id: String,
+  path: String,
+  #[serde(rename = "type")]
+  kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfo {
+  username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenInfo {
+  scopes: Vec<String>,
+}
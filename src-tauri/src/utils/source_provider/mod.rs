@@ -0,0 +1,630 @@
+//! Git hosts that remote skills can be downloaded from.
+//!
+//! Each host implements [`SourceProvider`]; [`RemoteSource`] picks the provider for a
+//! repository URL and layers ref resolution, archive download and skill hashing on top.
+
+mod bitbucket;
+mod gitea;
+mod github;
+mod gitlab;
+
+use crate::config::{load_config, SourceHostConfig};
+use crate::models::{
+  GitRefKind, ProviderKind, RateLimitStatus, SourceConnectionStatus, SourceRef, SourceType,
+};
+use crate::utils::zip::ZipHelper;
+use bitbucket::BitbucketProvider;
+use gitea::GiteaProvider;
+use github::GithubProvider;
+use gitlab::GitlabProvider;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use std::future::Future;
+use std::io::Cursor;
+use std::path::Path;
+use std::pin::Pin;
+
+pub type ProviderFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, String>> + Send + 'a>>;
+
+/// A repository on a git host. GitLab subgroups are kept in `owner`, joined with `/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoLocator {
+  pub owner: String,
+  pub repo: String,
+}
+
+impl RepoLocator {
+  pub fn full_name(&self) -> String {
+    format!("{}/{}", self.owner, self.repo)
+  }
+
+  /// Name safe to use as a single path component.
+  pub fn slug(&self) -> String {
+    self.full_name().replace('/', "-")
+  }
+}
+
+pub trait SourceProvider: Send + Sync {
+  fn kind(&self) -> ProviderKind;
+
+  /// Web base URL of the host, e.g. `https://gitlab.com`.
+  fn base_url(&self) -> &str;
+
+  fn has_token(&self) -> bool;
+
+  /// Attach the host's credentials, if any, to an API or archive request.
+  fn authorize(&self, request: RequestBuilder) -> RequestBuilder;
+
+  /// Parse the part of a repository URL that follows the host.
+  fn parse_repo_path(&self, path: &str) -> Result<RepoLocator, String>;
+
+  /// ZIP archive of the repository at a branch, tag or commit.
+  fn archive_url(&self, repo: &RepoLocator, archive_ref: &str) -> String;
+
+  fn default_branch<'a>(&'a self, repo: &'a RepoLocator) -> ProviderFuture<'a, String>;
+
+  /// Full SHA of the commit a branch, tag or commit prefix points at.
+  fn resolve_commit<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+  ) -> ProviderFuture<'a, String>;
+
+  /// Whether `branch` is a branch. Fails when the host cannot be asked, so callers
+  /// do not mistake an outage for "not a branch".
+  fn branch_exists<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    branch: &'a str,
+  ) -> ProviderFuture<'a, bool>;
+
+  /// Hash that changes whenever anything under `folder` changes at `git_ref`.
+  /// An empty `folder` means the repository root.
+  fn folder_hash<'a>(
+    &'a self,
+    repo: &'a RepoLocator,
+    git_ref: &'a str,
+    folder: &'a str,
+  ) -> ProviderFuture<'a, String>;
+
+  /// Make an authenticated request and report who the token belongs to, its scopes
+  /// and the remaining rate limit.
+  fn check_connection(&self) -> ProviderFuture<'_, SourceConnectionStatus>;
+}
+
+/// A repository together with the provider that hosts it.
+pub struct RemoteSource {
+  provider: Box<dyn SourceProvider>,
+  pub repo: RepoLocator,
+}
+
+impl RemoteSource {
+  /// Parse a repository URL on any configured host, or `owner/repo` for GitHub.
+  pub fn parse(url: &str) -> Result<Self, String> {
+    Self::parse_as(url, None)
+  }
+
+  /// Like [`RemoteSource::parse`], but `owner/repo` shorthands resolve to the public
+  /// host of `kind`. Used for sources read back from lock files.
+  pub fn parse_as(url: &str, kind: Option<ProviderKind>) -> Result<Self, String> {
+    let (host, path) = split_host(url)?;
+    let host = host.unwrap_or_else(|| host_of(public_base_url(kind.unwrap_or_default())));
+    let provider = configured_providers()
+      .into_iter()
+      .find(|provider| host_of(provider.base_url()) == host)
+      .ok_or_else(|| {
+        format!(
+          "Unsupported git host '{}'. Add it to source_hosts in the settings",
+          host
+        )
+      })?;
+
+    let repo = provider.parse_repo_path(&path)?;
+    Ok(Self { provider, repo })
+  }
+
+  pub fn kind(&self) -> ProviderKind {
+    self.provider.kind()
+  }
+
+  pub fn source_type(&self) -> SourceType {
+    self.kind().into()
+  }
+
+  pub fn web_url(&self) -> String {
+    format!("{}/{}", self.provider.base_url(), self.repo.full_name())
+  }
+
+  /// Value recorded as `source` in lock files: `owner/repo` for github.com, matching
+  /// `skills add`, and the repository URL for every other host.
+  pub fn source_id(&self) -> String {
+    if self.kind() == ProviderKind::Github && host_of(self.provider.base_url()) == "github.com" {
+      self.repo.full_name()
+    } else {
+      self.web_url()
+    }
+  }
+
+  pub async fn default_branch(&self) -> Result<String, String> {
+    self.provider.default_branch(&self.repo).await
+  }
+
+  /// Resolve a branch, tag or commit SHA (or the default branch when `git_ref` is
+  /// `None`) to the commit it currently points at.
+  pub async fn resolve_ref(&self, git_ref: Option<&str>) -> Result<SourceRef, String> {
+    let git_ref = git_ref.map(str::trim).filter(|r| !r.is_empty());
+    let ref_name = match git_ref {
+      Some(git_ref) => git_ref.to_string(),
+      None => self.default_branch().await?,
+    };
+
+    let commit = self
+      .provider
+      .resolve_commit(&self.repo, &ref_name)
+      .await
+      .map_err(|e| format!("Unknown ref '{}': {}", ref_name, e))?;
+
+    // An unknown kind keeps the skill updatable; guessing "tag" would pin it
+    let ref_kind = if git_ref.is_none() {
+      Some(GitRefKind::Branch)
+    } else {
+      match self.provider.branch_exists(&self.repo, &ref_name).await {
+        Ok(true) => Some(GitRefKind::Branch),
+        Ok(false) if is_commit_sha_prefix(&ref_name, &commit) => Some(GitRefKind::Commit),
+        Ok(false) => Some(GitRefKind::Tag),
+        Err(err) => {
+          tracing::warn!("Failed to look up branch '{}': {}", ref_name, err);
+          None
+        },
+      }
+    };
+
+    Ok(SourceRef {
+      git_ref: git_ref.map(str::to_string),
+      ref_kind,
+      resolved_commit: Some(commit),
+    })
+  }
+
+  /// Download the repository archive and extract it into `dest`.
+  ///
+  /// The ref is resolved to a commit first so the archive matches what gets
+  /// recorded in the lock file. When the API is unavailable (for example when
+  /// rate limited) the archive is fetched by name instead.
+  pub async fn download_to(&self, git_ref: Option<&str>, dest: &Path) -> Result<SourceRef, String> {
+    let git_ref = git_ref.map(str::trim).filter(|r| !r.is_empty());
+    let resolved = match self.resolve_ref(git_ref).await {
+      Ok(resolved) => resolved,
+      Err(err) => {
+        tracing::warn!("Failed to resolve ref for {}: {}", self.web_url(), err);
+        SourceRef {
+          git_ref: git_ref.map(str::to_string),
+          ..SourceRef::default()
+        }
+      },
+    };
+
+    // Try the resolved commit, then the ref by name, then main -> master
+    let mut candidates = Vec::new();
+    if let Some(commit) = &resolved.resolved_commit {
+      candidates.push(commit.clone());
+    }
+    match git_ref {
+      Some(git_ref) => candidates.push(git_ref.to_string()),
+      None => {
+        candidates.push("main".to_string());
+        candidates.push("master".to_string());
+      },
+    }
+
+    let mut last_error = String::new();
+    for archive_ref in candidates {
+      let url = self.provider.archive_url(&self.repo, &archive_ref);
+      match download_and_extract(self.provider.as_ref(), &url, dest).await {
+        Ok(()) => return Ok(resolved),
+        Err(e) => {
+          last_error = e;
+          // Clean up destination if it was partially created
+          if dest.exists() {
+            let _ = std::fs::remove_dir_all(dest);
+          }
+        },
+      }
+    }
+
+    Err(format!(
+      "Failed to download repository {}: {}",
+      self.web_url(),
+      last_error
+    ))
+  }
+
+  /// Hash of the folder holding `skill_path` at `git_ref`, or at the default branch
+  /// when `None`.
+  pub async fn skill_folder_hash(
+    &self,
+    skill_path: &str,
+    git_ref: Option<&str>,
+  ) -> Result<String, String> {
+    let normalized = skill_path.trim_start_matches("./");
+    let skill_folder = if normalized == "SKILL.md" {
+      ""
+    } else if let Some(folder) = normalized.strip_suffix("/SKILL.md") {
+      folder
+    } else {
+      return Err(format!("Invalid skill_path: {}", skill_path));
+    };
+
+    let tree_ref = match git_ref.map(str::trim).filter(|r| !r.is_empty()) {
+      Some(git_ref) => git_ref.to_string(),
+      None => self
+        .default_branch()
+        .await
+        .unwrap_or_else(|_| "main".to_string()),
+    };
+
+    self
+      .provider
+      .folder_hash(&self.repo, &tree_ref, skill_folder)
+      .await
+  }
+}
+
+/// Probe a host with the given settings, which do not need to be saved yet.
+pub async fn test_connection(host: &SourceHostConfig) -> Result<SourceConnectionStatus, String> {
+  provider_for_host(host).check_connection().await
+}
+
+/// The user's self-hosted servers first, then the public hosts. The first provider with
+/// a matching host wins, so a configured entry can override a public host's API URL.
+fn configured_providers() -> Vec<Box<dyn SourceProvider>> {
+  let custom_hosts = load_config()
+    .map(|config| config.source_hosts)
+    .unwrap_or_default();
+  let public_hosts = [
+    ProviderKind::Github,
+    ProviderKind::Gitlab,
+    ProviderKind::Gitea,
+    ProviderKind::Bitbucket,
+  ]
+  .into_iter()
+  .map(|kind| SourceHostConfig {
+    kind,
+    base_url: public_base_url(kind).to_string(),
+    api_url: None,
+    token: None,
+  });
+
+  custom_hosts
+    .into_iter()
+    .filter(|host| !host.base_url.trim().is_empty())
+    .chain(public_hosts)
+    .map(|host| provider_for_host(&host))
+    .collect()
+}
+
+fn public_base_url(kind: ProviderKind) -> &'static str {
+  match kind {
+    ProviderKind::Github => "https://github.com",
+    ProviderKind::Gitlab => "https://gitlab.com",
+    ProviderKind::Gitea => "https://codeberg.org",
+    ProviderKind::Bitbucket => "https://bitbucket.org",
+  }
+}
+
+fn provider_for_host(host: &SourceHostConfig) -> Box<dyn SourceProvider> {
+  let base_url = host.base_url.trim().trim_end_matches('/').to_string();
+  let api_url = host
+    .api_url
+    .as_deref()
+    .map(|url| url.trim().trim_end_matches('/').to_string())
+    .filter(|url| !url.is_empty());
+  let token = host
+    .token
+    .as_deref()
+    .map(str::trim)
+    .filter(|token| !token.is_empty())
+    .map(str::to_string);
+  match host.kind {
+    ProviderKind::Github => Box::new(GithubProvider::new(base_url, api_url, token)),
+    ProviderKind::Gitlab => Box::new(GitlabProvider::new(base_url, api_url, token)),
+    ProviderKind::Gitea => Box::new(GiteaProvider::new(base_url, api_url, token)),
+    ProviderKind::Bitbucket => Box::new(BitbucketProvider::new(base_url, api_url, token)),
+  }
+}
+
+/// Split a repository reference into its lowercase host (if any) and repository path.
+/// Accepts `https://host/path`, `git@host:path`, `host/path` and bare `owner/repo`.
+fn split_host(url: &str) -> Result<(Option<String>, String), String> {
+  let url = url.trim().trim_end_matches('/');
+  let url = url.strip_suffix(".git").unwrap_or(url);
+  if url.is_empty() {
+    return Err("Repository URL is required".to_string());
+  }
+
+  let (host, path) = if let Some(rest) = url.split_once("://").map(|(_, rest)| rest) {
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    (Some(host), path)
+  } else if let Some((host, path)) = url
+    .strip_prefix("git@")
+    .and_then(|rest| rest.split_once(':'))
+  {
+    (Some(host), path)
+  } else {
+    match url.split_once('/') {
+      Some((first, rest)) if first.contains('.') => (Some(first), rest),
+      _ => (None, url),
+    }
+  };
+
+  let host = host.map(|host| {
+    let host = host.rsplit('@').next().unwrap_or(host).to_ascii_lowercase();
+    host
+      .strip_prefix("www.")
+      .map(str::to_string)
+      .unwrap_or(host)
+  });
+  Ok((host, path.trim_matches('/').to_string()))
+}
+
+fn host_of(base_url: &str) -> String {
+  split_host(base_url)
+    .ok()
+    .and_then(|(host, _)| host)
+    .unwrap_or_default()
+}
+
+/// Path segments of a repository URL, dropping empty ones.
+fn path_segments(path: &str) -> Vec<&str> {
+  path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// `owner/repo` from the first two path segments, as used by GitHub, Gitea and Bitbucket.
+fn owner_repo_from_path(path: &str, host_name: &str) -> Result<RepoLocator, String> {
+  let segments = path_segments(path);
+  if segments.len() < 2 {
+    return Err(format!(
+      "Invalid {} URL format. Use https://host/owner/repo",
+      host_name
+    ));
+  }
+  Ok(RepoLocator {
+    owner: segments[0].to_string(),
+    repo: segments[1].to_string(),
+  })
+}
+
+/// Percent-encode a single URL path segment or query value.
+fn encode_component(value: &str) -> String {
+  let mut encoded = String::with_capacity(value.len());
+  for byte in value.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+        encoded.push(byte as char)
+      },
+      _ => encoded.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  encoded
+}
+
+fn is_commit_sha_prefix(git_ref: &str, commit: &str) -> bool {
+  git_ref.len() >= 7
+    && git_ref.chars().all(|c| c.is_ascii_hexdigit())
+    && commit.starts_with(&git_ref.to_ascii_lowercase())
+}
+
+async fn api_get(
+  provider: &dyn SourceProvider,
+  url: &str,
+  accept: &str,
+) -> Result<reqwest::Response, String> {
+  let resp = api_send(provider, url, accept).await?;
+  check_status(provider, resp.status(), "API")?;
+  Ok(resp)
+}
+
+/// `true` when `url` exists, `false` when the host answers 404, and an error for
+/// anything else (network failures, rate limits, rejected tokens).
+async fn api_exists(
+  provider: &dyn SourceProvider,
+  url: &str,
+  accept: &str,
+) -> Result<bool, String> {
+  let resp = api_send(provider, url, accept).await?;
+  if resp.status() == StatusCode::NOT_FOUND {
+    return Ok(false);
+  }
+  check_status(provider, resp.status(), "API")?;
+  Ok(true)
+}
+
+async fn api_send(
+  provider: &dyn SourceProvider,
+  url: &str,
+  accept: &str,
+) -> Result<reqwest::Response, String> {
+  let client = Client::builder()
+    .timeout(std::time::Duration::from_secs(20))
+    .build()
+    .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+  let request = client
+    .get(url)
+    .header("User-Agent", "you-skill")
+    .header("Accept", accept);
+  provider
+    .authorize(request)
+    .send()
+    .await
+    .map_err(|e| format!("Failed to request {}: {}", url, e))
+}
+
+async fn api_get_json<T: serde::de::DeserializeOwned>(
+  provider: &dyn SourceProvider,
+  url: &str,
+) -> Result<T, String> {
+  api_get(provider, url, "application/json")
+    .await?
+    .json()
+    .await
+    .map_err(|e| format!("Failed to parse API response: {}", e))
+}
+
+/// Hosts answer 404 rather than 401 for private repositories, so point at the token
+/// whenever access could be the problem.
+fn check_status(
+  provider: &dyn SourceProvider,
+  status: StatusCode,
+  what: &str,
+) -> Result<(), String> {
+  if status.is_success() {
+    return Ok(());
+  }
+  let hint = match (status.as_u16(), provider.has_token()) {
+    (401 | 403, true) => " (check the access token for this host)",
+    (401 | 403 | 404, false) => " (private repositories need an access token for this host)",
+    _ => "",
+  };
+  Err(format!("{} returned status {}{}", what, status, hint))
+}
+
+/// Build a connection report from the headers of an authenticated request.
+fn connection_status(
+  provider: &dyn SourceProvider,
+  headers: &HeaderMap,
+  user: Option<String>,
+  scopes: Option<Vec<String>>,
+) -> SourceConnectionStatus {
+  let scopes = scopes.unwrap_or_else(|| {
+    header_str(headers, &["x-oauth-scopes"])
+      .map(|value| {
+        value
+          .split(',')
+          .map(str::trim)
+          .filter(|scope| !scope.is_empty())
+          .map(str::to_string)
+          .collect()
+      })
+      .unwrap_or_default()
+  });
+  SourceConnectionStatus {
+    kind: provider.kind(),
+    base_url: provider.base_url().to_string(),
+    authenticated: provider.has_token(),
+    user,
+    scopes,
+    rate_limit: rate_limit_from_headers(headers),
+  }
+}
+
+/// GitHub, Gitea and Bitbucket send `X-RateLimit-*` headers; GitLab drops the `X-`.
+fn rate_limit_from_headers(headers: &HeaderMap) -> Option<RateLimitStatus> {
+  let number = |names: &[&str]| header_str(headers, names)?.trim().parse::<i64>().ok();
+  let limit = number(&["x-ratelimit-limit", "ratelimit-limit"])?;
+  let reset_at = number(&["x-ratelimit-reset", "ratelimit-reset"])
+    .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+    .map(|time| time.to_rfc3339());
+  Some(RateLimitStatus {
+    limit,
+    remaining: number(&["x-ratelimit-remaining", "ratelimit-remaining"]),
+    reset_at,
+  })
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, names: &[&str]) -> Option<&'a str> {
+  names
+    .iter()
+    .find_map(|name| headers.get(*name)?.to_str().ok())
+}
+
+/// Download a repository ZIP archive and extract it without requiring git.
+async fn download_and_extract(
+  provider: &dyn SourceProvider,
+  url: &str,
+  dest: &Path,
+) -> Result<(), String> {
+  let client = Client::builder()
+    .timeout(std::time::Duration::from_secs(60))
+    .build()
+    .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+  let request = client.get(url).header("User-Agent", "you-skill");
+  let response = provider
+    .authorize(request)
+    .send()
+    .await
+    .map_err(|e| format!("Failed to download repository: {}", e))?;
+
+  check_status(provider, response.status(), "Archive download")?;
+
+  let bytes = response
+    .bytes()
+    .await
+    .map_err(|e| format!("Failed to read response: {}", e))?;
+
+  // Extract ZIP synchronously (this is CPU-bound, not I/O bound)
+  extract_zip(&bytes, dest)
+}
+
+fn extract_zip(bytes: &[u8], dest: &Path) -> Result<(), String> {
+  // Repository archives wrap everything in a root folder such as repo-main/
+  Ok(ZipHelper::extract_reader(Cursor::new(bytes), dest, true)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn split_host_accepts_common_url_forms() {
+    let cases = [
+      ("owner/repo", None, "owner/repo"),
+      (
+        "https://github.com/owner/repo.git",
+        Some("github.com"),
+        "owner/repo",
+      ),
+      (
+        "https://www.github.com/owner/repo/tree/main",
+        Some("github.com"),
+        "owner/repo/tree/main",
+      ),
+      (
+        "git@gitlab.example.com:group/sub/repo.git",
+        Some("gitlab.example.com"),
+        "group/sub/repo",
+      ),
+      (
+        "codeberg.org/owner/repo/",
+        Some("codeberg.org"),
+        "owner/repo",
+      ),
+      (
+        "https://user@bitbucket.org/ws/repo",
+        Some("bitbucket.org"),
+        "ws/repo",
+      ),
+    ];
+    for (url, host, path) in cases {
+      let (parsed_host, parsed_path) = split_host(url).unwrap();
+      assert_eq!(parsed_host.as_deref(), host, "{}", url);
+      assert_eq!(parsed_path, path, "{}", url);
+    }
+  }
+
+  #[test]
+  fn gitlab_keeps_subgroups_and_drops_route_suffix() {
+    let provider = GitlabProvider::new("https://gitlab.com".to_string(), None, None);
+    let repo = provider
+      .parse_repo_path("group/sub/repo/-/tree/main/skills")
+      .unwrap();
+    assert_eq!(repo.owner, "group/sub");
+    assert_eq!(repo.repo, "repo");
+  }
+
+  #[test]
+  fn encode_component_escapes_reserved_characters() {
+    assert_eq!(encode_component("group/repo"), "group%2Frepo");
+    assert_eq!(encode_component("v1.0-rc_1~"), "v1.0-rc_1~");
+  }
+}
@@ -0,0 +1,341 @@
+use crate::models::InstallMethod;
+use crate::utils::folder::copy_dir_all_sync;
+use crate::utils::path::remove_path_any;
+use crate::utils::time::now_millis;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A planned filesystem change. Every operation replaces whatever is at `target`.
+#[derive(Debug, Clone)]
+pub enum FsOperation {
+  Remove {
+    target: PathBuf,
+  },
+  CopyDir {
+    source: PathBuf,
+    target: PathBuf,
+  },
+  /// Link `target` to `source`, or copy it when the method is `Copy`
+  Link {
+    source: PathBuf,
+    target: PathBuf,
+    method: InstallMethod,
+  },
+  WriteFile {
+    target: PathBuf,
+    content: String,
+  },
+}
+
+impl FsOperation {
+  fn target(&self) -> &Path {
+    match self {
+      FsOperation::Remove { target }
+      | FsOperation::CopyDir { target, .. }
+      | FsOperation::Link { target, .. }
+      | FsOperation::WriteFile { target, .. } => target,
+    }
+  }
+}
+
+/// A list of filesystem operations that are applied together or not at all.
+///
+/// New content is staged next to its target and swapped in with renames. Anything a
+/// step replaces is moved aside rather than deleted, so a failure at any step puts
+/// every earlier target back the way it was.
+#[derive(Debug, Default)]
+pub struct FsTransaction {
+  operations: Vec<FsOperation>,
+}
+
+impl FsTransaction {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn remove(&mut self, target: impl Into<PathBuf>) {
+    self.operations.push(FsOperation::Remove {
+      target: target.into(),
+    });
+  }
+
+  pub fn copy_dir(&mut self, source: impl Into<PathBuf>, target: impl Into<PathBuf>) {
+    self.operations.push(FsOperation::CopyDir {
+      source: source.into(),
+      target: target.into(),
+    });
+  }
+
+  pub fn link(
+    &mut self,
+    source: impl Into<PathBuf>,
+    target: impl Into<PathBuf>,
+    method: InstallMethod,
+  ) {
+    self.operations.push(FsOperation::Link {
+      source: source.into(),
+      target: target.into(),
+      method,
+    });
+  }
+
+  pub fn write_file(&mut self, target: impl Into<PathBuf>, content: impl Into<String>) {
+    self.operations.push(FsOperation::WriteFile {
+      target: target.into(),
+      content: content.into(),
+    });
+  }
+
+  /// Apply every operation in order. On failure the applied ones are rolled back and
+  /// the error names the step that failed.
+  ///
+  /// The returned guard keeps the replaced content until [`AppliedTransaction::commit`];
+  /// dropping it uncommitted rolls back, so follow-up work such as lock file writes can
+  /// use `?` and still leave the filesystem untouched on error.
+  pub fn apply(self) -> Result<AppliedTransaction, String> {
+    let stamp = now_millis();
+    let mut applied = AppliedTransaction {
+      undo: Vec::new(),
+      committed: false,
+    };
+    for (index, operation) in self.operations.iter().enumerate() {
+      if let Err(err) = applied.apply_one(operation, &format!("{}-{}", stamp, index)) {
+        let message = format!(
+          "Failed to update {}: {}",
+          operation.target().to_string_lossy(),
+          err
+        );
+        return Err(match applied.rollback() {
+          Ok(()) => message,
+          Err(rollback_err) => format!("{}\nRollback incomplete: {}", message, rollback_err),
+        });
+      }
+    }
+    Ok(applied)
+  }
+}
+
+#[derive(Debug)]
+struct UndoStep {
+  target: PathBuf,
+  /// Where the previous content was moved, if there was any
+  backup: Option<PathBuf>,
+  /// Top-most directory created to hold `target`
+  created_dir: Option<PathBuf>,
+}
+
+/// Operations that have been applied but whose previous content is still kept aside.
+#[derive(Debug)]
+pub struct AppliedTransaction {
+  undo: Vec<UndoStep>,
+  committed: bool,
+}
+
+impl AppliedTransaction {
+  /// Keep the new state and discard the previous content.
+  pub fn commit(mut self) {
+    for step in &self.undo {
+      if let Some(backup) = &step.backup {
+        if let Err(err) = remove_path_any(backup) {
+          tracing::warn!(
+            "Failed to remove backup {}: {}",
+            backup.to_string_lossy(),
+            err
+          );
+        }
+      }
+    }
+    self.committed = true;
+  }
+
+  /// Restore every target to its state before the transaction.
+  pub fn rollback(mut self) -> Result<(), String> {
+    self.committed = true;
+    self.rollback_steps()
+  }
+
+  fn apply_one(&mut self, operation: &FsOperation, suffix: &str) -> Result<(), String> {
+    let target = operation.target();
+    let (parent, name) = split_target(target)?;
+    let created_dir = create_missing_dirs(parent)?;
+
+    // Stage copies before touching the target, so a failed copy changes nothing.
+    let staging = match operation {
+      FsOperation::CopyDir { source, .. }
+      | FsOperation::Link {
+        source,
+        method: InstallMethod::Copy,
+        ..
+      } => {
+        let staging = parent.join(format!(".{}.staging-{}", name, suffix));
+        if let Err(err) = copy_dir_all_sync(source, &staging) {
+          let _ = fs::remove_dir_all(&staging);
+          remove_created_dir(created_dir.as_deref());
+          return Err(err);
+        }
+        Some(staging)
+      },
+      FsOperation::WriteFile { content, .. } => {
+        let staging = parent.join(format!(".{}.staging-{}", name, suffix));
+        if let Err(err) = fs::write(&staging, content) {
+          let _ = fs::remove_file(&staging);
+          remove_created_dir(created_dir.as_deref());
+          return Err(err.to_string());
+        }
+        Some(staging)
+      },
+      _ => None,
+    };
+
+    let backup = if target.exists() || target.is_symlink() {
+      let backup = parent.join(format!(".{}.backup-{}", name, suffix));
+      if let Err(err) = fs::rename(target, &backup) {
+        if let Some(staging) = &staging {
+          let _ = remove_path_any(staging);
+        }
+        remove_created_dir(created_dir.as_deref());
+        return Err(format!("Failed to move previous content aside: {}", err));
+      }
+      Some(backup)
+    } else {
+      None
+    };
+    // Recorded before the change so a half-made link is cleaned up on rollback too.
+    self.undo.push(UndoStep {
+      target: target.to_path_buf(),
+      backup,
+      created_dir,
+    });
+
+    match (operation, staging) {
+      (_, Some(staging)) => fs::rename(&staging, target).map_err(|e| {
+        let _ = remove_path_any(&staging);
+        e.to_string()
+      }),
+      (FsOperation::Link { source, .. }, None) => symlink_dir(source, target),
+      _ => Ok(()),
+    }
+  }
+
+  fn rollback_steps(&mut self) -> Result<(), String> {
+    let mut errors = Vec::new();
+    while let Some(step) = self.undo.pop() {
+      if step.target.exists() || step.target.is_symlink() {
+        if let Err(err) = remove_path_any(&step.target) {
+          errors.push(format!("{}: {}", step.target.to_string_lossy(), err));
+          continue;
+        }
+      }
+      if let Some(backup) = &step.backup {
+        if let Err(err) = fs::rename(backup, &step.target) {
+          errors.push(format!(
+            "{}: {} (previous content kept at {})",
+            step.target.to_string_lossy(),
+            err,
+            backup.to_string_lossy()
+          ));
+        }
+      }
+      remove_created_dir(step.created_dir.as_deref());
+    }
+    if !errors.is_empty() {
+      return Err(errors.join("\n"));
+    }
+    Ok(())
+  }
+}
+
+impl Drop for AppliedTransaction {
+  fn drop(&mut self) {
+    if self.committed {
+      return;
+    }
+    if let Err(err) = self.rollback_steps() {
+      tracing::warn!("Failed to roll back filesystem changes: {}", err);
+    }
+  }
+}
+
+fn split_target(target: &Path) -> Result<(&Path, String), String> {
+  let invalid = || format!("Invalid target path: {}", target.to_string_lossy());
+  let parent = target.parent().ok_or_else(invalid)?;
+  let name = target.file_name().ok_or_else(invalid)?;
+  Ok((parent, name.to_string_lossy().to_string()))
+}
+
+/// Create `dir` and any missing ancestors, returning the top-most one created.
+fn create_missing_dirs(dir: &Path) -> Result<Option<PathBuf>, String> {
+  let mut top_missing = None;
+  let mut current = Some(dir);
+  while let Some(path) = current {
+    if path.exists() {
+      break;
+    }
+    top_missing = Some(path.to_path_buf());
+    current = path.parent();
+  }
+  fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+  Ok(top_missing)
+}
+
+fn remove_created_dir(dir: Option<&Path>) {
+  if let Some(dir) = dir {
+    let _ = fs::remove_dir_all(dir);
+  }
+}
+
+fn symlink_dir(source: &Path, target: &Path) -> Result<(), String> {
+  #[cfg(unix)]
+  {
+    std::os::unix::fs::symlink(source, target).map_err(|e| e.to_string())
+  }
+  #[cfg(windows)]
+  {
+    match std::os::windows::fs::symlink_dir(source, target) {
+      Ok(_) => Ok(()),
+      Err(_) => copy_dir_all_sync(source, target),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn failed_step_restores_earlier_targets() {
+    let root = std::env::temp_dir().join(format!("skill-kit-test-txn-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let source = root.join("source");
+    let canonical = root.join("skills").join("demo");
+    let linked = root.join("agent").join("demo");
+    fs::create_dir_all(&source).unwrap();
+    fs::create_dir_all(&canonical).unwrap();
+    fs::create_dir_all(&linked).unwrap();
+    fs::write(source.join("SKILL.md"), "new").unwrap();
+    fs::write(canonical.join("SKILL.md"), "old").unwrap();
+    fs::write(linked.join("SKILL.md"), "old copy").unwrap();
+    // A regular file where a folder is expected makes the last step fail.
+    fs::write(root.join("blocked"), "").unwrap();
+
+    let mut transaction = FsTransaction::new();
+    transaction.copy_dir(&source, &canonical);
+    transaction.remove(&linked);
+    transaction.link(
+      &canonical,
+      root.join("blocked").join("demo"),
+      InstallMethod::Copy,
+    );
+    let result = transaction.apply();
+
+    let canonical_content = fs::read_to_string(canonical.join("SKILL.md")).unwrap();
+    let linked_content = fs::read_to_string(linked.join("SKILL.md")).unwrap();
+    let leftovers = fs::read_dir(root.join("skills")).unwrap().count();
+    let _ = fs::remove_dir_all(&root);
+
+    assert!(result.is_err());
+    assert_eq!(canonical_content, "old");
+    assert_eq!(linked_content, "old copy");
+    assert_eq!(leftovers, 1);
+  }
+}
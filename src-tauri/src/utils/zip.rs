@@ -1,31 +1,508 @@
+use std::collections::HashSet;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io::{self, Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Archives with more entries than this are rejected before anything is written.
+const MAX_ENTRIES: usize = 10_000;
+/// Total uncompressed size allowed across all entries.
+const MAX_TOTAL_BYTES: u64 = 512 * 1024 * 1024;
+/// Entries larger than this must not expand more than `MAX_COMPRESSION_RATIO` times.
+const RATIO_CHECK_MIN_BYTES: u64 = 1024 * 1024;
+const MAX_COMPRESSION_RATIO: u64 = 100;
+/// Symlink entries hold their target path as content, which is never this long.
+const MAX_SYMLINK_TARGET_BYTES: u64 = 4096;
+
+/// Why an archive, or one of its entries, was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZipExtractError {
+  /// The archive could not be opened or parsed
+  Archive(String),
+  /// Entry name is absolute or climbs out of the destination
+  UnsafePath {
+    entry: String,
+  },
+  /// Symlink entry pointing outside the destination
+  UnsafeSymlink {
+    entry: String,
+    target: String,
+  },
+  TooManyEntries {
+    count: usize,
+    limit: usize,
+  },
+  /// The archive expands past the total size limit at this entry
+  TooLarge {
+    entry: String,
+    limit: u64,
+  },
+  CompressionRatio {
+    entry: String,
+    ratio: u64,
+    limit: u64,
+  },
+  Io {
+    entry: String,
+    message: String,
+  },
+}
+
+impl fmt::Display for ZipExtractError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ZipExtractError::Archive(message) => write!(f, "Failed to read ZIP: {}", message),
+      ZipExtractError::UnsafePath { entry } => {
+        write!(
+          f,
+          "ZIP entry '{}' points outside the extraction folder",
+          entry
+        )
+      },
+      ZipExtractError::UnsafeSymlink { entry, target } => write!(
+        f,
+        "ZIP entry '{}' is a symlink to '{}' outside the extraction folder",
+        entry, target
+      ),
+      ZipExtractError::TooManyEntries { count, limit } => write!(
+        f,
+        "ZIP has {} entries, more than the limit of {}",
+        count, limit
+      ),
+      ZipExtractError::TooLarge { entry, limit } => write!(
+        f,
+        "ZIP expands past {} MB at entry '{}'",
+        limit / (1024 * 1024),
+        entry
+      ),
+      ZipExtractError::CompressionRatio {
+        entry,
+        ratio,
+        limit,
+      } => write!(
+        f,
+        "ZIP entry '{}' expands {}x, more than the limit of {}x",
+        entry, ratio, limit
+      ),
+      ZipExtractError::Io { entry, message } => {
+        write!(f, "Failed to extract ZIP entry '{}': {}", entry, message)
+      },
+    }
+  }
+}
+
+impl std::error::Error for ZipExtractError {}
+
+impl From<ZipExtractError> for String {
+  fn from(err: ZipExtractError) -> Self {
+    err.to_string()
+  }
+}
 
 pub struct ZipHelper;
 
 impl ZipHelper {
-  pub fn extract_to_dir(zip_path: &str, dest_dir: &Path) -> Result<(), String> {
-    let file = fs::File::open(zip_path).map_err(|e| format!("Failed to open ZIP: {}", e))?;
+  pub fn extract_to_dir(zip_path: &str, dest_dir: &Path) -> Result<(), ZipExtractError> {
+    let file = fs::File::open(zip_path)
+      .map_err(|e| ZipExtractError::Archive(format!("Failed to open ZIP: {}", e)))?;
+    Self::extract_reader(file, dest_dir, false)
+  }
+
+  /// Extract an archive into `dest_dir`. With `strip_root`, the first path component of
+  /// every entry is dropped, as for repository archives wrapped in `repo-main/`.
+  ///
+  /// Symlink entries are created last, so no file is ever written through one, and only
+  /// when their target stays inside `dest_dir` without passing through another link from
+  /// the archive. No entry may sit below one of the archive's links.
+  pub fn extract_reader<R: Read + Seek>(
+    reader: R,
+    dest_dir: &Path,
+    strip_root: bool,
+  ) -> Result<(), ZipExtractError> {
     let mut archive =
-      zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP: {}", e))?;
+      zip::ZipArchive::new(reader).map_err(|e| ZipExtractError::Archive(e.to_string()))?;
+    if archive.len() > MAX_ENTRIES {
+      return Err(ZipExtractError::TooManyEntries {
+        count: archive.len(),
+        limit: MAX_ENTRIES,
+      });
+    }
+    let root = fs::create_dir_all(dest_dir)
+      .and_then(|_| dest_dir.canonicalize())
+      .map_err(|e| ZipExtractError::Io {
+        entry: String::new(),
+        message: e.to_string(),
+      })?;
 
+    let mut remaining = MAX_TOTAL_BYTES;
+    let mut symlinks: Vec<(String, PathBuf, PathBuf)> = Vec::new();
+    let mut link_paths: HashSet<PathBuf> = HashSet::new();
     for i in 0..archive.len() {
-      let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
-      let outpath = dest_dir.join(file.name());
+      let mut file = archive
+        .by_index(i)
+        .map_err(|e| ZipExtractError::Archive(e.to_string()))?;
+      let entry = file.name().to_string();
+      let io_error = |err: io::Error| ZipExtractError::Io {
+        entry: entry.clone(),
+        message: err.to_string(),
+      };
+
+      let relative = file
+        .enclosed_name()
+        .ok_or_else(|| ZipExtractError::UnsafePath {
+          entry: entry.clone(),
+        })?;
+      let relative: PathBuf = if strip_root {
+        relative.components().skip(1).collect()
+      } else {
+        relative
+      };
+      if relative.as_os_str().is_empty() {
+        continue;
+      }
+      if passes_through_link(&relative, &link_paths) {
+        return Err(ZipExtractError::UnsafePath { entry });
+      }
+      let out_path = dest_dir.join(&relative);
+
+      if file.is_symlink() {
+        let mut target = String::new();
+        (&mut file)
+          .take(MAX_SYMLINK_TARGET_BYTES)
+          .read_to_string(&mut target)
+          .map_err(io_error)?;
+        link_paths.insert(relative.clone());
+        symlinks.push((entry, relative, PathBuf::from(target)));
+        continue;
+      }
+
+      if file.is_dir() {
+        create_parent_inside(&entry, &out_path, &root)?;
+        fs::create_dir_all(&out_path).map_err(io_error)?;
+        continue;
+      }
+
+      let size = file.size();
+      if size > remaining {
+        return Err(ZipExtractError::TooLarge {
+          entry,
+          limit: MAX_TOTAL_BYTES,
+        });
+      }
+      if size > RATIO_CHECK_MIN_BYTES {
+        let ratio = size / file.compressed_size().max(1);
+        if ratio > MAX_COMPRESSION_RATIO {
+          return Err(ZipExtractError::CompressionRatio {
+            entry,
+            ratio,
+            limit: MAX_COMPRESSION_RATIO,
+          });
+        }
+      }
+      create_parent_inside(&entry, &out_path, &root)?;
+      let mut out_file = fs::File::create(&out_path).map_err(io_error)?;
+      // The declared size can lie; count what is actually written.
+      let written =
+        io::copy(&mut (&mut file).take(remaining + 1), &mut out_file).map_err(io_error)?;
+      if written > remaining {
+        drop(out_file);
+        let _ = fs::remove_file(&out_path);
+        return Err(ZipExtractError::TooLarge {
+          entry,
+          limit: MAX_TOTAL_BYTES,
+        });
+      }
+      remaining -= written;
+
+      #[cfg(unix)]
+      if let Some(mode) = file.unix_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&out_path, fs::Permissions::from_mode(mode & 0o777))
+          .map_err(io_error)?;
+      }
+    }
+
+    // Checked against every link in the archive, whatever order they come in
+    for (entry, relative, target) in &symlinks {
+      if passes_through_link(relative, &link_paths) {
+        return Err(ZipExtractError::UnsafePath {
+          entry: entry.clone(),
+        });
+      }
+      if !symlink_stays_inside(relative, target, &link_paths) {
+        return Err(ZipExtractError::UnsafeSymlink {
+          entry: entry.clone(),
+          target: target.to_string_lossy().to_string(),
+        });
+      }
+    }
+    for (entry, relative, target) in &symlinks {
+      create_symlink(entry, &dest_dir.join(relative), target, &root)?;
+    }
+    Ok(())
+  }
 
-      if file.name().ends_with('/') {
-        fs::create_dir_all(&outpath).map_err(|e| e.to_string())?;
+  /// Add the contents of `dir` to `zip`, naming entries under `prefix` (the archive root
+  /// when empty). Symlinks are stored as links, never as what they point at, and left out
+  /// when they point outside the archive, which extraction would refuse.
+  pub fn add_dir<W: Write + Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    dir: &Path,
+    prefix: &str,
+    options: zip::write::SimpleFileOptions,
+  ) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read directory: {}", e))? {
+      let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+      let path = entry.path();
+      let file_name = entry.file_name().to_string_lossy().to_string();
+      let name = if prefix.is_empty() {
+        file_name
       } else {
-        if let Some(p) = outpath.parent() {
-          if !p.exists() {
-            fs::create_dir_all(p).map_err(|e| e.to_string())?;
-          }
+        format!("{}/{}", prefix, file_name)
+      };
+
+      let file_type = entry
+        .file_type()
+        .map_err(|e| format!("Failed to read directory entry: {}", e))?;
+      if file_type.is_symlink() {
+        let target = fs::read_link(&path).map_err(|e| format!("Failed to read symlink: {}", e))?;
+        if !symlink_stays_inside(Path::new(&name), &target, &HashSet::new()) {
+          tracing::warn!(
+            "Leaving out symlink {} -> {} that points outside the archive",
+            path.display(),
+            target.display()
+          );
+          continue;
         }
-        let mut outfile = fs::File::create(&outpath).map_err(|e| e.to_string())?;
-        std::io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
+        zip
+          .add_symlink(name.as_str(), target.to_string_lossy(), options)
+          .map_err(|e| format!("Failed to add symlink to ZIP: {}", e))?;
+      } else if file_type.is_file() {
+        let mut file = fs::File::open(&path).map_err(|e| format!("Failed to open file: {}", e))?;
+        zip
+          .start_file(name.as_str(), options)
+          .map_err(|e| format!("Failed to add file to ZIP: {}", e))?;
+        io::copy(&mut file, zip).map_err(|e| format!("Failed to write file to ZIP: {}", e))?;
+      } else if file_type.is_dir() {
+        zip
+          .add_directory(name.as_str(), options)
+          .map_err(|e| format!("Failed to add directory to ZIP: {}", e))?;
+        Self::add_dir(zip, &path, &name, options)?;
       }
     }
+    Ok(())
+  }
+}
+
+/// Whether a folder on the way to `relative` is one of the archive's symlinks
+fn passes_through_link(relative: &Path, links: &HashSet<PathBuf>) -> bool {
+  relative
+    .ancestors()
+    .skip(1)
+    .any(|ancestor| links.contains(ancestor))
+}
 
+/// Whether `target`, read relative to the folder of the link at `link`, stays within the
+/// extraction root without going through another of the archive's `links`, which would
+/// make the text of the path say nothing about where it ends up. All paths are relative
+/// to the root.
+fn symlink_stays_inside(link: &Path, target: &Path, links: &HashSet<PathBuf>) -> bool {
+  let mut resolved = link.parent().unwrap_or(Path::new("")).to_path_buf();
+  for component in target.components() {
+    match component {
+      Component::Normal(name) => {
+        if links.contains(&resolved) {
+          return false;
+        }
+        resolved.push(name);
+      },
+      Component::CurDir => {},
+      Component::ParentDir => {
+        if links.contains(&resolved) || !resolved.pop() {
+          return false;
+        }
+      },
+      Component::RootDir | Component::Prefix(_) => return false,
+    }
+  }
+  true
+}
+
+/// Create the folder holding `out_path`, and check that on disk it really is inside
+/// `root`, the canonical extraction folder.
+fn create_parent_inside(entry: &str, out_path: &Path, root: &Path) -> Result<(), ZipExtractError> {
+  let parent = out_path.parent().unwrap_or(root);
+  let real_parent = fs::create_dir_all(parent)
+    .and_then(|_| parent.canonicalize())
+    .map_err(|err| ZipExtractError::Io {
+      entry: entry.to_string(),
+      message: err.to_string(),
+    })?;
+  if !real_parent.starts_with(root) {
+    return Err(ZipExtractError::UnsafePath {
+      entry: entry.to_string(),
+    });
+  }
+  Ok(())
+}
+
+fn create_symlink(
+  entry: &str,
+  out_path: &Path,
+  target: &Path,
+  root: &Path,
+) -> Result<(), ZipExtractError> {
+  let io_error = |err: io::Error| ZipExtractError::Io {
+    entry: entry.to_string(),
+    message: err.to_string(),
+  };
+  create_parent_inside(entry, out_path, root)?;
+  if fs::symlink_metadata(out_path).is_ok() {
+    return Err(ZipExtractError::Io {
+      entry: entry.to_string(),
+      message: "an entry with the same name was already extracted".to_string(),
+    });
+  }
+  #[cfg(unix)]
+  {
+    std::os::unix::fs::symlink(target, out_path).map_err(io_error)
+  }
+  #[cfg(not(unix))]
+  {
+    // Creating symlinks needs extra privileges on Windows; skills work without them.
+    tracing::warn!(
+      "Skipping symlink entry '{}' -> '{}'",
+      entry,
+      target.to_string_lossy()
+    );
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{Cursor, Write};
+  use zip::write::SimpleFileOptions;
+
+  fn build_zip(entries: &[(&str, Option<&str>, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, link_target, content) in entries {
+      match link_target {
+        Some(target) => writer
+          .add_symlink(*name, *target, SimpleFileOptions::default())
+          .unwrap(),
+        None => {
+          writer
+            .start_file(*name, SimpleFileOptions::default().unix_permissions(0o755))
+            .unwrap();
+          writer.write_all(content).unwrap();
+        },
+      }
+    }
+    writer.finish().unwrap().into_inner()
+  }
+
+  fn temp_dest(name: &str) -> PathBuf {
+    let dest =
+      std::env::temp_dir().join(format!("youskill-zip-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dest);
+    dest
+  }
+
+  #[test]
+  fn rejects_entries_and_links_escaping_the_destination() {
+    let dest = temp_dest("escape");
+    let traversal = build_zip(&[("../evil.txt", None, b"x")]);
+    let outside_link = build_zip(&[("skill/link", Some("../../etc"), b"")]);
+
+    let traversal_result = ZipHelper::extract_reader(Cursor::new(traversal), &dest, false);
+    let link_result = ZipHelper::extract_reader(Cursor::new(outside_link), &dest, false);
+    let escaped = dest.parent().unwrap().join("evil.txt").exists();
+    let _ = fs::remove_dir_all(&dest);
+
+    assert_eq!(
+      traversal_result,
+      Err(ZipExtractError::UnsafePath {
+        entry: "../evil.txt".to_string()
+      })
+    );
+    assert!(matches!(
+      link_result,
+      Err(ZipExtractError::UnsafeSymlink { ref entry, .. }) if entry == "skill/link"
+    ));
+    assert!(!escaped);
+  }
+
+  #[test]
+  fn rejects_links_chained_through_other_links() {
+    let dest = temp_dest("chain");
+    let chained = build_zip(&[
+      ("a/b/up", Some("../.."), b""),
+      ("a/b/up/esc", Some("../../.."), b""),
+    ]);
+    let through_target = build_zip(&[
+      ("a/b/up", Some("../.."), b""),
+      ("c/esc", Some("../a/b/up/../.."), b""),
+    ]);
+    let file_below_link = build_zip(&[("a/up", Some(".."), b""), ("a/up/x.txt", None, b"x")]);
+
+    let chained_result = ZipHelper::extract_reader(Cursor::new(chained), &dest, false);
+    let escaped = dest.join("esc").is_symlink() || dest.join("a/b/up/esc").is_symlink();
+    let _ = fs::remove_dir_all(&dest);
+    let target_result = ZipHelper::extract_reader(Cursor::new(through_target), &dest, false);
+    let _ = fs::remove_dir_all(&dest);
+    let file_result = ZipHelper::extract_reader(Cursor::new(file_below_link), &dest, false);
+    let _ = fs::remove_dir_all(&dest);
+
+    assert_eq!(
+      chained_result,
+      Err(ZipExtractError::UnsafePath {
+        entry: "a/b/up/esc".to_string()
+      })
+    );
+    assert!(!escaped);
+    assert!(matches!(
+      target_result,
+      Err(ZipExtractError::UnsafeSymlink { ref entry, .. }) if entry == "c/esc"
+    ));
+    assert_eq!(
+      file_result,
+      Err(ZipExtractError::UnsafePath {
+        entry: "a/up/x.txt".to_string()
+      })
+    );
+  }
+
+  #[test]
+  fn extracts_files_links_and_permissions_and_rejects_zip_bombs() {
+    let dest = temp_dest("ok");
+    let archive = build_zip(&[
+      ("repo-main/skill/SKILL.md", None, b"---\nname: demo\n---\n"),
+      ("repo-main/skill/run.sh", None, b"#!/bin/sh\n"),
+      ("repo-main/skill/docs", Some("SKILL.md"), b""),
+    ]);
+    let bomb = build_zip(&[("bomb.txt", None, &vec![0u8; 4 * 1024 * 1024])]);
+
+    let result = ZipHelper::extract_reader(Cursor::new(archive), &dest, true);
+    let skill_md = fs::read_to_string(dest.join("skill/docs")).unwrap_or_default();
+    #[cfg(unix)]
+    let mode = {
+      use std::os::unix::fs::PermissionsExt;
+      fs::metadata(dest.join("skill/run.sh"))
+        .map(|metadata| metadata.permissions().mode() & 0o777)
+        .unwrap_or_default()
+    };
+    let bomb_result = ZipHelper::extract_reader(Cursor::new(bomb), &dest, false);
+    let _ = fs::remove_dir_all(&dest);
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(skill_md, "---\nname: demo\n---\n");
+    #[cfg(unix)]
+    assert_eq!(mode, 0o755);
+    assert!(matches!(
+      bomb_result,
+      Err(ZipExtractError::CompressionRatio { ref entry, .. }) if entry == "bomb.txt"
+    ));
+  }
+}
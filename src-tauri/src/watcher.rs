@@ -0,0 +1,20 @@
+use tauri::{AppHandle, Emitter, Manager};
+use you_skill::services::skill_watch_service::start_skill_watcher;
+
+use crate::tray::refresh_tray;
+
+/// Start the skill folder watcher and forward its changes as app events.
+pub fn setup_watcher(app: &AppHandle) -> Result<(), String> {
+  let handle = app.clone();
+  let watcher = start_skill_watcher(move |events| {
+    for event in events {
+      if let Err(e) = handle.emit(event.event_name(), &event) {
+        tracing::warn!("Failed to emit {}: {}", event.event_name(), e);
+      }
+    }
+    refresh_tray(&handle);
+  })?;
+  // Managed state lives as long as the app, which keeps the watcher running.
+  app.manage(watcher);
+  Ok(())
+}
@@ -11,6 +11,25 @@ import type { AgentInfo } from "./skills";
 
 export type AgentApp = AgentInfo;
 
+/** bundled：随应用打包的内置列表；refreshed：从注册表源更新的列表 */
+export type AgentRegistrySource = "bundled" | "refreshed";
+
+export interface AgentRegistryStatus {
+  source: AgentRegistrySource;
+  revision: number;
+  bundled_revision: number;
+  agent_count: number;
+}
+
+export type AgentAppImportStatus = "added" | "updated" | "unchanged" | "skipped";
+
+export interface AgentAppImportResult {
+  id: string;
+  display_name: string;
+  status: AgentAppImportStatus;
+  message?: string | null;
+}
+
 // ============ Agent Apps ============
 
 /**
@@ -27,6 +46,20 @@ export async function refreshAgentApps(): Promise<AgentApp[]> {
   return apiCall<AgentApp[]>("refresh_agent_apps");
 }
 
+/**
+ * 获取内置 Agent 应用列表的来源和版本
+ */
+export async function getAgentRegistryStatus(): Promise<AgentRegistryStatus> {
+  return apiCall<AgentRegistryStatus>("get_agent_registry_status");
+}
+
+/**
+ * 从注册表源（默认使用设置中的地址）更新内置 Agent 应用列表
+ */
+export async function refreshAgentRegistry(url?: string | null): Promise<AgentRegistryStatus> {
+  return apiCall<AgentRegistryStatus>("refresh_agent_registry", { url });
+}
+
 /**
  * 添加用户自定义 Agent 应用
  */
@@ -65,3 +98,17 @@ export async function updateAgentApp(
     projectPath,
   });
 }
+
+/**
+ * 将用户自定义 Agent 应用导出为可共享的注册表文件，ids 为空时导出全部
+ */
+export async function exportAgentApps(ids: string[], path: string): Promise<number> {
+  return apiCall<number>("export_user_agent_apps", { ids, path });
+}
+
+/**
+ * 从共享的注册表文件或 URL 导入自定义 Agent 应用，冲突的条目会被跳过
+ */
+export async function importAgentApps(source: string): Promise<AgentAppImportResult[]> {
+  return apiCall<AgentAppImportResult[]>("import_user_agent_apps", { source });
+}
@@ -5,6 +5,7 @@
  */
 
 import { apiCall } from "./index";
+import type { RemoteSkill } from "./skills";
 
 export interface AppSettings {
   language: "en" | "zh";
@@ -13,9 +14,84 @@ export interface AppSettings {
   unknown_skill_install_permission: boolean;
   backup_folder?: string | null;
   last_backup_time?: string | null;
+  /** 备份方式；省略时保持不变 */
+  backup_mode?: BackupMode | null;
+  /** 备份保留规则，备份后自动应用；省略时保持不变 */
+  backup_retention?: BackupRetention | null;
+  /** 自动备份到备份文件夹的频率；省略时保持不变 */
+  backup_schedule?: BackupSchedule | null;
+  /** 后台检查技能更新的间隔（小时），0 为关闭；省略时保持不变 */
+  update_check_hours?: number | null;
   openrouter_api_key?: string | null;
   translate_target_language: string;
   translate_model: string;
+  /** 自托管的 Git 服务（GitLab、Gitea/Forgejo、Bitbucket 等）；省略时保持不变 */
+  source_hosts?: SourceHostConfig[] | null;
+  /** 检测暂存区的容量上限（MB），启动时清理；省略时保持不变 */
+  workspace_max_mb?: number | null;
+}
+
+/** zip：每次备份一个完整 ZIP；dedup：相同文件只存一份，每次备份只记录清单 */
+export type BackupMode = "zip" | "dedup";
+
+export type BackupSchedule = "off" | "daily" | "weekly";
+
+export type ScheduledJob = "backup" | "update_check";
+
+/** 一次定时任务的结果，随 scheduled-job-finished 事件发送 */
+export interface ScheduledJobRun {
+  job: ScheduledJob;
+  /** RFC 3339 格式的完成时间 */
+  finished_at: string;
+  success: boolean;
+  message?: string | null;
+  /** 备份任务写入的备份文件 */
+  backup_path?: string | null;
+  /** 更新检查发现的有新版本的全局技能 */
+  updates: RemoteSkill[];
+}
+
+/** 各定时任务最近一次的结果，重启后保留 */
+export interface ScheduleState {
+  backup?: ScheduledJobRun | null;
+  update_check?: ScheduledJobRun | null;
+}
+
+/** 任一规则保留的备份都会保留；全部为 0 时不清理 */
+export interface BackupRetention {
+  /** 保留最近 N 个备份 */
+  keep_last: number;
+  /** 保留最近 N 天中每天最新的备份 */
+  keep_daily: number;
+  /** 保留最近 N 周中每周最新的备份 */
+  keep_weekly: number;
+}
+
+export type ProviderKind = "github" | "gitlab" | "gitea" | "bitbucket";
+
+export interface SourceHostConfig {
+  kind: ProviderKind;
+  /** 网页地址，例如 https://gitlab.example.com */
+  base_url: string;
+  /** API 地址，不在默认路径下时填写 */
+  api_url?: string | null;
+  /** 访问令牌，用于私有仓库；Bitbucket 应用密码写作 username:app_password */
+  token?: string | null;
+}
+
+export interface RateLimitStatus {
+  limit: number;
+  remaining?: number | null;
+  reset_at?: string | null;
+}
+
+export interface SourceConnectionStatus {
+  kind: ProviderKind;
+  base_url: string;
+  authenticated: boolean;
+  user?: string | null;
+  scopes: string[];
+  rate_limit?: RateLimitStatus | null;
 }
 
 /**
@@ -38,6 +114,11 @@ export async function updateSettings(
     | "openrouter_api_key"
     | "translate_target_language"
     | "translate_model"
+    | "backup_mode"
+    | "backup_retention"
+    | "backup_schedule"
+    | "update_check_hours"
+    | "source_hosts"
   >
 ): Promise<AppSettings> {
   return apiCall<AppSettings>("update_settings", { settings });
@@ -64,6 +145,53 @@ export async function backupSkills(backupFolder: string): Promise<BackupResult>
   return apiCall<BackupResult>("backup_skills", { backupFolder });
 }
 
+/**
+ * 列出备份文件夹中的备份（默认使用设置中的备份文件夹）
+ */
+export async function listBackups(backupFolder?: string | null): Promise<BackupInfo[]> {
+  return apiCall<BackupInfo[]>("list_backups", { backupFolder });
+}
+
+/**
+ * 按保留规则清理备份，并删除不再被引用的备份文件；dryRun 时只返回将被删除的备份
+ */
+export async function pruneBackups(
+  backupFolder?: string | null,
+  dryRun = false
+): Promise<BackupPruneResult> {
+  return apiCall<BackupPruneResult>("prune_backups", { backupFolder, dryRun });
+}
+
+/**
+ * 获取定时备份和更新检查最近一次的结果
+ */
+export async function getScheduleState(): Promise<ScheduleState> {
+  return apiCall<ScheduleState>("get_schedule_state");
+}
+
+/**
+ * 预览恢复备份会新增、覆盖或移除哪些技能
+ */
+export async function previewBackupRestore(backupPath: string): Promise<BackupRestorePreview> {
+  return apiCall<BackupRestorePreview>("preview_backup_restore", { backupPath });
+}
+
+/**
+ * 从备份恢复全部或选定的技能，并重新关联 Agent 应用
+ */
+export async function restoreBackup(request: RestoreBackupRequest): Promise<BackupRestoreResult> {
+  return apiCall<BackupRestoreResult>("restore_backup", { request });
+}
+
+/**
+ * 测试 Git 服务连接，返回令牌权限范围与速率限制
+ */
+export async function testSourceConnection(
+  host: SourceHostConfig
+): Promise<SourceConnectionStatus> {
+  return apiCall<SourceConnectionStatus>("test_source_connection", { host });
+}
+
 export interface OpenRouterModelOption {
   id: string;
   name: string;
@@ -81,3 +209,56 @@ export interface BackupResult {
   backup_path: string | null;
   backup_time: string | null;
 }
+
+export interface BackupInfo {
+  file_name: string;
+  path: string;
+  mode: BackupMode;
+  /** ZIP 文件大小；去重备份为备份文件的原始总大小 */
+  size: number;
+  backup_time: string;
+  skill_count: number;
+}
+
+export interface BackupPruneResult {
+  removed: BackupInfo[];
+  kept: number;
+  freed_bytes: number;
+}
+
+export interface BackupRestorePreview {
+  added: string[];
+  overwritten: string[];
+  removed: string[];
+  unchanged: string[];
+  /** 仅存在于 Agent 目录中的全局技能 */
+  agent_skills: string[];
+  /** 备份中包含的项目路径 */
+  projects: string[];
+  /** 本机不存在的项目路径，需映射到新路径才会恢复 */
+  missing_projects: string[];
+  /** 备份中包含的锁文件、Agent、项目和设置文件 */
+  state_files: string[];
+}
+
+export interface RestoreBackupRequest {
+  backup_path: string;
+  /** 为空时恢复备份中的全部技能 */
+  skills?: string[];
+  /** 同时删除备份中不存在的已安装技能 */
+  remove_missing?: boolean;
+  /** 同时恢复锁文件、自定义 Agent、项目列表和设置 */
+  include_state?: boolean;
+  /** 同时恢复本机已存在的项目中的技能和 skills-lock.json */
+  include_projects?: boolean;
+  /** 备份中的项目路径到本机新路径的映射 */
+  project_roots?: Record<string, string>;
+}
+
+export interface BackupRestoreResult {
+  restored: string[];
+  removed: string[];
+  errors: string[];
+  projects: string[];
+  state_files: string[];
+}
@@ -8,7 +8,7 @@ import { apiCall } from "./index";
 
 // ============ Types ============
 
-export type SourceType = "github" | "native" | "unknown";
+export type SourceType = "github" | "gitlab" | "gitea" | "bitbucket" | "native" | "unknown";
 
 export interface LocalSkill {
   name: string;
@@ -16,6 +16,19 @@ export interface LocalSkill {
   root_folder?: string | null;
   installed_agent_apps: InstalledAgentApp[];
   source_type: SourceType;
+  frontmatter?: SkillFrontmatter | null;
+}
+
+/** SKILL.md frontmatter; keys outside the known fields are kept in `extra` */
+export interface SkillFrontmatter {
+  name?: string | null;
+  description?: string | null;
+  license?: string | null;
+  version?: string | null;
+  compatibility?: string | null;
+  allowed_tools: string[];
+  metadata: Record<string, unknown>;
+  extra: Record<string, unknown>;
 }
 
 export interface InstalledAgentApp {
@@ -52,20 +65,122 @@ export interface InstallResult {
   stdout: string;
   stderr: string;
   message: string;
+  /** Lint findings for the installed skill */
+  diagnostics?: SkillDiagnostic[];
 }
 
 export interface AgentInfo {
   id: string;
   display_name: string;
   project_path?: string | null;
+  /** May hold `~/`, `${VAR}` or `${VAR:-default}` */
   global_path?: string | null;
+  /** Other project skills folders the agent reads, in order of preference */
+  project_paths?: string[];
+  /** Other global skills folders the agent reads, in order of preference */
+  global_paths?: string[];
   is_user_custom: boolean;
+  /** Registry rules for spotting the agent; absent for custom apps */
+  detection?: AgentDetection | null;
+  /** Native file format skills are also rendered into for this agent */
+  adapter?: SkillAdapterKind | null;
+  /** Why the agent counts as installed; only set on detected agents */
+  detected_by?: AgentDetectionMatch | null;
+  /** Version printed by the agent's CLI, when it has one */
+  detected_version?: string | null;
+  /** Global skills folder in use, with variables expanded; only set on detected agents */
+  global_root?: string | null;
+}
+
+export interface AgentDetection {
+  binaries: string[];
+  config_files: string[];
+  version_args: string[];
+}
+
+export type SkillAdapterKind =
+  | "cursor_rules"
+  | "windsurf_rules"
+  | "copilot_instructions"
+  | "continue_prompt"
+  | "roo_rules";
+
+export type AgentDetectionKind = "binary" | "config_file" | "skills_folder" | "agent_folder";
+
+export interface AgentDetectionMatch {
+  kind: AgentDetectionKind;
+  /** Binary, config file or folder that matched */
+  path: string;
+}
+
+export type GitRefKind = "branch" | "tag" | "commit";
+
+export interface SourceRef {
+  /** Branch, tag or commit requested by the user; null means the default branch */
+  git_ref?: string | null;
+  ref_kind?: GitRefKind | null;
+  resolved_commit?: string | null;
 }
 
 export interface DetectedSkill {
   name: string;
-  tmp_path: string;
+  /** 暂存检测结果的会话，安装时原样传回，结束后调用 releaseDetectSession */
+  session_id: string;
+  /** SKILL.md 相对来源根目录的路径 */
   skill_path: string;
+  source_ref?: SourceRef | null;
+  frontmatter?: SkillFrontmatter | null;
+  diagnostics?: SkillDiagnostic[];
+  /** 来自技能包时记录的原始来源和关联的 Agent */
+  pack_origin?: SkillPackOrigin | null;
+}
+
+export interface SkillPackAgentLink {
+  id: string;
+  method: InstallMethod;
+}
+
+export interface SkillPackOrigin {
+  source_type: SourceType;
+  /** 仓库来源，本地技能为空 */
+  source?: string | null;
+  agent_apps: SkillPackAgentLink[];
+  /** 内容与导出时不同，恢复来源时按本地技能安装 */
+  modified?: boolean;
+}
+
+export interface ExportSkillPackRequest {
+  names: string[];
+  scope: InstallScope;
+  project_path?: string | null;
+  /** 要写入的 ZIP 文件路径 */
+  output_path: string;
+}
+
+export interface SkillPackExportResult {
+  path: string;
+  skills: string[];
+}
+
+export type LintSeverity = "error" | "warning" | "info";
+
+export interface SkillDiagnostic {
+  severity: LintSeverity;
+  /** Stable rule id, e.g. "name-format" or "link-missing" */
+  code: string;
+  message: string;
+  /** Path relative to the skill folder */
+  file?: string | null;
+  /** 1-based line number */
+  line?: number | null;
+}
+
+export interface SkillLintReport {
+  name?: string | null;
+  path: string;
+  scope?: InstallScope | null;
+  project_path?: string | null;
+  diagnostics: SkillDiagnostic[];
 }
 
 export interface SkillDirectoryEntry {
@@ -85,20 +200,23 @@ export type InstallScope = "global" | "project";
 
 export interface InstallNativeRequest {
   name: string;
-  tmp_path: string;
+  session_id: string;
   skill_path: string;
   agent_apps: string[];
   method: InstallMethod;
   scope: InstallScope;
   project_path?: string | null;
+  /** 技能包中的技能：恢复记录的来源并同时链接导出时关联的 Agent */
+  restore_pack_origin?: boolean;
 }
 
 export interface InstallGithubRequest {
   name: string;
-  tmp_path: string;
+  session_id: string;
   skill_path: string;
   source_url: string;
   skill_folder_hash?: string | null;
+  source_ref?: SourceRef | null;
   agent_apps: string[];
   method: InstallMethod;
   scope: InstallScope;
@@ -130,6 +248,120 @@ export interface SkillUpdateCheckItem {
   name: string;
   source: string;
   remote_sha: string;
+  /** Branch the remote SHA was read from */
+  branch?: string | null;
+}
+
+export interface SkillUpdateResult {
+  name: string;
+  success: boolean;
+  /** false when the skill was already up to date or the update failed */
+  updated: boolean;
+  previous_hash?: string | null;
+  current_hash?: string | null;
+  /** Agent apps whose links were carried over to the updated skill */
+  agent_apps: string[];
+  message: string;
+}
+
+export interface SyncProjectLockRequest {
+  project_path: string;
+  /** Empty means every agent whose skills folder already exists in the project */
+  agent_apps: string[];
+  method: InstallMethod;
+}
+
+export type ProjectLockSyncStatus =
+  | "installed"
+  | "up_to_date"
+  | "hash_mismatch"
+  | "unsatisfiable"
+  | "failed";
+
+export interface ProjectLockSyncResult {
+  name: string;
+  status: ProjectLockSyncStatus;
+  source: string;
+  expected_hash: string;
+  actual_hash?: string | null;
+  message: string;
+}
+
+export type SkillDriftStatus = "clean" | "modified" | "missing" | "untracked";
+
+export type FileChangeKind = "added" | "removed" | "modified";
+
+export interface SkillFileChange {
+  /** Path relative to the skill folder */
+  path: string;
+  change: FileChangeKind;
+}
+
+export interface SkillDriftItem {
+  name: string;
+  status: SkillDriftStatus;
+  path?: string | null;
+  source?: string | null;
+  expected_hash?: string | null;
+  actual_hash?: string | null;
+  /** Empty for modified skills locked before per-file hashes were recorded */
+  changes: SkillFileChange[];
+  /** Whether the locked content can be fetched again from a repository */
+  restorable: boolean;
+}
+
+export type DoctorIssueKind =
+  | "broken_link"
+  | "link_outside_store"
+  | "missing_locked_folder"
+  | "unlocked_skill"
+  | "unlinked_skill"
+  | "duplicate_name"
+  | "unreadable_skill_md";
+
+/** 修复动作，action 之外的字段随动作不同 */
+export type DoctorFix =
+  | { action: "remove_link"; path: string }
+  | { action: "relink_to_store"; path: string; name: string }
+  | { action: "adopt_into_store"; path: string; name: string }
+  | { action: "remove_lock_entry"; name: string }
+  | { action: "lock_as_native"; name: string }
+  | { action: "link_to_agents"; name: string }
+  | { action: "replace_with_store_copy"; path: string; name: string }
+  /** 移到配置目录下的 doctor-quarantine，不会删除 */
+  | { action: "quarantine_folder"; path: string };
+
+export interface DoctorFinding {
+  id: string;
+  kind: DoctorIssueKind;
+  scope: InstallScope;
+  project_path?: string | null;
+  skill_name?: string | null;
+  path?: string | null;
+  agent_id?: string | null;
+  message: string;
+  fix: DoctorFix;
+}
+
+export interface DoctorFixResult {
+  id: string;
+  success: boolean;
+  message: string;
+}
+
+/** 文件监听推送的技能变更（skill-added / skill-removed / skill-modified 事件） */
+export interface SkillChangeEvent {
+  kind: "skill_added" | "skill_removed" | "skill_modified";
+  name: string;
+  scope: InstallScope;
+  project_path?: string | null;
+}
+
+/** 文件监听推送的 Agent 变更（agent-detected / agent-removed 事件） */
+export interface AgentChangeEvent {
+  kind: "agent_detected" | "agent_removed";
+  id: string;
+  display_name: string;
 }
 
 export interface ManageSkillAgentAppsRequest {
@@ -194,8 +426,11 @@ export async function fetchSkillsByNames(names: string[]): Promise<RemoteSkill[]
 /**
  * 从 GitHub URL 检测技能
  */
-export async function detectGithubManual(githubPath: string): Promise<DetectedSkill[]> {
-  return apiCall<DetectedSkill[]>("detect_github_manual", { githubPath });
+export async function detectGithubManual(
+  githubPath: string,
+  gitRef?: string | null
+): Promise<DetectedSkill[]> {
+  return apiCall<DetectedSkill[]>("detect_github_manual", { githubPath, gitRef });
 }
 
 /**
@@ -221,9 +456,27 @@ export async function detectFolder(folderPath: string): Promise<DetectedSkill[]>
  */
 export async function detectGithubAuto(
   githubPath: string,
-  skillName: string
+  skillName: string,
+  gitRef?: string | null
 ): Promise<DetectedSkill> {
-  return apiCall<DetectedSkill>("detect_github_auto", { githubPath, skillName });
+  return apiCall<DetectedSkill>("detect_github_auto", { githubPath, skillName, gitRef });
+}
+
+/**
+ * 安装完成或取消后释放检测会话的暂存文件
+ */
+export async function releaseDetectSession(sessionId: string): Promise<void> {
+  return apiCall<void>("release_detect_session", { sessionId });
+}
+
+/**
+ * 释放一组检测结果涉及的所有会话，失败时只记录日志
+ */
+export async function releaseDetectedSkills(skills: DetectedSkill[]): Promise<void> {
+  const sessionIds = new Set(skills.map((skill) => skill.session_id).filter(Boolean));
+  await Promise.all(
+    Array.from(sessionIds, (sessionId) => releaseDetectSession(sessionId).catch(console.error))
+  );
 }
 
 export async function installFromNative(request: InstallNativeRequest): Promise<InstallResult> {
@@ -292,6 +545,104 @@ export async function checkSkillsUpdates(checks: SkillUpdateCheckItem[]): Promis
   }
 }
 
+/**
+ * 把有更新的全局技能同步到托盘菜单
+ */
+export async function setTraySkillUpdates(skills: RemoteSkill[]): Promise<void> {
+  return apiCall<void>("set_tray_skill_updates", { skills });
+}
+
+/**
+ * 从 GitHub 来源更新技能，保留已关联的 Agent 应用
+ */
+export async function updateSkill(
+  name: string,
+  scope: InstallScope,
+  projectPath?: string | null
+): Promise<SkillUpdateResult> {
+  return apiCall<SkillUpdateResult>("update_skill", { name, scope, projectPath });
+}
+
+/**
+ * 更新当前范围内所有 GitHub 来源的技能
+ */
+export async function updateAllSkills(
+  scope: InstallScope,
+  projectPath?: string | null
+): Promise<SkillUpdateResult[]> {
+  return apiCall<SkillUpdateResult[]>("update_all_skills", { scope, projectPath });
+}
+
+/**
+ * 按项目的 skills-lock.json 重新安装其中列出的所有技能
+ */
+export async function syncProjectFromLock(
+  request: SyncProjectLockRequest
+): Promise<ProjectLockSyncResult[]> {
+  return apiCall<ProjectLockSyncResult[]>("sync_project_from_lock", { request });
+}
+
+/**
+ * 对比项目中的技能与 skills-lock.json，找出被修改、缺失或未记录的技能
+ */
+export async function checkProjectDrift(projectPath: string): Promise<SkillDriftItem[]> {
+  return apiCall<SkillDriftItem[]>("check_project_drift", { projectPath });
+}
+
+/**
+ * 接受技能的本地改动，更新 skills-lock.json
+ */
+export async function relockProjectSkill(projectPath: string, name: string): Promise<void> {
+  return apiCall<void>("relock_project_skill", { projectPath, name });
+}
+
+/**
+ * 从来源仓库恢复技能为 skills-lock.json 中记录的内容
+ */
+export async function restoreProjectSkill(
+  projectPath: string,
+  name: string
+): Promise<ProjectLockSyncResult> {
+  return apiCall<ProjectLockSyncResult>("restore_project_skill", { projectPath, name });
+}
+
+/**
+ * 检查单个技能目录（或 SKILL.md）的格式问题
+ */
+export async function lintSkill(path: string): Promise<SkillLintReport> {
+  return apiCall<SkillLintReport>("lint_skill", { path });
+}
+
+/**
+ * 检查全局及所有项目中已安装技能的格式问题
+ */
+export async function lintInstalledSkills(): Promise<SkillLintReport[]> {
+  return apiCall<SkillLintReport[]>("lint_installed_skills");
+}
+
+/**
+ * 将选中的技能连同来源和 Agent 关联导出为技能包 ZIP
+ */
+export async function exportSkillPack(
+  request: ExportSkillPackRequest
+): Promise<SkillPackExportResult> {
+  return apiCall<SkillPackExportResult>("export_skill_pack", { request });
+}
+
+/**
+ * 检查全局及所有项目中的失效链接、孤立技能和锁文件不一致
+ */
+export async function runDoctor(): Promise<DoctorFinding[]> {
+  return apiCall<DoctorFinding[]>("run_doctor");
+}
+
+/**
+ * 按顺序应用检查结果的修复动作，单项失败不影响其他项
+ */
+export async function applyDoctorFixes(findings: DoctorFinding[]): Promise<DoctorFixResult[]> {
+  return apiCall<DoctorFixResult[]>("apply_doctor_fixes", { findings });
+}
+
 /**
  * 在文件管理器中打开文件
  */
@@ -13,6 +13,13 @@ const translations: Record<string, Dictionary> = {
     "local.search.placeholder": "Search local skills",
     "local.agent.all": "All Agents",
     "local.refresh": "Refresh",
+    "local.export": "Export skill pack",
+    "local.export.title": "Export skill pack",
+    "local.export.description": "Bundle skills into one ZIP that keeps their source and agent links. Import it from Add Skill → ZIP.",
+    "local.export.empty": "No skills in this scope.",
+    "local.export.confirm": "Export {count}",
+    "local.export.exporting": "Exporting...",
+    "local.export.done": "Saved to {path}",
     "local.loading": "Scanning local skills...",
     "local.empty": "No local skills found",
     "local.section.appCount": "{count} agent apps installed",
@@ -61,6 +68,70 @@ const translations: Record<string, Dictionary> = {
     "settings.backup.lastBackup": "Last backup: {time}",
     "settings.backup.noBackupYet": "No backup yet",
     "settings.backup.openFolder": "Open folder",
+    "settings.backup.mode": "Backup format",
+    "settings.backup.mode.zip": "ZIP per backup",
+    "settings.backup.mode.dedup": "Deduplicated",
+    "settings.backup.retention": "Keep",
+    "settings.backup.retention.keep_last": "last",
+    "settings.backup.retention.keep_daily": "daily",
+    "settings.backup.retention.keep_weekly": "weekly",
+    "settings.backup.retentionHint": "Backups outside these rules are pruned after each backup. All 0 keeps everything.",
+    "settings.backup.pruneNow": "Prune now",
+    "settings.backup.pruned": "Removed {removed} backups, kept {kept}",
+    "settings.backup.schedule": "Automatic backup",
+    "settings.backup.schedule.off": "Off",
+    "settings.backup.schedule.daily": "Daily",
+    "settings.backup.schedule.weekly": "Weekly",
+    "settings.backup.scheduleFailed": "Scheduled backup failed at {time}: {message}",
+    "settings.updateCheck": "Skill update checks",
+    "settings.updateCheck.every": "Every",
+    "settings.updateCheck.hours": "hours",
+    "settings.updateCheck.hint": "Checks global skills for new versions in the background. 0 turns it off.",
+    "settings.updateCheck.last": "Last checked {time}: {count} updates",
+    "settings.updateCheck.failed": "Update check failed at {time}: {message}",
+    "settings.sourceHosts": "Git Hosts",
+    "settings.sourceHosts.description": "Self-hosted servers and access tokens for private repositories",
+    "settings.sourceHosts.add": "Add Host",
+    "settings.sourceHosts.save": "Save",
+    "settings.sourceHosts.remove": "Remove",
+    "settings.sourceHosts.kind.github": "GitHub",
+    "settings.sourceHosts.kind.gitlab": "GitLab",
+    "settings.sourceHosts.kind.gitea": "Gitea / Forgejo",
+    "settings.sourceHosts.kind.bitbucket": "Bitbucket",
+    "settings.sourceHosts.baseUrlPlaceholder": "https://gitlab.example.com",
+    "settings.sourceHosts.apiUrlPlaceholder": "API URL (optional, when not at the usual path)",
+    "settings.sourceHosts.tokenPlaceholder": "Access token (optional)",
+    "settings.sourceHosts.bitbucketTokenPlaceholder": "username:app_password (optional)",
+    "settings.sourceHosts.showToken": "Show token",
+    "settings.sourceHosts.hideToken": "Hide token",
+    "settings.sourceHosts.test": "Test",
+    "settings.sourceHosts.authenticated": "Connected as {user}",
+    "settings.sourceHosts.anonymous": "Connected without a token",
+    "settings.sourceHosts.scopes": "Scopes: {scopes}",
+    "settings.sourceHosts.rateLimit": "{remaining}/{limit} requests left",
+    "settings.doctor": "Skill Doctor",
+    "settings.doctor.description": "Find broken links, orphaned skills and lock file problems",
+    "settings.doctor.runCheck": "Run Check",
+    "settings.doctor.checking": "Checking...",
+    "settings.doctor.noIssues": "No problems found",
+    "settings.doctor.allGood": "Skills and lock files are consistent",
+    "settings.doctor.issueCount": "{count} problems found",
+    "settings.doctor.fixAll": "Fix All",
+    "settings.doctor.kind.broken_link": "Broken link",
+    "settings.doctor.kind.link_outside_store": "Link outside shared folder",
+    "settings.doctor.kind.missing_locked_folder": "Locked skill missing",
+    "settings.doctor.kind.unlocked_skill": "Skill not in lock file",
+    "settings.doctor.kind.unlinked_skill": "Skill not linked to any agent",
+    "settings.doctor.kind.duplicate_name": "Conflicting copies",
+    "settings.doctor.kind.unreadable_skill_md": "Unreadable SKILL.md",
+    "settings.doctor.fix.remove_link": "Remove link",
+    "settings.doctor.fix.relink_to_store": "Relink",
+    "settings.doctor.fix.adopt_into_store": "Move to shared folder",
+    "settings.doctor.fix.remove_lock_entry": "Remove from lock",
+    "settings.doctor.fix.lock_as_native": "Add to lock",
+    "settings.doctor.fix.link_to_agents": "Link to agents",
+    "settings.doctor.fix.replace_with_store_copy": "Use shared copy",
+    "settings.doctor.fix.quarantine_folder": "Move to quarantine",
     "settings.translation.title": "Translation",
     "settings.translation.apiKey": "OpenRouter API Key",
     "settings.translation.model": "Model",
@@ -87,6 +158,7 @@ const translations: Record<string, Dictionary> = {
     "addSkill.zip.clickToChange": "Click to change file",
     "addSkill.zip.detect": "Detect",
     "addSkill.zip.selectSkill": "Select skills to install:",
+    "addSkill.zip.restorePackOrigin": "Restore original source and agent links from the skill pack",
     "addSkill.zip.dropReadError":
       "Could not read the compressed file from drag and drop. Please try click-to-select.",
     "addSkill.zip.invalidCompressedFile":
@@ -113,6 +185,11 @@ const translations: Record<string, Dictionary> = {
     "addSkill.duplicateNames":
       "Selected skills have duplicate names: {names}. Please uncheck duplicates before installing.",
     "addSkill.selectAll": "Select all",
+    "addSkill.lintErrors": "{count} errors",
+    "addSkill.lintWarnings": "{count} warnings",
+    "addSkill.packOrigin": "From skill pack · {source}",
+    "addSkill.packOriginLocal": "local",
+    "addSkill.packOriginModified": "Edited since export, installs as a local skill",
     "addSkill.cancel": "Cancel",
     "addSkill.installing": "Installing...",
     "addSkill.confirm": "Install",
@@ -161,6 +238,23 @@ const translations: Record<string, Dictionary> = {
     "agentApps.remove": "Remove",
     "agentApps.removeConfirm": "Remove this agent app?",
     "agentApps.edit": "Edit",
+    "agentApps.registry.bundled": "Built-in list: revision {revision}, {count} agents",
+    "agentApps.registry.refreshed": "Built-in list: revision {revision} (updated), {count} agents",
+    "agentApps.registry.refresh": "Check for new agents",
+    "agentApps.detectedBy.binary": "On PATH",
+    "agentApps.detectedBy.config_file": "Config file",
+    "agentApps.detectedBy.skills_folder": "Skills folder",
+    "agentApps.detectedBy.agent_folder": "App folder",
+    "agentApps.share.export": "Export",
+    "agentApps.share.exported": "Exported {count} agent apps to {path}",
+    "agentApps.share.import": "Import",
+    "agentApps.share.importFile": "From file",
+    "agentApps.share.urlPlaceholder": "or paste a URL to an agent apps file",
+    "agentApps.share.importUrl": "Import URL",
+    "agentApps.share.status.added": "Added",
+    "agentApps.share.status.updated": "Updated",
+    "agentApps.share.status.unchanged": "Unchanged",
+    "agentApps.share.status.skipped": "Skipped",
     "projectManage.title": "Project Management",
     "projectManage.loading": "Loading projects...",
     "projectManage.empty": "No projects yet",
@@ -186,6 +280,13 @@ const translations: Record<string, Dictionary> = {
     "local.search.placeholder": "搜索本地技能",
     "local.agent.all": "全部 Agent",
     "local.refresh": "刷新",
+    "local.export": "导出技能包",
+    "local.export.title": "导出技能包",
+    "local.export.description": "将技能打包为一个 ZIP，并保留来源和 Agent 关联。可在添加技能 → ZIP 中导入。",
+    "local.export.empty": "当前范围内没有技能。",
+    "local.export.confirm": "导出 {count} 个",
+    "local.export.exporting": "导出中...",
+    "local.export.done": "已保存到 {path}",
     "local.loading": "正在扫描本地 skill...",
     "local.empty": "未找到本地技能",
     "local.section.appCount": "{count} 个 Agent 应用已安装",
@@ -234,6 +335,70 @@ const translations: Record<string, Dictionary> = {
     "settings.backup.lastBackup": "上次备份：{time}",
     "settings.backup.noBackupYet": "尚未备份",
     "settings.backup.openFolder": "打开文件夹",
+    "settings.backup.mode": "备份格式",
+    "settings.backup.mode.zip": "每次一个 ZIP",
+    "settings.backup.mode.dedup": "去重存储",
+    "settings.backup.retention": "保留",
+    "settings.backup.retention.keep_last": "最近",
+    "settings.backup.retention.keep_daily": "每日",
+    "settings.backup.retention.keep_weekly": "每周",
+    "settings.backup.retentionHint": "每次备份后清理不符合规则的备份。全部为 0 时保留所有备份。",
+    "settings.backup.pruneNow": "立即清理",
+    "settings.backup.pruned": "已删除 {removed} 个备份，保留 {kept} 个",
+    "settings.backup.schedule": "自动备份",
+    "settings.backup.schedule.off": "关闭",
+    "settings.backup.schedule.daily": "每天",
+    "settings.backup.schedule.weekly": "每周",
+    "settings.backup.scheduleFailed": "{time} 自动备份失败：{message}",
+    "settings.updateCheck": "技能更新检查",
+    "settings.updateCheck.every": "每",
+    "settings.updateCheck.hours": "小时",
+    "settings.updateCheck.hint": "在后台检查全局技能是否有新版本。设为 0 时关闭。",
+    "settings.updateCheck.last": "上次检查 {time}：{count} 个更新",
+    "settings.updateCheck.failed": "{time} 更新检查失败：{message}",
+    "settings.sourceHosts": "Git 服务",
+    "settings.sourceHosts.description": "自托管服务器及访问私有仓库所需的令牌",
+    "settings.sourceHosts.add": "添加服务",
+    "settings.sourceHosts.save": "保存",
+    "settings.sourceHosts.remove": "移除",
+    "settings.sourceHosts.kind.github": "GitHub",
+    "settings.sourceHosts.kind.gitlab": "GitLab",
+    "settings.sourceHosts.kind.gitea": "Gitea / Forgejo",
+    "settings.sourceHosts.kind.bitbucket": "Bitbucket",
+    "settings.sourceHosts.baseUrlPlaceholder": "https://gitlab.example.com",
+    "settings.sourceHosts.apiUrlPlaceholder": "API 地址（可选，不在默认路径时填写）",
+    "settings.sourceHosts.tokenPlaceholder": "访问令牌（可选）",
+    "settings.sourceHosts.bitbucketTokenPlaceholder": "username:app_password（可选）",
+    "settings.sourceHosts.showToken": "显示令牌",
+    "settings.sourceHosts.hideToken": "隐藏令牌",
+    "settings.sourceHosts.test": "测试连接",
+    "settings.sourceHosts.authenticated": "已连接，用户 {user}",
+    "settings.sourceHosts.anonymous": "已连接（未使用令牌）",
+    "settings.sourceHosts.scopes": "权限：{scopes}",
+    "settings.sourceHosts.rateLimit": "剩余 {remaining}/{limit} 次请求",
+    "settings.doctor": "技能体检",
+    "settings.doctor.description": "查找失效链接、孤立技能和锁文件问题",
+    "settings.doctor.runCheck": "开始检查",
+    "settings.doctor.checking": "正在检查...",
+    "settings.doctor.noIssues": "未发现问题",
+    "settings.doctor.allGood": "技能与锁文件一致",
+    "settings.doctor.issueCount": "发现 {count} 个问题",
+    "settings.doctor.fixAll": "全部修复",
+    "settings.doctor.kind.broken_link": "失效链接",
+    "settings.doctor.kind.link_outside_store": "链接指向共享目录之外",
+    "settings.doctor.kind.missing_locked_folder": "锁文件中的技能缺失",
+    "settings.doctor.kind.unlocked_skill": "技能未记录在锁文件中",
+    "settings.doctor.kind.unlinked_skill": "技能未链接到任何 Agent",
+    "settings.doctor.kind.duplicate_name": "同名技能内容不一致",
+    "settings.doctor.kind.unreadable_skill_md": "SKILL.md 无法读取",
+    "settings.doctor.fix.remove_link": "删除链接",
+    "settings.doctor.fix.relink_to_store": "重新链接",
+    "settings.doctor.fix.adopt_into_store": "移入共享目录",
+    "settings.doctor.fix.remove_lock_entry": "从锁文件移除",
+    "settings.doctor.fix.lock_as_native": "加入锁文件",
+    "settings.doctor.fix.link_to_agents": "链接到 Agent",
+    "settings.doctor.fix.replace_with_store_copy": "使用共享副本",
+    "settings.doctor.fix.quarantine_folder": "移到隔离区",
     "settings.translation.title": "翻译设置",
     "settings.translation.apiKey": "OpenRouter API Key",
     "settings.translation.model": "模型",
@@ -258,6 +423,7 @@ const translations: Record<string, Dictionary> = {
     "addSkill.zip.clickToChange": "点击更换文件",
     "addSkill.zip.detect": "检测",
     "addSkill.zip.selectSkill": "选择要安装的 skill：",
+    "addSkill.zip.restorePackOrigin": "恢复技能包中记录的原始来源和 Agent 关联",
     "addSkill.zip.dropReadError": "无法读取拖入的压缩文件，请改用点击选择。",
     "addSkill.zip.invalidCompressedFile": "请选择 .zip 或 .skill 格式的压缩文件。",
     "addSkill.folder.description": "选择包含 skill 的文件夹。",
@@ -279,6 +445,11 @@ const translations: Record<string, Dictionary> = {
     "addSkill.noSkillSelected": "请至少选择一个 skill。",
     "addSkill.duplicateNames": "选中的 skill 存在重名：{names}。请先取消重复项再安装。",
     "addSkill.selectAll": "全选",
+    "addSkill.lintErrors": "{count} 个错误",
+    "addSkill.lintWarnings": "{count} 个警告",
+    "addSkill.packOrigin": "来自技能包 · {source}",
+    "addSkill.packOriginLocal": "本地",
+    "addSkill.packOriginModified": "导出后已被修改，将作为本地技能安装",
     "addSkill.cancel": "取消",
     "addSkill.installing": "安装中...",
     "addSkill.confirm": "安装",
@@ -326,6 +497,23 @@ const translations: Record<string, Dictionary> = {
     "agentApps.remove": "移除",
     "agentApps.removeConfirm": "确认移除此 Agent 应用？",
     "agentApps.edit": "编辑",
+    "agentApps.registry.bundled": "内置列表：第 {revision} 版，共 {count} 个 Agent",
+    "agentApps.registry.refreshed": "内置列表：第 {revision} 版（已更新），共 {count} 个 Agent",
+    "agentApps.registry.refresh": "检查新的 Agent",
+    "agentApps.detectedBy.binary": "命令行",
+    "agentApps.detectedBy.config_file": "配置文件",
+    "agentApps.detectedBy.skills_folder": "技能目录",
+    "agentApps.detectedBy.agent_folder": "应用目录",
+    "agentApps.share.export": "导出",
+    "agentApps.share.exported": "已导出 {count} 个 Agent 应用到 {path}",
+    "agentApps.share.import": "导入",
+    "agentApps.share.importFile": "从文件导入",
+    "agentApps.share.urlPlaceholder": "或粘贴 Agent 应用文件的 URL",
+    "agentApps.share.importUrl": "导入 URL",
+    "agentApps.share.status.added": "已添加",
+    "agentApps.share.status.updated": "已更新",
+    "agentApps.share.status.unchanged": "无变化",
+    "agentApps.share.status.skipped": "已跳过",
     "projectManage.title": "项目管理",
     "projectManage.loading": "正在加载项目...",
     "projectManage.empty": "暂无项目",
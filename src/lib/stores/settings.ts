@@ -108,6 +108,11 @@ export const loadSettings = async () => {
       openrouter_api_key: remote.openrouter_api_key ?? null,
       translate_target_language: remote.translate_target_language ?? "",
       translate_model: remote.translate_model ?? "",
+      backup_mode: remote.backup_mode ?? null,
+      backup_retention: remote.backup_retention ?? null,
+      backup_schedule: remote.backup_schedule ?? null,
+      update_check_hours: remote.update_check_hours ?? null,
+      source_hosts: remote.source_hosts ?? [],
     };
     settings.set(merged);
     applyTheme(merged.theme);
@@ -131,6 +136,11 @@ export const updateSettings = async (
       | "openrouter_api_key"
       | "translate_target_language"
       | "translate_model"
+      | "backup_mode"
+      | "backup_retention"
+      | "backup_schedule"
+      | "update_check_hours"
+      | "source_hosts"
     >
   >
 ) => {
@@ -5,10 +5,12 @@ import {
   fetchRemoteSkills,
   fetchSkillsByNames,
   listSkills,
+  setTraySkillUpdates,
   type InstallScope,
   type AgentInfo,
   type LocalSkill,
   type RemoteSkill,
+  type SkillUpdateCheckItem,
 } from "../api/skills";
 
 export const agents = writable<AgentInfo[]>([]);
@@ -104,6 +106,7 @@ export async function checkForSkillUpdates(): Promise<void> {
   const local = get(localSkills);
   if (local.length === 0) {
     skillsWithUpdate.set([]);
+    setTraySkillUpdates([]).catch(console.error);
     return;
   }
 
@@ -120,6 +123,7 @@ export async function checkForSkillUpdates(): Promise<void> {
           name: skill.name,
           source: skill.source,
           remote_sha: skill.skill_path_sha!,
+          branch: skill.branch,
         }));
       const updatedNames = new Set(await checkSkillsUpdates(checks));
       const updates = skillNames
@@ -127,6 +131,7 @@ export async function checkForSkillUpdates(): Promise<void> {
         .map((name) => remoteByName.get(name))
         .filter((skill): skill is RemoteSkill => Boolean(skill));
       skillsWithUpdate.set(updates);
+      setTraySkillUpdates(updates).catch(console.error);
     } catch (error) {
       console.error("Failed to check for skill updates:", error);
     } finally {
@@ -153,9 +158,10 @@ export async function checkUpdatesFromRemoteList(): Promise<void> {
         name: localSkill.name,
         source: remoteSkill.source,
         remote_sha: remoteSkill.skill_path_sha,
+        branch: remoteSkill.branch,
       };
     })
-    .filter((item): item is { name: string; source: string; remote_sha: string } => item !== null);
+    .filter((item): item is SkillUpdateCheckItem => item !== null);
   if (checks.length === 0) return;
 
   const updatedNames = new Set(await checkSkillsUpdates(checks));